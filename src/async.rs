@@ -0,0 +1,159 @@
+//! The async API, using `mysql_async` to query the database.
+//!
+//! Mirrors the blocking API of the crate root, so both `query_sync` and `query_async` can be enabled in the same build.
+//! The SQL generation and row mapping are shared, only the execution of statements differs.
+//!
+//! Queries are wrapper types dereferencing to the result types of the crate root, e.g. [`WpQuery`] to [`crate::WpQuery`].
+//! Database methods of the other types are provided by extension traits, e.g. [`WpPostExt`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use wp_query_rs::{r#async::WpQuery, ParamBuilder, PostQueryable, PostType};
+//!
+//! let params = ParamBuilder::new().post_type(PostType::Post);
+//!
+//! let wp_q = WpQuery::new(params).await?;
+//!
+//! for post in wp_q.posts.iter() {
+//!     println!("{}", post.post_title);
+//! }
+//! ```
+//!
+//! When `query_sync` is enabled as well, inherent blocking methods of the same name take precedence
+//! over the methods of the extension traits. Call the async methods through the trait in that case,
+//! e.g. `WpPostExt::update(&mut post).await` or `Insertable::insert(post).await`.
+
+use std::ops::Deref;
+
+use crate::{
+    post_ids, query_builder::QueryAndValues, sql::r#async::get_conn, wp_post, MetaCache, Params,
+    PostsQuery, WpError,
+};
+
+pub use crate::sql::pool::r#async::PoolInit;
+pub use crate::sql::traits::r#async::{Executor, Insertable};
+pub use crate::wp_comment::r#async::*;
+pub use crate::wp_options::r#async::*;
+pub use crate::wp_post::r#async::*;
+#[cfg(feature = "rewrite")]
+pub use crate::wp_rewrite::r#async::*;
+pub use crate::wp_term::r#async::*;
+pub use crate::wp_user::r#async::*;
+
+/// Queries posts like [`crate::WpQuery`], dereferencing to its results.
+#[derive(Debug)]
+pub struct WpQuery(crate::WpQuery);
+
+impl WpQuery {
+    /// Queries the WordPress Database for posts.
+    ///
+    /// Uses environment variables to create a connection pool that is shared through the life of the application ('static).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use wp_query_rs::{r#async::WpQuery, ParamBuilder, PostType, PostQueryable};
+    ///
+    /// let params = ParamBuilder::new().page(1).post_type(PostType::Post);
+    ///
+    /// let wp_q = WpQuery::new(params).await;
+    /// ```
+    ///
+    /// # Errors
+    /// Will return an error if there is an error in the mysql query. This may be from innapropriate SQL built in the query builder,
+    /// or more likely a connection issue from incorrect environment variables.
+    pub async fn new<'a, T>(params: T) -> Result<Self, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let mut conn = get_conn().await?;
+
+        Self::query(&mut conn, params).await
+    }
+
+    /// Queries the WordPress database with a mysql connection, or any other [`Executor`].
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn with_connection<'a, T>(
+        conn: &mut impl Executor,
+        params: T,
+    ) -> Result<Self, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        Self::query(conn, params).await
+    }
+
+    /// Runs `EXPLAIN` for the posts query on a mysql connection, returning the rows of the query plan.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn explain<'a, T>(
+        conn: &mut impl Executor,
+        params: T,
+    ) -> Result<Vec<mysql_common::Row>, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let (q, values) = crate::WpQuery::to_sql(params)?;
+
+        conn.fetch(&format!("EXPLAIN {q}"), values).await
+    }
+
+    /// Consumes the wrapper returning the results of the query.
+    pub fn into_inner(self) -> crate::WpQuery {
+        self.0
+    }
+
+    async fn query<'a, T>(conn: &mut impl Executor, params: T) -> Result<Self, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let plan = PostsQuery::new(params.into())?;
+
+        let QueryAndValues(q, values) = plan.posts;
+        let mut posts: Vec<wp_post::WpPost> = conn.fetch(&q, values).await?;
+
+        let found_posts = match plan.count {
+            // No need to count if there are no matches
+            Some(QueryAndValues(q, values)) if !posts.is_empty() => {
+                conn.fetch_first(&q, values).await?.unwrap_or(0)
+            }
+            _ => 0,
+        };
+
+        if plan.include_sticky {
+            wp_post::sticky::r#async::stick_posts(conn, &mut posts, plan.table_prefix).await?;
+        }
+
+        let meta_cache = if plan.update_meta_cache {
+            wp_post::meta::r#async::load_meta_cache(conn, &post_ids(&posts), plan.table_prefix)
+                .await?
+        } else {
+            MetaCache::new()
+        };
+
+        Ok(Self(crate::WpQuery::from_results(
+            posts,
+            found_posts,
+            plan.posts_per_page,
+            meta_cache,
+        )))
+    }
+}
+
+impl Deref for WpQuery {
+    type Target = crate::WpQuery;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<WpQuery> for crate::WpQuery {
+    fn from(query: WpQuery) -> Self {
+        query.0
+    }
+}
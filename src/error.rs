@@ -0,0 +1,156 @@
+use std::fmt::Display;
+
+/// Error returned by all fallible functions of the crate, the same type in sync and async mode.
+///
+/// # Example
+///
+/// ```rust
+/// use wp_query_rs::{ParamBuilder, WpError};
+///
+/// let err = ParamBuilder::new().try_monthnum(13).err().unwrap();
+///
+/// assert!(matches!(err, WpError::InvalidParams(_)));
+/// ```
+#[derive(Debug)]
+pub enum WpError {
+    /// An error of the database driver, e.g. a failed connection or SQL statement.
+    /// The source is a `mysql::Error`, `mysql_async::Error` or `sqlx::Error`, depending on the enabled feature.
+    Database(Box<dyn std::error::Error + Send + Sync>),
+    /// Query params or arguments that are out of range or conflict with each other.
+    InvalidParams(String),
+    /// The requested row does not exist.
+    NotFound(String),
+    /// A value read from the database could not be decoded, e.g. a malformed PHP serialized string.
+    Decode(String),
+    /// A permalink structure or request URL could not be parsed.
+    Rewrite(String),
+}
+
+impl Display for WpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Database(err) => write!(f, "Database error: {err}"),
+            Self::InvalidParams(msg) => write!(f, "Invalid params: {msg}"),
+            Self::NotFound(msg) => write!(f, "Not found: {msg}"),
+            Self::Decode(msg) => write!(f, "Decode error: {msg}"),
+            Self::Rewrite(msg) => write!(f, "Rewrite error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for WpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Database(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "query_sync")]
+impl From<mysql::Error> for WpError {
+    fn from(err: mysql::Error) -> Self {
+        match err {
+            mysql::Error::FromValueError(_) | mysql::Error::FromRowError(_) => {
+                Self::Decode(err.to_string())
+            }
+            err => Self::Database(Box::new(err)),
+        }
+    }
+}
+
+#[cfg(feature = "query_async")]
+impl From<mysql_async::Error> for WpError {
+    fn from(err: mysql_async::Error) -> Self {
+        use mysql_async::{DriverError, Error};
+
+        match err {
+            Error::Driver(DriverError::FromValue { .. } | DriverError::FromRow { .. }) => {
+                Self::Decode(err.to_string())
+            }
+            err => Self::Database(Box::new(err)),
+        }
+    }
+}
+
+impl From<serde_php::Error> for WpError {
+    fn from(err: serde_php::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+impl From<bcrypt::BcryptError> for WpError {
+    fn from(err: bcrypt::BcryptError) -> Self {
+        Self::InvalidParams(err.to_string())
+    }
+}
+
+impl From<mysql_common::FromValueError> for WpError {
+    fn from(err: mysql_common::FromValueError) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+impl From<mysql_common::FromRowError> for WpError {
+    fn from(err: mysql_common::FromRowError) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+#[cfg(feature = "query_sqlx")]
+impl From<sqlx::Error> for WpError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => Self::NotFound(err.to_string()),
+            sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => {
+                Self::Decode(err.to_string())
+            }
+            err => Self::Database(Box::new(err)),
+        }
+    }
+}
+
+#[cfg(feature = "rewrite")]
+impl From<crate::wp_rewrite::PermalinkStructureParseError> for WpError {
+    fn from(err: crate::wp_rewrite::PermalinkStructureParseError) -> Self {
+        Self::Rewrite(err.to_string())
+    }
+}
+
+#[cfg(feature = "rewrite")]
+impl From<url::ParseError> for WpError {
+    fn from(err: url::ParseError) -> Self {
+        Self::Rewrite(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_errors_keep_their_meaning() {
+        let err: WpError = serde_php::Error::UnexpectedEof.into();
+        assert!(matches!(err, WpError::Decode(_)));
+
+        let err = WpError::NotFound(String::from("gone"));
+        assert_eq!(err.to_string(), "Not found: gone");
+    }
+
+    #[cfg(feature = "query_async")]
+    #[test]
+    fn async_conversion_errors_are_decode_errors() {
+        use std::error::Error;
+
+        let err: WpError = mysql_async::Error::from(mysql_async::DriverError::FromValue {
+            value: mysql_async::Value::NULL,
+        })
+        .into();
+        assert!(matches!(err, WpError::Decode(_)));
+
+        let err: WpError =
+            mysql_async::Error::from(mysql_async::DriverError::ConnectionClosed).into();
+        assert!(matches!(err, WpError::Database(_)));
+        assert!(err.source().is_some());
+    }
+}
@@ -11,6 +11,7 @@
 //! - WORDPRESS_DB_PASSWORD
 //! - WORDPRESS_DB_NAME
 //! - WORDPRESS_DB_PORT
+//! - WORDPRESS_TABLE_PREFIX (optional, defaults to `wp_`)
 //!
 //! ## Implicitly Initializing Global Pool
 //!
@@ -32,8 +33,13 @@
 //!
 //! ## query_async
 //!
-//! **TODO**
-//! Use asynchronous mysql logic to query database.
+//! Use asynchronous mysql logic to query database. The async API lives in the `r#async` module and mirrors
+//! the blocking API, so this feature can be enabled together with query_sync.
+//!
+//! ## query_sqlx
+//!
+//! Implements the async `Executor` for `sqlx::MySqlConnection`, so queries can run on an existing sqlx connection.
+//! Enables query_async.
 //!
 //! ## native-tls
 //!
@@ -52,46 +58,106 @@
 //! ## rewrite
 //!
 //! **WIP**
-//! Adds ability to parse pretty URLs from wordpress rewrite settings. Currently we can only parse urls into parameters,
-//! not generate PHP serialized rewrite rules to be used in WordPress.
+//! Adds ability to parse pretty URLs from wordpress rewrite settings, and to generate permalinks of posts, terms and authors.
+//! Generating PHP serialized rewrite rules to be used in WordPress is not supported.
 
+pub use error::WpError;
 pub use params::date_query::DateColumn;
 pub use params::date_query::DateQuery;
 pub use params::date_query::DateQueryAfterBefore;
 pub use params::meta_query::MetaQuery;
+pub use params::meta_query::MetaQueryTree;
 pub use params::meta_query::MetaRelation;
 pub use params::orderby::WpOrderBy;
 pub use params::param_builder::ParamBuilder;
 pub use params::post_type::PostType;
 pub use params::tax_query::TaxField;
 pub use params::tax_query::TaxQuery;
+pub use params::tax_query::TaxQueryTree;
 pub use params::tax_query::TaxRelation;
 pub use params::traits::*;
 pub use params::Params;
-use query_builder::QueryBuilder;
+pub use php_serialize::{is_serialized, maybe_serialize, maybe_unserialize};
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
+use query_builder::QueryAndValues;
+use query_builder::QueryBuilder;
+#[cfg(feature = "query_sync")]
 use sql::get_conn;
+pub use sql::cast_type::CastType;
 pub use sql::SqlOrder;
+pub use sql::SqlCompareOperator;
 pub use sql::SqlSearchOperators;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use sql::SqlConditionOperator;
+#[cfg(feature = "query_sync")]
 pub use sql::pool::PoolInit;
+#[cfg(feature = "query_sync")]
+pub use stream::{KeysetStream, PostStream};
+use std::collections::HashMap;
+pub use wp_comment::arg_builder::WpCommentArgBuilder;
+pub use wp_comment::WpComment;
+pub use wp_comment::WpCommentQuery;
+pub use wp_options::WpOptions;
+use wp_post::meta::MetaCache;
 pub use wp_post::post_status::PostStatus;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use wp_post::PostField;
 use wp_post::WpPost;
+pub use wp_term::arg_builder::WpTermArgBuilder;
+pub use wp_term::WpTerm;
+pub use wp_user::arg_builder::WpUserArgBuilder;
+pub use wp_user::UserField;
 pub use wp_user::WpUser;
+pub use wp_user::{wp_check_password, wp_hash_password};
 
-#[cfg(feature = "query_sync")]
-use mysql::prelude::Queryable;
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
 pub use sql::env_vars::EnvVars;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use sql::fake::FakeExecutor;
+pub use sql::traits::Executor;
+#[cfg(feature = "query_sync")]
 pub use sql::traits::Insertable;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+#[cfg(feature = "query_sync")]
+pub use wp_comment::delete_comment_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_comment::get_comment_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_comment::update_comment_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_options::add_option;
+#[cfg(feature = "query_sync")]
+pub use wp_options::delete_option;
+#[cfg(feature = "query_sync")]
+pub use wp_options::delete_transient;
+#[cfg(feature = "query_sync")]
+pub use wp_options::get_option;
+#[cfg(feature = "query_sync")]
+pub use wp_options::get_transient;
+#[cfg(feature = "query_sync")]
+pub use wp_options::set_transient;
+#[cfg(feature = "query_sync")]
+pub use wp_options::update_option;
+#[cfg(feature = "query_sync")]
 pub use wp_post::add_post_meta;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+#[cfg(feature = "query_sync")]
+pub use wp_post::delete_post_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_post::get_post_custom;
+#[cfg(feature = "query_sync")]
 pub use wp_post::get_post_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_post::update_post_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_term::get_terms;
+#[cfg(feature = "query_sync")]
+pub use wp_term::wp_get_post_terms;
+#[cfg(feature = "query_sync")]
+pub use wp_user::delete_user_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_user::get_user_meta;
+#[cfg(feature = "query_sync")]
+pub use wp_user::update_user_meta;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use wp_user::WpUserQuery;
 
-#[cfg(feature = "query_async")]
-use mysql_async::prelude::*;
 
 // TODO remove on next major version
 #[allow(non_camel_case_types)]
@@ -99,12 +165,20 @@ pub type WP_Post = WpPost;
 #[allow(non_camel_case_types)]
 pub type WP_Query = WpQuery;
 
+#[cfg(feature = "query_async")]
+pub mod r#async;
+mod error;
 mod params;
+mod php_serialize;
 mod query_builder;
 mod sql;
-mod wp_comment;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod stream;
+pub mod wp_comment;
+pub mod wp_options;
 pub mod wp_post;
-mod wp_user;
+pub mod wp_term;
+pub mod wp_user;
 
 #[cfg(feature = "rewrite")]
 pub mod wp_rewrite;
@@ -112,6 +186,9 @@ pub mod wp_rewrite;
 #[derive(Debug)]
 pub struct WpQuery {
     pub posts: Vec<WpPost>,
+    found_posts: usize,
+    max_num_pages: usize,
+    meta_cache: MetaCache,
 }
 
 impl WpQuery {
@@ -133,29 +210,16 @@ impl WpQuery {
     /// Will return an error if there is an error in the mysql query. This may be from innapropriate SQL built in the query builder,
     /// or more likely a connection issue from incorrect environment variables.
     #[cfg(feature = "query_sync")]
-    pub fn new<'a, T>(params: T) -> Result<Self, mysql::Error>
+    pub fn new<'a, T>(params: T) -> Result<Self, WpError>
     where
         T: Into<Params<'a>>,
     {
         let mut conn = get_conn()?;
 
-        let posts: Vec<WpPost> = Self::query(&mut conn, params)?;
-
-        Ok(Self { posts })
+        Self::query(&mut conn, params)
     }
-    #[cfg(feature = "query_async")]
-    pub async fn new<'a, T>(params: T) -> Result<Self, mysql_async::Error>
-    where
-        T: Into<Params<'a>>,
-    {
-        let mut conn = get_conn().await?;
 
-        let posts: Vec<WpPost> = Self::query(&mut conn, params).await?;
-
-        Ok(Self { posts })
-    }
-
-    /// Queries the WordPress database with a mysql connection.
+    /// Queries the WordPress database with a mysql connection, or any other [`Executor`].
     ///
     /// # Example
     ///
@@ -174,62 +238,188 @@ impl WpQuery {
     /// # Errors
     /// When an error occurs in the SQL query.
     #[cfg(feature = "query_sync")]
-    pub fn with_connection<'a, T>(
-        conn: &mut impl Queryable,
-        params: T,
-    ) -> Result<Self, mysql::Error>
+    pub fn with_connection<'a, T>(conn: &mut impl Executor, params: T) -> Result<Self, WpError>
     where
         T: Into<Params<'a>>,
     {
-        let posts: Vec<WpPost> = Self::query(conn, params)?;
+        Self::query(conn, params)
+    }
 
-        Ok(Self { posts })
+    /// Builds the SQL statement and its values for querying the posts, without a database connection.
+    /// The statement uses `?` placeholders for the values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wp_query_rs::{ParamBuilder, WpQuery};
+    ///
+    /// let params = ParamBuilder::new().p(1);
+    ///
+    /// let (q, values) = WpQuery::to_sql(params).unwrap();
+    ///
+    /// assert!(q.contains("wp_posts.ID = ?"));
+    /// assert_eq!(values[0], 1u64.into());
+    /// ```
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid.
+    pub fn to_sql<'a, T>(params: T) -> Result<(String, Vec<mysql_common::Value>), WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let (query_builder::QueryAndValues(q, values), _) =
+            QueryBuilder::new(params.into()).query()?;
+
+        Ok((q, values))
     }
-    #[cfg(feature = "query_async")]
-    pub async fn with_connection<'a, T>(
-        conn: &mut mysql_async::Conn,
-        params: T,
-    ) -> Result<Self, mysql_async::Error>
+
+    /// Builds the SQL statement for querying the posts with the values interpolated as escaped literals.
+    ///
+    /// Only meant for logging and debugging. Use [`WpQuery::to_sql`] to execute the statement with placeholders.
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid.
+    pub fn to_sql_literal<'a, T>(params: T) -> Result<String, WpError>
     where
         T: Into<Params<'a>>,
     {
-        let posts = Self::query(conn, params).await?;
+        let (q, values) = Self::to_sql(params)?;
 
-        Ok(Self { posts })
+        Ok(query_builder::interpolate(&q, &values))
     }
 
+    /// Runs `EXPLAIN` for the posts query on a mysql connection, returning the rows of the query plan.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut conn = my_pool.get_conn().unwrap();
+    ///
+    /// let plan = WP_Query::explain(&mut conn, ParamBuilder::new().post_type(PostType::Post))?;
+    /// ```
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
     #[cfg(feature = "query_sync")]
-    fn query<'a, T>(conn: &mut impl Queryable, params: T) -> Result<Vec<WpPost>, mysql::Error>
+    pub fn explain<'a, T>(
+        conn: &mut impl Executor,
+        params: T,
+    ) -> Result<Vec<mysql_common::Row>, WpError>
     where
         T: Into<Params<'a>>,
     {
-        let query_builder::QueryAndValues(q, values) = QueryBuilder::new(params.into()).query();
-
-        let stmt = conn.prep(q)?;
+        let (q, values) = Self::to_sql(params)?;
 
-        conn.exec(stmt, values)
+        conn.fetch(&format!("EXPLAIN {q}"), values)
     }
-    #[cfg(feature = "query_async")]
-    async fn query<'a, T>(
-        conn: &mut mysql_async::Conn,
-        params: T,
-    ) -> Result<Vec<WpPost>, mysql_async::Error>
+
+    #[cfg(feature = "query_sync")]
+    fn query<'a, T>(conn: &mut impl Executor, params: T) -> Result<Self, WpError>
     where
         T: Into<Params<'a>>,
     {
-        let query_builder::QueryAndValues(q, values) = QueryBuilder::new(params.into()).query();
+        let plan = PostsQuery::new(params.into())?;
+
+        let QueryAndValues(q, values) = plan.posts;
+        let mut posts: Vec<WpPost> = conn.fetch(&q, values)?;
+
+        let found_posts = match plan.count {
+            // No need to count if there are no matches
+            Some(QueryAndValues(q, values)) if !posts.is_empty() => {
+                conn.fetch_first(&q, values)?.unwrap_or(0)
+            }
+            _ => 0,
+        };
+
+        if plan.include_sticky {
+            wp_post::sticky::stick_posts(conn, &mut posts, plan.table_prefix)?;
+        }
 
-        let stmt = conn.prep(q).await?;
+        let meta_cache = if plan.update_meta_cache {
+            wp_post::meta::load_meta_cache(conn, &post_ids(&posts), plan.table_prefix)?
+        } else {
+            MetaCache::new()
+        };
 
-        conn.exec(stmt, values).await
+        Ok(Self::from_results(
+            posts,
+            found_posts,
+            plan.posts_per_page,
+            meta_cache,
+        ))
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn from_results(
+        posts: Vec<WpPost>,
+        found_posts: usize,
+        posts_per_page: u64,
+        meta_cache: MetaCache,
+    ) -> Self {
+        let mut query = Self::with_found_posts(posts, found_posts, posts_per_page);
+        query.meta_cache = meta_cache;
+
+        query
+    }
+
+    fn with_found_posts(posts: Vec<WpPost>, found_posts: usize, posts_per_page: u64) -> Self {
+        let max_num_pages = if posts_per_page > 0 {
+            found_posts.div_ceil(posts_per_page as usize)
+        } else {
+            0
+        };
+
+        Self {
+            posts,
+            found_posts,
+            max_num_pages,
+            meta_cache: MetaCache::new(),
+        }
     }
 
     pub fn post_count(&self) -> usize {
         self.posts.len()
     }
 
-    fn _max_num_pages(&self) -> usize {
-        0
+    /// The total number of posts found matching the current query parameters, ignoring pagination.
+    /// Always 0 if `no_found_rows` was set.
+    pub fn found_posts(&self) -> usize {
+        self.found_posts
+    }
+
+    /// The total number of pages. Is the result of found_posts / posts_per_page, rounded up.
+    pub fn max_num_pages(&self) -> usize {
+        self.max_num_pages
+    }
+
+    /// The first value of a post's meta field, read from the meta loaded with the query.
+    /// Always None if `update_post_meta_cache` was disabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let wp_q = WP_Query::new(ParamBuilder::new()).expect("SqlFailed");
+    ///
+    /// for post in wp_q.posts.iter() {
+    ///     let price = wp_q.meta(post.ID, "price");
+    /// }
+    /// ```
+    pub fn meta(&self, post_id: u64, meta_key: &str) -> Option<&str> {
+        self.meta_values(post_id, meta_key)
+            .first()
+            .map(String::as_str)
+    }
+
+    /// All values of a post's meta field, read from the meta loaded with the query.
+    pub fn meta_values(&self, post_id: u64, meta_key: &str) -> &[String] {
+        self.post_custom(post_id)
+            .and_then(|meta| meta.get(meta_key))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// All meta fields of a post grouped by key, read from the meta loaded with the query.
+    pub fn post_custom(&self, post_id: u64) -> Option<&HashMap<String, Vec<String>>> {
+        self.meta_cache.get(&post_id)
     }
 
     pub fn to_vec(self) -> Vec<WpPost> {
@@ -237,11 +427,84 @@ impl WpQuery {
     }
 }
 
+/// The statements of a posts query and the options for processing its results,
+/// shared by the blocking and the async API.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+struct PostsQuery<'a> {
+    posts: QueryAndValues,
+    count: Option<QueryAndValues>,
+    posts_per_page: u64,
+    include_sticky: bool,
+    update_meta_cache: bool,
+    table_prefix: Option<&'a str>,
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl<'a> PostsQuery<'a> {
+    fn new(params: Params<'a>) -> Result<Self, WpError> {
+        let posts_per_page = params.posts_per_page.unwrap_or(10);
+        let include_sticky = params.includes_sticky_posts();
+        let update_meta_cache = params.update_post_meta_cache.unwrap_or(true);
+        let table_prefix = params.table_prefix;
+
+        let (posts, count) = QueryBuilder::new(params).query()?;
+
+        Ok(Self {
+            posts,
+            count,
+            posts_per_page,
+            include_sticky,
+            update_meta_cache,
+            table_prefix,
+        })
+    }
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn post_ids(posts: &[WpPost]) -> Vec<u64> {
+    posts.iter().map(|post| post.ID).collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn it_works() {
         //let result = add(2, 2);
         // assert_eq!(result, 4);
     }
+
+    #[test]
+    fn builds_sql_without_connection() {
+        let params = ParamBuilder::new().p(1).post_type(PostType::Post);
+
+        let (q, values) = WpQuery::to_sql(params).unwrap();
+
+        assert!(q.starts_with("SELECT"));
+        assert_eq!(q.matches('?').count(), values.len());
+    }
+
+    #[test]
+    fn interpolates_literal_sql() {
+        let params = ParamBuilder::new().name("it's");
+
+        let q = WpQuery::to_sql_literal(params).unwrap();
+
+        assert!(q.contains("'it\\'s'"));
+        assert!(!q.contains('?'));
+    }
+
+    #[test]
+    fn can_calculate_max_num_pages() {
+        let q = WpQuery::with_found_posts(vec![WpPost::new(1)], 21, 10);
+        assert_eq!(q.found_posts(), 21);
+        assert_eq!(q.max_num_pages(), 3);
+
+        let q = WpQuery::with_found_posts(vec![WpPost::new(1)], 20, 10);
+        assert_eq!(q.max_num_pages(), 2);
+
+        let q = WpQuery::with_found_posts(vec![], 0, 10);
+        assert_eq!(q.max_num_pages(), 0);
+    }
 }
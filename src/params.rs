@@ -9,16 +9,14 @@ pub mod traits;
 #[cfg(feature = "php")]
 mod zval;
 
-use std::collections::HashMap;
-
 use crate::sql::{SqlCompareOperator, SqlOrder, SqlSearchOperators};
 use crate::wp_post::post_status::PostStatus;
 
 use self::date_query::DateQuery;
-use self::meta_query::{MetaQuery, MetaRelation};
-use self::orderby::WpOrderBy;
+use self::meta_query::MetaQueryTree;
+use self::orderby::OrderByList;
 use self::post_type::PostType;
-use self::tax_query::{TaxQuery, TaxRelation};
+use self::tax_query::TaxQueryTree;
 
 /// Configuration for running a WordPress database query.
 #[allow(non_snake_case)]
@@ -34,9 +32,9 @@ pub struct Params<'a> {
     pub term_slug_and: Option<Vec<&'a str>>,
     pub term_slug_in: Option<Vec<&'a str>>,
     /**
-     * Key is The logical relationship between each inner taxonomy array when there is more than one. Possible values are ‘AND’, ‘OR’. Do not use with a single inner taxonomy array
+     * A single taxonomy clause, or nested groups of clauses each with their logical relationship, ‘AND’ or ‘OR’.
      */
-    pub tax_query: Option<HashMap<TaxRelation, Vec<TaxQuery<'a>>>>,
+    pub tax_query: Option<TaxQueryTree<'a>>,
     pub s: Option<&'a str>,
     pub p: Option<u64>,
     pub name: Option<&'a str>,
@@ -60,9 +58,17 @@ pub struct Params<'a> {
     pub comment_count: Option<u64>,
     pub posts_per_page: Option<u64>,
     pub page: Option<u64>,
+    /**
+     * Skips counting the total matches for pagination, i.e. found_posts and max_num_pages.
+     */
+    pub no_found_rows: Option<bool>,
+    /**
+     * Loads the meta of all queried posts with a single query, i.e. update_post_meta_cache. Defaults to true.
+     */
+    pub update_post_meta_cache: Option<bool>,
     pub ignore_sticky_posts: Option<bool>,
     pub order: Option<SqlOrder>,
-    pub orderby: Option<WpOrderBy>,
+    pub orderby: Option<OrderByList>,
     pub year: Option<u16>,
     pub monthnum: Option<u8>,
     /**
@@ -83,15 +89,56 @@ pub struct Params<'a> {
     pub meta_value_num: Option<i64>,
     pub meta_compare: Option<SqlSearchOperators>,
     /**
-     * HashMap key is relation, value is the meta query
-     * relation: The logical relationship between each inner meta_query array when there is more than one.
-     * Possible values are ‘AND’, ‘OR’. Do not use with a single inner meta_query array
+     * A single custom field clause, or nested groups of clauses each with their logical relationship, ‘AND’ or ‘OR’.
      */
-    pub meta_query: Option<HashMap<MetaRelation, Vec<MetaQuery>>>,
+    pub meta_query: Option<MetaQueryTree>,
     pub post_mime_type: Option<&'a str>,
+    /**
+     * Overrides the global table prefix for this query only, i.e. `$table_prefix` in wp-config.php.
+     * Writes always use the global prefix.
+     */
+    pub table_prefix: Option<&'a str>,
 }
 
 impl<'a> Params<'a> {
+    /// Whether sticky posts are moved to the front of the results, like the first page of the blog home in WordPress.
+    /// Set `ignore_sticky_posts` to opt out.
+    pub fn includes_sticky_posts(&self) -> bool {
+        !self.ignore_sticky_posts.unwrap_or(false) && self.page.unwrap_or(0) == 0 && self.is_home()
+    }
+
+    /// Whether the params query the blog home, i.e. not a single post, page, search or archive.
+    fn is_home(&self) -> bool {
+        let singular = self.p.is_some()
+            || self.name.is_some()
+            || self.page_id.is_some()
+            || self.pagename.is_some();
+        let author = self.author.is_some()
+            || self.author_name.is_some()
+            || self.author__in.is_some()
+            || self.author__not_in.is_some();
+        let taxonomy = self.term_and.is_some()
+            || self.term_in.is_some()
+            || self.term_not_in.is_some()
+            || self.term_slug_and.is_some()
+            || self.term_slug_in.is_some()
+            || self.tax_query.is_some();
+        let date = self.year.is_some()
+            || self.monthnum.is_some()
+            || self.w.is_some()
+            || self.day.is_some()
+            || self.hour.is_some()
+            || self.minute.is_some()
+            || self.second.is_some()
+            || self.m.is_some();
+        let post_type_archive = self
+            .post_type
+            .as_ref()
+            .is_some_and(|post_types| post_types.as_slice() != [PostType::Post]);
+
+        !(singular || author || taxonomy || date || post_type_archive || self.s.is_some())
+    }
+
     pub fn new() -> Self {
         Self {
             author: None,
@@ -122,6 +169,8 @@ impl<'a> Params<'a> {
             comment_count: None,
             posts_per_page: None,
             page: None,
+            no_found_rows: None,
+            update_post_meta_cache: None,
             ignore_sticky_posts: None,
             order: None,
             orderby: None,
@@ -140,10 +189,18 @@ impl<'a> Params<'a> {
             meta_compare: None,
             meta_query: None,
             post_mime_type: None,
+            table_prefix: None,
         }
     }
 }
 
+impl<'a> Default for Params<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct CommentCount {
     /**
@@ -154,4 +211,45 @@ pub struct CommentCount {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn home_includes_sticky_posts() {
+        let mut params = Params::new();
+        assert!(params.includes_sticky_posts());
+
+        params.post_type = Some(vec![PostType::Post]);
+        // Pages are stored 0-based
+        params.page = Some(0);
+        assert!(params.includes_sticky_posts());
+
+        params.ignore_sticky_posts = Some(true);
+        assert!(!params.includes_sticky_posts());
+    }
+
+    #[test]
+    fn archives_and_later_pages_exclude_sticky_posts() {
+        let mut params = Params::new();
+        params.page = Some(1);
+        assert!(!params.includes_sticky_posts());
+
+        let params: Params = crate::ParamBuilder::new().page(2).into();
+        assert!(!params.includes_sticky_posts());
+
+        let params: Params = crate::ParamBuilder::new().page(1).into();
+        assert!(params.includes_sticky_posts());
+
+        let mut params = Params::new();
+        params.s = Some("hello");
+        assert!(!params.includes_sticky_posts());
+
+        let mut params = Params::new();
+        params.post_type = Some(vec![PostType::Page]);
+        assert!(!params.includes_sticky_posts());
+
+        let mut params = Params::new();
+        params.p = Some(1);
+        assert!(!params.includes_sticky_posts());
+    }
+}
@@ -109,6 +109,12 @@ impl DateQuery {
     }
 }
 
+impl Default for DateQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum DateColumn {
     PostDate,
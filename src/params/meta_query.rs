@@ -1,14 +1,18 @@
 use std::fmt::Display;
 
-use crate::sql::SqlSearchOperators;
+use crate::sql::{cast_type::CastType, SqlSearchOperators};
 
 #[derive(Debug)]
 pub struct MetaQuery {
     pub key: String,
     pub value: String,
+    /// Values for operators comparing against a list, i.e. IN, NOT IN, BETWEEN and NOT BETWEEN.
+    pub values: Vec<String>,
     pub compare: SqlSearchOperators,
-    // TODO
-    // type
+    /// Type the meta value is cast to before comparing. Defaults to CHAR, comparing as a string.
+    pub cast: CastType,
+    /// Name of the clause, allowing posts to be ordered by its value with `WpOrderBy::MetaClause`.
+    pub name: Option<String>,
 }
 
 impl MetaQuery {
@@ -19,9 +23,155 @@ impl MetaQuery {
         Self {
             key: key.to_string(),
             value: value.to_string(),
+            values: vec![],
             compare,
+            cast: CastType::Char,
+            name: None,
         }
     }
+
+    /// Creates a query comparing against a list of values, i.e. for IN or BETWEEN.
+    ///
+    /// # Example
+    /// ```
+    /// use wp_query_rs::{CastType, MetaQuery, SqlSearchOperators};
+    ///
+    /// let q = MetaQuery::with_values("price", vec![100, 200], SqlSearchOperators::Between)
+    ///     .cast(CastType::Numeric);
+    /// ```
+    pub fn with_values<T>(key: &str, values: Vec<T>, compare: SqlSearchOperators) -> Self
+    where
+        T: Display,
+    {
+        Self {
+            key: key.to_string(),
+            value: String::new(),
+            values: values.into_iter().map(|v| v.to_string()).collect(),
+            compare,
+            cast: CastType::Char,
+            name: None,
+        }
+    }
+
+    /// Sets the type the meta value is cast to, e.g. NUMERIC to compare numbers.
+    pub fn cast(mut self, cast: CastType) -> Self {
+        self.cast = cast;
+
+        self
+    }
+
+    /// Names the clause so posts can be ordered by its value, like the keys of `meta_query` arrays in WordPress.
+    ///
+    /// # Example
+    /// ```
+    /// use wp_query_rs::{
+    ///     CastType, MetaQuery, MetaQueryable, MetaRelation, ParamBuilder, SqlSearchOperators, WpOrderBy,
+    /// };
+    ///
+    /// let params = ParamBuilder::new()
+    ///     .meta_query(
+    ///         MetaQuery::new("price", 0, SqlSearchOperators::GreaterThan)
+    ///             .cast(CastType::Numeric)
+    ///             .name("price_clause"),
+    ///         MetaRelation::And,
+    ///     )
+    ///     .orderby(WpOrderBy::MetaClause(String::from("price_clause")));
+    /// ```
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+
+        self
+    }
+
+    /// The values to compare against. For list operators given a single value, the value is split on commas
+    /// and spaces like WordPress.
+    pub fn compare_values(&self) -> Vec<String> {
+        if !self.compare.takes_multiple_values() {
+            return vec![self.value.clone()];
+        }
+
+        if !self.values.is_empty() {
+            return self.values.clone();
+        }
+
+        self.value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// A meta query clause, or a group of nested clauses joined by a relation, like the arrays of `meta_query` in WordPress.
+///
+/// # Example
+/// ```
+/// use wp_query_rs::{MetaQuery, MetaQueryTree, MetaRelation, SqlSearchOperators};
+///
+/// // (color = blue AND size = large) OR (color = red AND size = small)
+/// let tree = MetaQueryTree::group(MetaRelation::Or)
+///     .push(
+///         MetaQueryTree::group(MetaRelation::And)
+///             .push(MetaQuery::new("color", "blue", SqlSearchOperators::Equals))
+///             .push(MetaQuery::new("size", "large", SqlSearchOperators::Equals)),
+///     )
+///     .push(
+///         MetaQueryTree::group(MetaRelation::And)
+///             .push(MetaQuery::new("color", "red", SqlSearchOperators::Equals))
+///             .push(MetaQuery::new("size", "small", SqlSearchOperators::Equals)),
+///     );
+/// ```
+#[derive(Debug)]
+pub enum MetaQueryTree {
+    Clause(MetaQuery),
+    Group(MetaRelation, Vec<MetaQueryTree>),
+}
+
+impl MetaQueryTree {
+    /// Creates an empty group of clauses.
+    pub fn group(relation: MetaRelation) -> Self {
+        Self::Group(relation, vec![])
+    }
+
+    /// Adds a clause or nested group. A single clause becomes the first clause of an AND group.
+    pub fn push<T>(self, query: T) -> Self
+    where
+        T: Into<MetaQueryTree>,
+    {
+        match self {
+            Self::Group(relation, mut queries) => {
+                queries.push(query.into());
+
+                Self::Group(relation, queries)
+            }
+            clause => Self::Group(MetaRelation::And, vec![clause, query.into()]),
+        }
+    }
+
+    /// The key of the first clause in the tree, used when ordering by meta value.
+    /// NOT EXISTS clauses are skipped, as the posts they match have no value for the key.
+    pub fn first_key(&self) -> Option<&str> {
+        match self {
+            Self::Clause(query) if query.compare == SqlSearchOperators::NotExists => None,
+            Self::Clause(query) => Some(query.key.as_str()),
+            Self::Group(_, queries) => queries.iter().find_map(Self::first_key),
+        }
+    }
+
+    /// Finds a clause by its name.
+    pub fn find_clause(&self, name: &str) -> Option<&MetaQuery> {
+        match self {
+            Self::Clause(query) if query.name.as_deref() == Some(name) => Some(query),
+            Self::Clause(_) => None,
+            Self::Group(_, queries) => queries.iter().find_map(|q| q.find_clause(name)),
+        }
+    }
+}
+
+impl From<MetaQuery> for MetaQueryTree {
+    fn from(value: MetaQuery) -> Self {
+        Self::Clause(value)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -65,6 +215,91 @@ mod tests {
         assert_eq!(q.compare, SqlSearchOperators::NotEquals);
     }
 
+    #[test]
+    fn can_create_with_values() {
+        let q = MetaQuery::with_values("price", vec![1, 2], SqlSearchOperators::Between)
+            .cast(CastType::Numeric);
+
+        assert_eq!(
+            q.compare_values(),
+            vec![String::from("1"), String::from("2")]
+        );
+        assert_eq!(q.cast, CastType::Numeric);
+    }
+
+    #[test]
+    fn splits_single_value_for_list_operators() {
+        let q = MetaQuery::new("color", "red, blue,green", SqlSearchOperators::In);
+        assert_eq!(q.compare_values(), vec!["red", "blue", "green"]);
+
+        let q = MetaQuery::new("color", "red, blue", SqlSearchOperators::Equals);
+        assert_eq!(q.compare_values(), vec!["red, blue"]);
+    }
+
+    #[test]
+    fn can_nest_meta_queries() {
+        let tree = MetaQueryTree::group(MetaRelation::Or)
+            .push(MetaQuery::new("a", 1, SqlSearchOperators::Equals))
+            .push(
+                MetaQueryTree::group(MetaRelation::And)
+                    .push(MetaQuery::new("b", 2, SqlSearchOperators::Equals))
+                    .push(MetaQuery::new("c", 3, SqlSearchOperators::Equals)),
+            );
+
+        match tree {
+            MetaQueryTree::Group(MetaRelation::Or, queries) => {
+                assert_eq!(queries.len(), 2);
+                assert!(
+                    matches!(&queries[1], MetaQueryTree::Group(MetaRelation::And, q) if q.len() == 2)
+                );
+            }
+            _ => panic!("Not a group"),
+        }
+    }
+
+    #[test]
+    fn adding_to_clause_creates_group() {
+        let tree = MetaQueryTree::from(MetaQuery::new("a", 1, SqlSearchOperators::Equals))
+            .push(MetaQuery::new("b", 2, SqlSearchOperators::Equals));
+
+        assert!(matches!(tree, MetaQueryTree::Group(MetaRelation::And, q) if q.len() == 2));
+    }
+
+    #[test]
+    fn can_find_first_key() {
+        let tree = MetaQueryTree::group(MetaRelation::And)
+            .push(MetaQueryTree::group(MetaRelation::Or))
+            .push(MetaQuery::new("b", 2, SqlSearchOperators::Equals));
+
+        assert_eq!(tree.first_key(), Some("b"));
+        assert_eq!(MetaQueryTree::group(MetaRelation::Or).first_key(), None);
+    }
+
+    #[test]
+    fn first_key_skips_not_exists() {
+        let tree = MetaQueryTree::group(MetaRelation::And)
+            .push(MetaQuery::new("sale", "", SqlSearchOperators::NotExists))
+            .push(MetaQuery::new("price", 2, SqlSearchOperators::GreaterThan));
+
+        assert_eq!(tree.first_key(), Some("price"));
+    }
+
+    #[test]
+    fn can_find_named_clause() {
+        let tree = MetaQueryTree::group(MetaRelation::And)
+            .push(MetaQuery::new("a", 1, SqlSearchOperators::Equals))
+            .push(
+                MetaQueryTree::group(MetaRelation::Or)
+                    .push(MetaQuery::new("b", 2, SqlSearchOperators::Equals).name("b_clause")),
+            );
+
+        assert_eq!(
+            tree.find_clause("b_clause").map(|q| q.key.as_str()),
+            Some("b")
+        );
+        assert!(tree.find_clause("a_clause").is_none());
+    }
+
     #[test]
     fn can_write_meta_relation_to_string() {
         assert_eq!(MetaRelation::And.to_string(), String::from("AND"));
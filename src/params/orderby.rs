@@ -1,4 +1,10 @@
-#[derive(Debug, PartialEq, Eq)]
+use crate::sql::SqlOrder;
+
+/// Orderby keys paired with their direction, like the array form of `orderby` in WordPress.
+/// Keys without a direction use the `order` param.
+pub type OrderByList = Vec<(WpOrderBy, Option<SqlOrder>)>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum WpOrderBy {
     None,
     ID,
@@ -12,10 +18,23 @@ pub enum WpOrderBy {
     CommentCount,
     MetaValue,
     MetaValueNum,
+    MenuOrder,
+    /// Random order.
+    Rand,
+    /// Keeps the order given in the `post__in` param.
+    PostIn,
+    /// Keeps the order given in the `post_name__in` param.
+    PostNameIn,
+    /// Keeps the order given in the `post_parent__in` param.
+    PostParentIn,
+    /// Orders search results by matches in the title, then by date. Requires the `s` param.
+    Relevance,
+    /// Orders by the value of a named meta query clause, cast to the clause's type.
+    MetaClause(String),
 }
 
-impl ToString for WpOrderBy {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for WpOrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let str = match self {
             Self::None => "",
             Self::ID => "ID",
@@ -29,15 +48,22 @@ impl ToString for WpOrderBy {
             Self::CommentCount => "comment_count",
             Self::MetaValue => "meta_value",
             Self::MetaValueNum => "meta_value",
+            Self::MenuOrder => "menu_order",
+            Self::Rand => "rand",
+            Self::PostIn => "post__in",
+            Self::PostNameIn => "post_name__in",
+            Self::PostParentIn => "post_parent__in",
+            Self::Relevance => "relevance",
+            Self::MetaClause(name) => name.as_str(),
         };
 
-        str.to_string()
+        f.write_str(str)
     }
 }
 
-impl Into<WpOrderBy> for &str {
-    fn into(self) -> WpOrderBy {
-        match self {
+impl From<&str> for WpOrderBy {
+    fn from(value: &str) -> Self {
+        match value {
             "ID" | "id" => WpOrderBy::ID,
             "post_author" | "author" => WpOrderBy::Author,
             "post_title" | "title" => WpOrderBy::Title,
@@ -49,7 +75,15 @@ impl Into<WpOrderBy> for &str {
             "comment_count" => WpOrderBy::CommentCount,
             "meta_value" => WpOrderBy::MetaValue,
             "meta_value_num" => WpOrderBy::MetaValueNum,
-            _ => WpOrderBy::None,
+            "menu_order" => WpOrderBy::MenuOrder,
+            "rand" => WpOrderBy::Rand,
+            "post__in" => WpOrderBy::PostIn,
+            "post_name__in" => WpOrderBy::PostNameIn,
+            "post_parent__in" => WpOrderBy::PostParentIn,
+            "relevance" => WpOrderBy::Relevance,
+            "" | "none" => WpOrderBy::None,
+            // Any other key names a meta query clause
+            name => WpOrderBy::MetaClause(name.to_string()),
         }
     }
 }
@@ -76,4 +110,22 @@ mod tests {
         let order: WpOrderBy = str.into();
         assert_eq!(order, WpOrderBy::Modified);
     }
+
+    #[test]
+    fn can_convert_special_keys_from_str() {
+        let order: WpOrderBy = "rand".into();
+        assert_eq!(order, WpOrderBy::Rand);
+
+        let order: WpOrderBy = "post__in".into();
+        assert_eq!(order, WpOrderBy::PostIn);
+
+        let order: WpOrderBy = "none".into();
+        assert_eq!(order, WpOrderBy::None);
+    }
+
+    #[test]
+    fn unknown_keys_name_meta_clauses() {
+        let order: WpOrderBy = "price_clause".into();
+        assert_eq!(order, WpOrderBy::MetaClause(String::from("price_clause")));
+    }
 }
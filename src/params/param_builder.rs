@@ -1,14 +1,15 @@
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
 use crate::{
-    sql::{SqlOrder, SqlSearchOperators},
+    sql::{table_prefix::validate_table_prefix, SqlOrder, SqlSearchOperators},
     wp_post::post_status::PostStatus,
-    DateQuery, MetaQuery, MetaRelation, Params, PostType,
+    DateQuery, MetaQuery, MetaRelation, Params, PostType, WpError,
 };
 
 use super::{
+    meta_query::MetaQueryTree,
     orderby::WpOrderBy,
-    tax_query::{TaxQuery, TaxRelation},
+    tax_query::{TaxQuery, TaxQueryTree, TaxRelation},
     traits::{MetaQueryable, PostQueryable},
 };
 
@@ -34,6 +35,18 @@ use super::{
 /// let params = ParamBuilder::new()
 ///     .hour(24); // InvalidHour
 /// ```
+///
+/// Use the `try_` variants for values supplied by users, which return a [`WpError::InvalidParams`] instead.
+///
+/// ```
+/// use wp_query_rs::ParamBuilder;
+///
+/// let params = ParamBuilder::new()
+///     .try_year(2023)
+///     .and_then(|params| params.try_monthnum(13));
+///
+/// assert!(params.is_err());
+/// ```
 pub struct ParamBuilder<'a>(Params<'a>);
 
 
@@ -126,17 +139,30 @@ impl<'a> ParamBuilder<'a> {
         add_to_vec!(self, term_slug_in, tag_slug)
     }
 
+    /// Adds a taxonomy clause. With a relation, the clause joins the top level group when it has the same
+    /// relation, otherwise the existing clauses are nested in a new group with that relation,
+    /// i.e. `a OR b` followed by `c` with AND becomes `(a OR b) AND c`.
+    /// Without a relation, replaces the tax query with the single clause.
     pub fn tax_query(mut self, query: TaxQuery<'a>, relation: Option<TaxRelation>) -> Self {
-        let mut tax_q = self.0.tax_query.unwrap_or(HashMap::new());
+        self.0.tax_query = match (self.0.tax_query.take(), relation) {
+            (_, None) => Some(TaxQueryTree::Clause(query)),
+            (Some(TaxQueryTree::Group(current, mut queries)), Some(rel))
+                if current == rel || queries.is_empty() =>
+            {
+                queries.push(query.into());
+
+                Some(TaxQueryTree::Group(rel, queries))
+            }
+            (Some(clause), Some(rel)) => Some(TaxQueryTree::Group(rel, vec![clause, query.into()])),
+            (None, Some(rel)) => Some(TaxQueryTree::Group(rel, vec![query.into()])),
+        };
 
-        if let Some(rel) = relation {
-            let qs_for_relation = tax_q.entry(rel).or_insert(vec![]);
-            qs_for_relation.push(query);
+        self
+    }
 
-            self.0.tax_query = Some(tax_q);
-        } else {
-            self.0.tax_query = Some(TaxQuery::new_single_tax_map(query));
-        }
+    /// Sets a tax query of nested clauses, replacing any previously added clauses.
+    pub fn tax_query_tree(mut self, tree: TaxQueryTree<'a>) -> Self {
+        self.0.tax_query = Some(tree);
 
         self
     }
@@ -162,14 +188,17 @@ impl<'a> ParamBuilder<'a> {
         self
     }
 
+    #[allow(dead_code)]
     fn page_id(self) -> Self {
         self
     }
 
+    #[allow(dead_code)]
     fn pagename(self) -> Self {
         self
     }
 
+    #[allow(dead_code)]
     fn comment_count(mut self, count: u64) -> Self {
         self.0.comment_count = Some(count);
 
@@ -182,14 +211,43 @@ impl<'a> ParamBuilder<'a> {
         self
     }
 
-    /// Starts from page 1
+    /// Starts from page 1. Page 0 is treated as page 1, like in WordPress.
     pub fn page(mut self, n: u64) -> Self {
-        self.0.page = Some(n - 1);
+        self.0.page = Some(n.saturating_sub(1));
+
+        self
+    }
+
+    /// Like [`ParamBuilder::page`], returning an error instead of treating page 0 as the first page.
+    pub fn try_page(self, n: u64) -> Result<Self, WpError> {
+        if n < 1 {
+            return Err(WpError::InvalidParams(String::from(
+                "page must be 1 or greater",
+            )));
+        }
+
+        Ok(self.page(n))
+    }
+
+    /// Skips counting the total number of matching posts. Use when pagination information is not needed.
+    pub fn no_found_rows(mut self, no_found_rows: bool) -> Self {
+        self.0.no_found_rows = Some(no_found_rows);
+
+        self
+    }
+
+    /// Loads the meta of all queried posts after the query, to be read with `WpQuery::meta` without further queries.
+    /// Enabled by default.
+    pub fn update_post_meta_cache(mut self, update_post_meta_cache: bool) -> Self {
+        self.0.update_post_meta_cache = Some(update_post_meta_cache);
 
         self
     }
 
-    fn ignore_sticky_posts(self) -> Self {
+    /// Skips moving sticky posts to the front of the first page.
+    pub fn ignore_sticky_posts(mut self, ignore_sticky_posts: bool) -> Self {
+        self.0.ignore_sticky_posts = Some(ignore_sticky_posts);
+
         self
     }
 
@@ -199,91 +257,162 @@ impl<'a> ParamBuilder<'a> {
         self
     }
 
-    /// Sort retrieved posts by parameter.
+    /// Sort retrieved posts by parameter, replacing any previous orderby keys.
     pub fn orderby(mut self, ob: WpOrderBy) -> Self {
-        self.0.orderby = Some(ob);
+        self.0.orderby = Some(vec![(ob, None)]);
+
+        self
+    }
+
+    /// Adds an orderby key with its own direction, like the array form of `orderby` in WordPress.
+    /// Posts are sorted by each key in the order they were added.
+    ///
+    /// # Example
+    /// ```
+    /// use wp_query_rs::{ParamBuilder, SqlOrder, WpOrderBy};
+    ///
+    /// // orderby => ['menu_order' => 'ASC', 'date' => 'DESC']
+    /// let params = ParamBuilder::new()
+    ///     .add_orderby(WpOrderBy::MenuOrder, SqlOrder::Asc)
+    ///     .add_orderby(WpOrderBy::Date, SqlOrder::Desc);
+    /// ```
+    pub fn add_orderby(mut self, ob: WpOrderBy, order: SqlOrder) -> Self {
+        self.0
+            .orderby
+            .get_or_insert_with(Vec::new)
+            .push((ob, Some(order)));
 
         self
     }
 
     /// 4 digit year (e.g. 2011).
-    pub fn year(mut self, y: u16) -> Self {
+    pub fn year(self, y: u16) -> Self {
+        self.try_year(y).expect("InvalidYear")
+    }
+
+    /// Like [`ParamBuilder::year`], returning an error instead of panicking if the value is out of range.
+    pub fn try_year(mut self, y: u16) -> Result<Self, WpError> {
         if y > 9999 {
-            panic!("InvalidYear");
+            return Err(WpError::InvalidParams(String::from(
+                "year must be at most 9999",
+            )));
         }
 
         self.0.year = Some(y);
 
-        self
+        Ok(self)
     }
 
     /// Month number (from 1 to 12).
-    pub fn monthnum(mut self, m: u8) -> Self {
-        if m > 12 || m < 1 {
-            panic!("InvalidMonth");
+    pub fn monthnum(self, m: u8) -> Self {
+        self.try_monthnum(m).expect("InvalidMonth")
+    }
+
+    /// Like [`ParamBuilder::monthnum`], returning an error instead of panicking if the value is out of range.
+    pub fn try_monthnum(mut self, m: u8) -> Result<Self, WpError> {
+        if !(1..=12).contains(&m) {
+            return Err(WpError::InvalidParams(String::from(
+                "monthnum must be from 1 to 12",
+            )));
         }
 
         self.0.monthnum = Some(m);
 
-        self
+        Ok(self)
     }
 
     ///  Week of the year (from 0 to 53). Uses MySQL WEEK command. The mode is dependent on the “start_of_week” option.
-    pub fn w(mut self, w: u8) -> Self {
+    pub fn w(self, w: u8) -> Self {
+        self.try_w(w).expect("InalidWeekNo")
+    }
+
+    /// Like [`ParamBuilder::w`], returning an error instead of panicking if the value is out of range.
+    pub fn try_w(mut self, w: u8) -> Result<Self, WpError> {
         if w > 53 {
-            panic!("InalidWeekNo");
+            return Err(WpError::InvalidParams(String::from(
+                "w must be from 0 to 53",
+            )));
         }
 
         self.0.w = Some(w);
 
-        self
+        Ok(self)
     }
 
     /// Day of the month (from 1 to 31).
-    pub fn day(mut self, d: u8) -> Self {
-        if d > 31 || d < 1 {
-            panic!("InvalidDay");
+    pub fn day(self, d: u8) -> Self {
+        self.try_day(d).expect("InvalidDay")
+    }
+
+    /// Like [`ParamBuilder::day`], returning an error instead of panicking if the value is out of range.
+    pub fn try_day(mut self, d: u8) -> Result<Self, WpError> {
+        if !(1..=31).contains(&d) {
+            return Err(WpError::InvalidParams(String::from(
+                "day must be from 1 to 31",
+            )));
         }
 
         self.0.day = Some(d);
 
-        self
+        Ok(self)
     }
 
     /// Hour (from 0 to 23).
-    pub fn hour(mut self, h: u8) -> Self {
+    pub fn hour(self, h: u8) -> Self {
+        self.try_hour(h).expect("InvalidHour")
+    }
+
+    /// Like [`ParamBuilder::hour`], returning an error instead of panicking if the value is out of range.
+    pub fn try_hour(mut self, h: u8) -> Result<Self, WpError> {
         if h > 23 {
-            panic!("InvalidHour");
+            return Err(WpError::InvalidParams(String::from(
+                "hour must be from 0 to 23",
+            )));
         }
 
         self.0.hour = Some(h);
 
-        self
+        Ok(self)
     }
 
     /// Minute (from 0 to 60).
-    pub fn minute(mut self, min: u8) -> Self {
+    pub fn minute(self, min: u8) -> Self {
+        self.try_minute(min).expect("InvalidMinutes")
+    }
+
+    /// Like [`ParamBuilder::minute`], returning an error instead of panicking if the value is out of range.
+    pub fn try_minute(mut self, min: u8) -> Result<Self, WpError> {
         if min > 60 {
-            panic!("InvalidMinutes");
+            return Err(WpError::InvalidParams(String::from(
+                "minute must be from 0 to 60",
+            )));
         }
 
         self.0.minute = Some(min);
 
-        self
+        Ok(self)
     }
 
     /// Second (0 to 60).
-    pub fn second(mut self, s: u8) -> Self {
+    pub fn second(self, s: u8) -> Self {
+        self.try_second(s).expect("InvalidSeconds")
+    }
+
+    /// Like [`ParamBuilder::second`], returning an error instead of panicking if the value is out of range.
+    pub fn try_second(mut self, s: u8) -> Result<Self, WpError> {
         if s > 60 {
-            panic!("InvalidSeconds");
+            return Err(WpError::InvalidParams(String::from(
+                "second must be from 0 to 60",
+            )));
         }
 
         self.0.second = Some(s);
 
-        self
+        Ok(self)
     }
 
     /// YearMonth (For e.g.: 201307).
+    #[allow(dead_code)]
     fn m(mut self, m: u64) -> Self {
         if m > 999999 {
             panic!("InvalidYearMonth");
@@ -297,7 +426,7 @@ impl<'a> ParamBuilder<'a> {
     }
 
     pub fn date_query(mut self, query: DateQuery) -> Self {
-        let mut queries = self.0.date_query.unwrap_or(Vec::new());
+        let mut queries = self.0.date_query.unwrap_or_default();
 
         queries.push(query);
 
@@ -306,9 +435,77 @@ impl<'a> ParamBuilder<'a> {
         self
     }
 
+    #[allow(dead_code)]
     fn post_mime_type(self) -> Self {
         self
     }
+
+    /// Table prefix for this query, overrides the global prefix set with PoolInit or WORDPRESS_TABLE_PREFIX.
+    /// Writes always use the global prefix.
+    pub fn table_prefix(self, prefix: &'a str) -> Self {
+        self.try_table_prefix(prefix).expect("InvalidTablePrefix")
+    }
+
+    /// Like [`ParamBuilder::table_prefix`], returning an error instead of panicking if the prefix contains
+    /// characters other than letters, numbers and underscores.
+    pub fn try_table_prefix(mut self, prefix: &'a str) -> Result<Self, WpError> {
+        validate_table_prefix(prefix)?;
+
+        self.0.table_prefix = Some(prefix);
+
+        Ok(self)
+    }
+
+    /// Custom field key. Returns an error instead of panicking if a meta query was already set.
+    pub fn try_meta_key(mut self, key: &'a str) -> Result<Self, WpError> {
+        self.check_no_meta_query()?;
+
+        self.0.meta_key = Some(key);
+
+        Ok(self)
+    }
+
+    /// Custom field value. Returns an error instead of panicking if a meta query was already set.
+    pub fn try_meta_value(mut self, val: impl Display) -> Result<Self, WpError> {
+        self.check_no_meta_query()?;
+
+        if self.0.meta_value_num.is_some() {
+            self.0.meta_value_num = None;
+        }
+
+        self.0.meta_value = Some(val.to_string());
+
+        Ok(self)
+    }
+
+    /// Custom field value (number). Returns an error instead of panicking if a meta query was already set.
+    pub fn try_meta_value_num(mut self, n: i64) -> Result<Self, WpError> {
+        self.check_no_meta_query()?;
+
+        if self.0.meta_value.is_some() {
+            self.0.meta_value = None;
+        }
+
+        self.0.meta_value_num = Some(n);
+
+        Ok(self)
+    }
+
+    /// Single meta key params cannot be mixed with `meta_query`.
+    fn check_no_meta_query(&self) -> Result<(), WpError> {
+        match self.0.meta_query {
+            Some(_) => Err(WpError::InvalidParams(String::from(
+                "cannot add a single meta key query when meta_query is set",
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Default for ParamBuilder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[allow(non_snake_case)]
@@ -365,44 +562,21 @@ impl<'a> PostQueryable<'a> for ParamBuilder<'a> {
 
 impl<'a> MetaQueryable<'a> for ParamBuilder<'a> {
     /// Custom field key.
-    fn meta_key(mut self, key: &'a str) -> Self {
-        if self.0.meta_query.is_some() {
-            panic!("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet");
-        }
-
-        self.0.meta_key = Some(key);
-
-        self
+    fn meta_key(self, key: &'a str) -> Self {
+        self.try_meta_key(key)
+            .expect("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet")
     }
 
     /// Custom field value.
-    fn meta_value(mut self, val: impl Display) -> Self {
-        if self.0.meta_query.is_some() {
-            panic!("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet");
-        }
-
-        if self.0.meta_value_num.is_some() {
-            self.0.meta_value_num = None;
-        }
-
-        self.0.meta_value = Some(val.to_string());
-
-        self
+    fn meta_value(self, val: impl Display) -> Self {
+        self.try_meta_value(val)
+            .expect("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet")
     }
 
     /// Custom field value (number).
-    fn meta_value_num(mut self, n: i64) -> Self {
-        if self.0.meta_query.is_some() {
-            panic!("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet");
-        }
-
-        if self.0.meta_value.is_some() {
-            self.0.meta_value = None;
-        }
-
-        self.0.meta_value_num = Some(n);
-
-        self
+    fn meta_value_num(self, n: i64) -> Self {
+        self.try_meta_value_num(n)
+            .expect("CannotAddSingleMetaKeyQueryWhenMetaQueryIsSet")
     }
 
     /// Operator to test the ‘meta_value‘
@@ -419,19 +593,38 @@ impl<'a> MetaQueryable<'a> for ParamBuilder<'a> {
         self.0.meta_value = None;
         self.0.meta_value_num = None;
 
-        let meta_qs = self.0.meta_query.get_or_insert(HashMap::new());
+        // Clauses with a different relation wrap the existing ones, so `a OR b` then `c` with AND is `(a OR b) AND c`
+        self.0.meta_query = Some(match self.0.meta_query.take() {
+            Some(MetaQueryTree::Group(current, mut queries))
+                if current == relation || queries.is_empty() =>
+            {
+                queries.push(query.into());
+
+                MetaQueryTree::Group(relation, queries)
+            }
+            Some(tree) => MetaQueryTree::Group(relation, vec![tree, query.into()]),
+            None => MetaQueryTree::Group(relation, vec![query.into()]),
+        });
 
-        let queries_for_relation = meta_qs.entry(relation).or_insert(vec![]);
+        self
+    }
+
+    fn meta_query_tree(mut self, tree: MetaQueryTree) -> Self {
+        // Clear single meta
+        self.0.meta_compare = None;
+        self.0.meta_key = None;
+        self.0.meta_value = None;
+        self.0.meta_value_num = None;
 
-        queries_for_relation.push(query);
+        self.0.meta_query = Some(tree);
 
         self
     }
 }
 
-impl<'a> Into<Params<'a>> for ParamBuilder<'a> {
-    fn into(self) -> Params<'a> {
-        self.0
+impl<'a> From<ParamBuilder<'a>> for Params<'a> {
+    fn from(value: ParamBuilder<'a>) -> Self {
+        value.0
     }
 }
 
@@ -549,18 +742,17 @@ mod tests {
         let terms = vec![String::from("1")];
         let tax = TaxQuery::new("custom_tax", terms.clone());
         let q = ParamBuilder::new().tax_query(tax, None);
-        let stored = q.0.tax_query.unwrap();
-        assert!(stored.get(&TaxRelation::Single).is_some());
-        let stored = stored.get(&TaxRelation::Single).unwrap().first().unwrap();
-        assert_eq!(stored.taxonomy, "custom_tax");
-        assert_eq!(stored.terms, terms);
+        match q.0.tax_query.unwrap() {
+            TaxQueryTree::Clause(stored) => {
+                assert_eq!(stored.taxonomy, "custom_tax");
+                assert_eq!(stored.terms, terms);
+            }
+            _ => panic!("Not a single clause"),
+        }
     }
 
     #[test]
     fn can_add_multiple_tax() {
-        let tax_name = String::from("custom_tax");
-        let tax_name_two = String::from("category");
-        let tax_name_three = String::from("cust_2");
         let terms = vec![String::from("1")];
         let tax1 = TaxQuery::new("custom_tax", terms.clone());
         let tax2 = TaxQuery::new("category", terms.clone());
@@ -570,10 +762,55 @@ mod tests {
             .tax_query(tax2, Some(TaxRelation::And))
             .tax_query(tax3, Some(TaxRelation::Or));
 
-        let created = q.0.tax_query.unwrap();
-        assert_eq!(created.len(), 2);
-        assert_eq!(created.get(&TaxRelation::And).unwrap().len(), 2);
-        assert_eq!(created.get(&TaxRelation::Or).unwrap().len(), 1);
+        match q.0.tax_query.unwrap() {
+            TaxQueryTree::Group(relation, queries) => {
+                assert_eq!(relation, TaxRelation::Or);
+                assert_eq!(queries.len(), 2);
+                assert!(matches!(
+                    &queries[0],
+                    TaxQueryTree::Group(TaxRelation::And, q) if q.len() == 2
+                ));
+                assert!(matches!(&queries[1], TaxQueryTree::Clause(_)));
+            }
+            _ => panic!("Not a group"),
+        }
+    }
+
+    #[test]
+    fn tax_query_with_new_relation_nests_set_tree() {
+        let tree = TaxQueryTree::group(TaxRelation::Or)
+            .push(TaxQuery::new("category", vec![1]))
+            .push(TaxQuery::new("post_tag", vec![2]));
+        let q = ParamBuilder::new()
+            .tax_query_tree(tree)
+            .tax_query(TaxQuery::new("cust_2", vec![3]), Some(TaxRelation::Or))
+            .tax_query(TaxQuery::new("cust_3", vec![4]), Some(TaxRelation::And));
+
+        match q.0.tax_query.unwrap() {
+            TaxQueryTree::Group(TaxRelation::And, queries) => {
+                assert_eq!(queries.len(), 2);
+                assert!(matches!(
+                    &queries[0],
+                    TaxQueryTree::Group(TaxRelation::Or, q) if q.len() == 3
+                ));
+            }
+            _ => panic!("Not an AND group"),
+        }
+    }
+
+    #[test]
+    fn can_set_tax_tree() {
+        let tree = TaxQueryTree::group(TaxRelation::Or)
+            .push(TaxQuery::new("category", vec![1]))
+            .push(TaxQuery::new("post_tag", vec![2]));
+        let q = ParamBuilder::new()
+            .tax_query(TaxQuery::new("cust_2", vec![3]), None)
+            .tax_query_tree(tree);
+
+        assert!(matches!(
+            q.0.tax_query.unwrap(),
+            TaxQueryTree::Group(TaxRelation::Or, queries) if queries.len() == 2
+        ));
     }
 
     #[test]
@@ -617,7 +854,6 @@ mod tests {
 
     #[test]
     fn can_add_post_name_in() {
-        let id = 1;
         let q = ParamBuilder::new()
             .post_name__in("malcolm-x")
             .post_name__in("mlk");
@@ -656,15 +892,53 @@ mod tests {
         assert_eq!(q.0.posts_per_page.unwrap(), 20);
     }
 
+    #[test]
+    fn page_zero_is_first_page() {
+        let q = ParamBuilder::new().page(0);
+        assert_eq!(q.0.page.unwrap(), 0);
+
+        assert!(matches!(
+            ParamBuilder::new().try_page(0),
+            Err(WpError::InvalidParams(_))
+        ));
+        assert_eq!(ParamBuilder::new().try_page(2).unwrap().0.page.unwrap(), 1);
+    }
+
+    #[test]
+    fn can_add_no_found_rows() {
+        let q = ParamBuilder::new().no_found_rows(true);
+        assert!(q.0.no_found_rows.unwrap());
+    }
+
+    #[test]
+    fn can_disable_meta_cache() {
+        let q = ParamBuilder::new().update_post_meta_cache(false);
+        assert!(!q.0.update_post_meta_cache.unwrap());
+    }
+
     #[test]
     fn can_add_orderby_params() {
         let q = ParamBuilder::new()
             .orderby(WpOrderBy::Author)
             .order(SqlOrder::Asc);
-        assert_eq!(q.0.orderby.unwrap(), WpOrderBy::Author);
+        assert_eq!(q.0.orderby.unwrap(), vec![(WpOrderBy::Author, None)]);
         assert_eq!(q.0.order.unwrap(), SqlOrder::Asc);
     }
 
+    #[test]
+    fn can_add_multiple_orderby_keys() {
+        let q = ParamBuilder::new()
+            .add_orderby(WpOrderBy::MenuOrder, SqlOrder::Asc)
+            .add_orderby(WpOrderBy::Date, SqlOrder::Desc);
+        assert_eq!(
+            q.0.orderby.unwrap(),
+            vec![
+                (WpOrderBy::MenuOrder, Some(SqlOrder::Asc)),
+                (WpOrderBy::Date, Some(SqlOrder::Desc))
+            ]
+        );
+    }
+
     #[test]
     fn can_add_date_params() {
         let q = ParamBuilder::new()
@@ -685,6 +959,42 @@ mod tests {
         assert_eq!(q.0.second.unwrap(), 60);
     }
 
+    #[test]
+    fn try_date_values_return_errors() {
+        assert!(ParamBuilder::new().try_year(10000).is_err());
+        assert!(ParamBuilder::new().try_monthnum(0).is_err());
+        assert!(ParamBuilder::new().try_w(54).is_err());
+        assert!(ParamBuilder::new().try_day(32).is_err());
+        assert!(ParamBuilder::new().try_hour(24).is_err());
+        assert!(ParamBuilder::new().try_minute(61).is_err());
+        assert!(matches!(
+            ParamBuilder::new().try_second(61),
+            Err(WpError::InvalidParams(_))
+        ));
+
+        let q = ParamBuilder::new()
+            .try_year(2023)
+            .and_then(|q| q.try_day(31))
+            .unwrap();
+        assert_eq!(q.0.year, Some(2023));
+        assert_eq!(q.0.day, Some(31));
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidHour")]
+    fn invalid_hour_panics() {
+        ParamBuilder::new().hour(24);
+    }
+
+    #[test]
+    fn try_table_prefix_rejects_invalid_prefixes() {
+        assert!(ParamBuilder::new().try_table_prefix("site7_").is_ok());
+        assert!(matches!(
+            ParamBuilder::new().try_table_prefix("wp_posts; --"),
+            Err(WpError::InvalidParams(_))
+        ));
+    }
+
     #[test]
     fn can_add_date_queries() {
         let dq1 = DateQuery::new().after(crate::DateQueryAfterBefore::new(2022, 2, 2));
@@ -703,6 +1013,12 @@ mod tests {
         assert_eq!(q.0.m.unwrap(), 202308);
     }
 
+    #[test]
+    fn can_set_table_prefix() {
+        let q = ParamBuilder::new().table_prefix("site7_");
+        assert_eq!(q.0.table_prefix.unwrap(), "site7_");
+    }
+
     #[test]
     fn can_set_single_meta() {
         let q = ParamBuilder::new()
@@ -719,22 +1035,77 @@ mod tests {
         let q = ParamBuilder::new()
             .meta_key("key1")
             .meta_query(
-                MetaQuery {
-                    key: String::from("key1"),
-                    value: String::from("1"),
-                    compare: SqlSearchOperators::Equals,
-                },
+                MetaQuery::new("key1", "1", SqlSearchOperators::Equals),
                 MetaRelation::And,
             )
             .meta_query(
-                MetaQuery {
-                    key: String::from("key2"),
-                    value: String::from("2"),
-                    compare: SqlSearchOperators::GreaterThan,
-                },
+                MetaQuery::new("key2", "2", SqlSearchOperators::GreaterThan),
                 MetaRelation::And,
             );
         let queries = q.0.meta_query.unwrap();
-        assert_eq!(queries.get(&MetaRelation::And).unwrap().len(), 2);
+        assert!(matches!(queries, MetaQueryTree::Group(MetaRelation::And, q) if q.len() == 2));
+    }
+
+    #[test]
+    fn meta_query_with_new_relation_nests_previous_clauses() {
+        let q = ParamBuilder::new()
+            .meta_query(
+                MetaQuery::new("a", "1", SqlSearchOperators::Equals),
+                MetaRelation::Or,
+            )
+            .meta_query(
+                MetaQuery::new("b", "2", SqlSearchOperators::Equals),
+                MetaRelation::Or,
+            )
+            .meta_query(
+                MetaQuery::new("c", "3", SqlSearchOperators::Equals),
+                MetaRelation::And,
+            );
+
+        match q.0.meta_query.unwrap() {
+            MetaQueryTree::Group(MetaRelation::And, queries) => {
+                assert_eq!(queries.len(), 2);
+                assert!(matches!(
+                    &queries[0],
+                    MetaQueryTree::Group(MetaRelation::Or, q) if q.len() == 2
+                ));
+                assert!(matches!(&queries[1], MetaQueryTree::Clause(c) if c.key == "c"));
+            }
+            _ => panic!("Not an AND group"),
+        }
+    }
+
+    #[test]
+    fn can_set_meta_tree() {
+        let tree = MetaQueryTree::group(MetaRelation::Or)
+            .push(MetaQuery::new("key1", 1, SqlSearchOperators::Equals))
+            .push(MetaQuery::new("key2", 2, SqlSearchOperators::Equals));
+        let q = ParamBuilder::new().meta_key("key1").meta_query_tree(tree);
+
+        assert!(q.0.meta_key.is_none());
+        assert!(matches!(
+            q.0.meta_query.unwrap(),
+            MetaQueryTree::Group(MetaRelation::Or, q) if q.len() == 2
+        ));
+    }
+
+    #[test]
+    fn try_single_meta_errors_when_meta_query_is_set() {
+        let q = ParamBuilder::new().meta_query(
+            MetaQuery::new("key1", "1", SqlSearchOperators::Equals),
+            MetaRelation::And,
+        );
+
+        assert!(matches!(
+            q.try_meta_key("key2"),
+            Err(WpError::InvalidParams(_))
+        ));
+
+        let q = ParamBuilder::new()
+            .try_meta_value("a")
+            .and_then(|q| q.try_meta_value_num(1))
+            .unwrap();
+        assert!(q.0.meta_value.is_none());
+        assert_eq!(q.0.meta_value_num, Some(1));
     }
 }
@@ -25,9 +25,9 @@ impl<'a> std::fmt::Display for PostType<'a> {
     }
 }
 
-impl<'a> Into<Vec<u8>> for PostType<'a> {
-    fn into(self) -> Vec<u8> {
-        self.to_string().into_bytes()
+impl<'a> From<PostType<'a>> for Vec<u8> {
+    fn from(value: PostType<'a>) -> Self {
+        value.to_string().into_bytes()
     }
 }
 
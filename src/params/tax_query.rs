@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::sql::SqlConditionOperator;
 
 #[derive(Debug)]
@@ -10,24 +8,13 @@ pub struct TaxQuery<'a> {
      */
     pub field: TaxField,
     pub terms: Vec<String>,
+    /// Expands the terms to all of their descendant terms for hierarchical taxonomies, i.e. subcategories.
+    /// Uses a recursive CTE, requiring MySQL 8.0 or MariaDB 10.2 and later.
     pub include_children: bool,
     pub operator: SqlConditionOperator,
 }
 
 impl<'a> TaxQuery<'a> {
-    pub fn new_single_tax_map(r: Self) -> HashMap<TaxRelation, Vec<Self>> {
-        let k = Self::get_single_tax_key();
-        let mut m = HashMap::new();
-
-        m.insert(k, vec![r]);
-
-        m
-    }
-
-    fn get_single_tax_key() -> TaxRelation {
-        TaxRelation::Single
-    }
-
     pub fn new<T>(taxonomy: &'a str, terms: Vec<T>) -> Self
     where
         T: std::fmt::Display,
@@ -36,7 +23,7 @@ impl<'a> TaxQuery<'a> {
             taxonomy,
             field: TaxField::TermId,
             terms: terms.into_iter().map(|v| v.to_string()).collect(),
-            include_children: true,
+            include_children: false,
             operator: SqlConditionOperator::In,
         }
     }
@@ -56,6 +43,14 @@ impl<'a> TaxQuery<'a> {
         self
     }
 
+    /// Whether to include posts in the children of hierarchical terms. Defaults to false, unlike WordPress,
+    /// as the children are selected with `WITH RECURSIVE` which needs MySQL 8.0 or MariaDB 10.2 and later.
+    pub fn include_children(mut self, include_children: bool) -> Self {
+        self.include_children = include_children;
+
+        self
+    }
+
     pub fn operator(mut self, operator: SqlConditionOperator) -> Self {
         self.operator = operator;
 
@@ -63,6 +58,55 @@ impl<'a> TaxQuery<'a> {
     }
 }
 
+/// A tax query clause, or a group of nested clauses joined by a relation, like the arrays of `tax_query` in WordPress.
+///
+/// # Example
+/// ```
+/// use wp_query_rs::{TaxField, TaxQuery, TaxQueryTree, TaxRelation};
+///
+/// // Posts in the news category, or tagged with both rust and php
+/// let tree = TaxQueryTree::group(TaxRelation::Or)
+///     .push(TaxQuery::new("category", vec!["news"]).field(TaxField::Slug))
+///     .push(
+///         TaxQueryTree::group(TaxRelation::And)
+///             .push(TaxQuery::new("post_tag", vec!["rust"]).field(TaxField::Slug))
+///             .push(TaxQuery::new("post_tag", vec!["php"]).field(TaxField::Slug)),
+///     );
+/// ```
+#[derive(Debug)]
+pub enum TaxQueryTree<'a> {
+    Clause(TaxQuery<'a>),
+    Group(TaxRelation, Vec<TaxQueryTree<'a>>),
+}
+
+impl<'a> TaxQueryTree<'a> {
+    /// Creates an empty group of clauses.
+    pub fn group(relation: TaxRelation) -> Self {
+        Self::Group(relation, vec![])
+    }
+
+    /// Adds a clause or nested group. A single clause becomes the first clause of an AND group.
+    pub fn push<T>(self, query: T) -> Self
+    where
+        T: Into<TaxQueryTree<'a>>,
+    {
+        match self {
+            Self::Group(relation, mut queries) => {
+                queries.push(query.into());
+
+                Self::Group(relation, queries)
+            }
+            clause => Self::Group(TaxRelation::And, vec![clause, query.into()]),
+        }
+    }
+}
+
+impl<'a> From<TaxQuery<'a>> for TaxQueryTree<'a> {
+    fn from(value: TaxQuery<'a>) -> Self {
+        Self::Clause(value)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub enum TaxRelation {
     And,
@@ -92,14 +136,14 @@ pub enum TaxField {
 }
 
 impl std::fmt::Display for TaxField {
-    /// requires the addition of the wp table prefix
+    /// requires the addition of the table prefix, `wp_` by default
     ///
     /// # Example
     /// ```
     /// use wp_query_rs::*;
     ///
     /// let field = TaxField::TermTaxonomyId;
-    /// format!("wp_{}", field);
+    /// format!("site7_{}", field);
     /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -142,6 +186,36 @@ mod tests {
         assert_eq!(tax.field, TaxField::Name);
     }
 
+    #[test]
+    fn excludes_children_by_default() {
+        let tax = TaxQuery::new("category", vec![1]);
+        assert!(!tax.include_children);
+
+        let tax = tax.include_children(true);
+        assert!(tax.include_children);
+    }
+
+    #[test]
+    fn can_nest_tax_queries() {
+        let tree = TaxQueryTree::group(TaxRelation::Or)
+            .push(TaxQuery::new("category", vec![1]))
+            .push(
+                TaxQueryTree::group(TaxRelation::And)
+                    .push(TaxQuery::new("post_tag", vec![2]))
+                    .push(TaxQuery::new("post_tag", vec![3])),
+            );
+
+        match tree {
+            TaxQueryTree::Group(TaxRelation::Or, queries) => {
+                assert!(matches!(&queries[0], TaxQueryTree::Clause(q) if q.taxonomy == "category"));
+                assert!(
+                    matches!(&queries[1], TaxQueryTree::Group(TaxRelation::And, q) if q.len() == 2)
+                );
+            }
+            _ => panic!("Not a group"),
+        }
+    }
+
     #[test]
     fn can_change_operator() {
         let tax = TaxQuery::new("category", vec![String::from("1")])
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{MetaQuery, MetaRelation, PostStatus, SqlSearchOperators};
+use crate::{MetaQuery, MetaQueryTree, MetaRelation, PostStatus, SqlSearchOperators};
 
 use super::post_type::PostType;
 
@@ -45,5 +45,10 @@ pub trait MetaQueryable<'a> {
     /// Operator to test the ‘meta_value‘
     fn meta_compare(self, compare: SqlSearchOperators) -> Self;
 
+    /// Adds a clause to the top level group of meta queries when it has the same relation,
+    /// otherwise nests the existing clauses in a new group with the given relation.
     fn meta_query(self, query: MetaQuery, relation: MetaRelation) -> Self;
+
+    /// Sets a meta query of nested clauses, replacing any previously added clauses.
+    fn meta_query_tree(self, tree: MetaQueryTree) -> Self;
 }
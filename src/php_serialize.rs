@@ -0,0 +1,229 @@
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any, Serialize,
+};
+
+/// Checks if a value is PHP serialized, like `is_serialized` in WordPress.
+///
+/// # Example
+/// ```
+/// use wp_query_rs::is_serialized;
+///
+/// assert!(is_serialized("a:1:{i:0;s:3:\"red\";}"));
+/// assert!(!is_serialized("red"));
+/// ```
+pub fn is_serialized(data: &str) -> bool {
+    let data = data.trim();
+
+    if data == "N;" {
+        return true;
+    }
+    if data.len() < 4 || data.as_bytes()[1] != b':' {
+        return false;
+    }
+    if !data.ends_with(';') && !data.ends_with('}') {
+        return false;
+    }
+
+    match data.as_bytes()[0] {
+        b's' => data.ends_with("\";"),
+        b'a' | b'O' | b'E' => data[2..]
+            .split_once(':')
+            .is_some_and(|(len, _)| !len.is_empty() && len.bytes().all(|c| c.is_ascii_digit())),
+        b'b' | b'i' | b'd' => data[2..]
+            .strip_suffix(';')
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|c| b"0123456789.E+-".contains(&c))),
+        _ => false,
+    }
+}
+
+/// Decodes a value that may be PHP serialized, like `maybe_unserialize` in WordPress.
+///
+/// Serialized values are decoded with serde_php. Other values are read as plain strings,
+/// which can also be parsed into numbers or booleans.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use wp_query_rs::maybe_unserialize;
+///
+/// let sizes: HashMap<String, u64> =
+///     maybe_unserialize("a:2:{s:5:\"width\";i:640;s:6:\"height\";i:480;}").unwrap();
+/// assert_eq!(sizes["width"], 640);
+///
+/// let price: u64 = maybe_unserialize("100").unwrap();
+/// assert_eq!(price, 100);
+/// ```
+pub fn maybe_unserialize<T>(data: &str) -> Result<T, serde_php::Error>
+where
+    T: DeserializeOwned,
+{
+    if is_serialized(data) {
+        serde_php::from_bytes(data.trim().as_bytes())
+    } else {
+        T::deserialize(PlainValue(data))
+    }
+}
+
+/// Encodes a value to be stored in the database, like `maybe_serialize` in WordPress.
+///
+/// Arrays, maps and structs are PHP serialized while scalars are stored as plain strings.
+/// Strings that are already serialized are serialized again, so they are read back unchanged.
+pub fn maybe_serialize<T>(value: &T) -> Result<String, serde_php::Error>
+where
+    T: Serialize + ?Sized,
+{
+    let serialized = serde_php::to_vec(value)?;
+    let serialized = String::from_utf8(serialized).map_err(de::Error::custom)?;
+
+    let plain = match serialized.as_bytes().first() {
+        Some(b's') => serialized
+            .split_once('"')
+            .and_then(|(_, s)| s.strip_suffix("\";"))
+            .filter(|s| !is_serialized(s)),
+        Some(b'i' | b'd') => serialized.get(2..).and_then(|s| s.strip_suffix(';')),
+        Some(b'b') => Some(if serialized == "b:1;" { "1" } else { "" }),
+        Some(b'N') => Some(""),
+        _ => None,
+    };
+
+    Ok(plain.map(String::from).unwrap_or(serialized))
+}
+
+/// Deserializes a plain, not serialized value, parsing it for numeric and boolean types.
+struct PlainValue<'a>(&'a str);
+
+macro_rules! deserialize_parsed {
+    ($($method: ident => $visit: ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.$visit(self.0.trim().parse().map_err(de::Error::custom)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for PlainValue<'a> {
+    type Error = serde_php::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        // PHP stores true as "1" and false as an empty string
+        visitor.visit_bool(!matches!(self.0.trim(), "" | "0" | "false"))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    deserialize_parsed!(
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64
+    );
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn can_detect_serialized_values() {
+        assert!(is_serialized("a:2:{i:0;i:12;i:1;i:34;}"));
+        assert!(is_serialized("s:5:\"hello\";"));
+        assert!(is_serialized("i:42;"));
+        assert!(is_serialized("b:1;"));
+        assert!(is_serialized("N;"));
+        assert!(is_serialized(" d:1.5; "));
+
+        assert!(!is_serialized("hello"));
+        assert!(!is_serialized("42"));
+        assert!(!is_serialized("a:b:{}"));
+        assert!(!is_serialized("i:4x;"));
+    }
+
+    #[test]
+    fn unserializes_serialized_values() {
+        let ids: Vec<u64> = maybe_unserialize("a:2:{i:0;i:12;i:1;i:34;}").unwrap();
+        assert_eq!(ids, vec![12, 34]);
+
+        let roles: HashMap<String, bool> =
+            maybe_unserialize("a:1:{s:13:\"administrator\";b:1;}").unwrap();
+        assert!(roles["administrator"]);
+    }
+
+    #[test]
+    fn reads_plain_values() {
+        let s: String = maybe_unserialize("hello").unwrap();
+        assert_eq!(s, "hello");
+
+        let n: i64 = maybe_unserialize("-42").unwrap();
+        assert_eq!(n, -42);
+
+        let f: f64 = maybe_unserialize("1.5").unwrap();
+        assert_eq!(f, 1.5);
+
+        let b: bool = maybe_unserialize("1").unwrap();
+        assert!(b);
+
+        let none: Option<String> = maybe_unserialize("").unwrap();
+        assert_eq!(none, None);
+
+        assert!(maybe_unserialize::<u64>("hello").is_err());
+    }
+
+    #[test]
+    fn serializes_compound_values_only() {
+        assert_eq!(maybe_serialize(&42).unwrap(), "42");
+        assert_eq!(maybe_serialize(&1.5).unwrap(), "1.5");
+        assert_eq!(maybe_serialize("hello").unwrap(), "hello");
+        assert_eq!(maybe_serialize(&true).unwrap(), "1");
+        assert_eq!(maybe_serialize(&false).unwrap(), "");
+        assert_eq!(
+            maybe_serialize(&vec![12, 34]).unwrap(),
+            "a:2:{i:0;i:12;i:1;i:34;}"
+        );
+    }
+
+    #[test]
+    fn serializes_serialized_strings_again() {
+        let serialized = maybe_serialize("i:42;").unwrap();
+        assert_eq!(serialized, "s:5:\"i:42;\";");
+
+        let s: String = maybe_unserialize(&serialized).unwrap();
+        assert_eq!(s, "i:42;");
+    }
+}
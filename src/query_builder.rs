@@ -2,30 +2,76 @@ use mysql_common::{prelude::ToValue, Value};
 use sql_paginatorr::LimitOffsetPair;
 
 use crate::{
-    params::Params, sql::SqlOrder, wp_post::post_status::PostStatus, PostType, SqlSearchOperators,
+    params::{orderby::WpOrderBy, Params},
+    sql::{
+        cast_type::CastType, table_prefix::table_prefix_or_global, SqlConditionOperator, SqlOrder,
+    },
+    wp_post::post_status::PostStatus,
+    MetaQuery, MetaQueryTree, PostType, SqlSearchOperators, TaxField, TaxQuery, TaxQueryTree,
+    WpError,
 };
 
-type StmtValues = Vec<Value>;
+pub type StmtValues = Vec<Value>;
 
 pub struct QueryBuilder<'a> {
     params: Params<'a>,
     query: String,
     values: StmtValues,
+    pagination: Pagination,
 }
 
 pub struct QueryAndValues(pub String, pub Vec<Value>);
 
+/// How the rows of the posts query are limited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    not(any(feature = "query_sync", feature = "query_async")),
+    allow(dead_code)
+)]
+enum Pagination {
+    /// Paginated with `page` and `posts_per_page`.
+    Page,
+    /// Every matching post.
+    All,
+    /// Batches of posts ordered by ID, continuing after the ID given as the second to last value.
+    Keyset(u64),
+}
+
 impl<'a> QueryBuilder<'a> {
     pub fn new(params: Params<'a>) -> Self {
         Self {
             params,
             query: String::new(),
             values: vec![],
+            pagination: Pagination::Page,
         }
     }
 
-    pub fn query(mut self) -> QueryAndValues {
-        let params = self.params;
+    /// Builds the query of every post matching the conditions, ignoring `page` and `posts_per_page`.
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    pub fn stream_query(mut self) -> Result<QueryAndValues, WpError> {
+        self.pagination = Pagination::All;
+
+        Ok(self.query()?.0)
+    }
+
+    /// Builds the query of a batch of posts matching the conditions, ordered by ID.
+    /// The second to last value is the ID to continue after, starting at 0, and the last the batch size.
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    pub fn keyset_query(mut self, batch_size: u64) -> Result<QueryAndValues, WpError> {
+        self.pagination = Pagination::Keyset(batch_size);
+
+        Ok(self.query()?.0)
+    }
+
+    /// Builds the posts query along with a query counting all posts matching the same conditions, ignoring pagination.
+    /// The count query is None if `no_found_rows` is set.
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid.
+    pub fn query(mut self) -> Result<(QueryAndValues, Option<QueryAndValues>), WpError> {
+        let mut params = self.params;
+        let prefix = table_prefix_or_global(params.table_prefix)?;
 
         macro_rules! add_if_some_id {
             ($prop: ident, $query: expr) => {
@@ -49,37 +95,97 @@ impl<'a> QueryBuilder<'a> {
             };
         }
 
-        self.query.push_str(
-            "SELECT DISTINCT(wp_posts.ID),post_author,comment_count,post_parent,menu_order,
-            post_date,post_date_gmt,post_modified,post_modified_gmt,
-            post_status,post_content,post_title,post_excerpt,comment_status,ping_status,
-            post_password,post_name,to_ping,pinged,post_content_filtered,guid,
-            post_type,post_mime_type
-            FROM wp_posts",
-        );
-
         let join_term = check_if_term_join_necessary(&params);
         let join_meta = check_if_meta_join_necessary(&params);
         let join_user = check_if_user_join_necessary(&params);
         let has_single_date = has_valid_single_date(&params);
 
+        let paginated = self.pagination == Pagination::Page;
+        let keyset = matches!(self.pagination, Pagination::Keyset(_));
+        // Keyset batches are always ordered by ID
+        let orderby = params
+            .orderby
+            .take()
+            .filter(|_| !keyset)
+            .unwrap_or_default();
+        let default_order = params.order.unwrap_or(SqlOrder::Desc);
+        // Lists are consumed by the WHERE conditions but also needed to keep their order
+        let post_in = params.post__in.clone();
+        let post_name_in = params.post_name__in.clone();
+        let post_parent_in = params.post_parent__in.clone();
+
+        self.query.push_str(&format!(
+            "SELECT DISTINCT({prefix}posts.ID),post_author,comment_count,post_parent,menu_order,
+            post_date,post_date_gmt,post_modified,post_modified_gmt,
+            post_status,post_content,post_title,post_excerpt,comment_status,ping_status,
+            post_password,post_name,to_ping,pinged,post_content_filtered,guid,
+            post_type,post_mime_type"
+        ));
+        // Meta values must be selected to be ordered by with DISTINCT
+        let mut selected_orders = vec![];
+        for (i, (ob, _)) in orderby.iter().enumerate() {
+            let col = match ob {
+                WpOrderBy::MetaValue if join_meta => format!("{prefix}postmeta.meta_value"),
+                WpOrderBy::MetaValueNum if join_meta => format!("{prefix}postmeta.meta_value+0"),
+                WpOrderBy::MetaClause(name) => {
+                    let Some(clause) = params.meta_query.as_ref().and_then(|t| t.find_clause(name))
+                    else {
+                        continue;
+                    };
+                    let value = match clause.cast {
+                        CastType::Char => String::from("orderby_meta.meta_value"),
+                        cast => format!("CAST(orderby_meta.meta_value AS {cast})"),
+                    };
+                    self.values
+                        .push(Value::Bytes(clause.key.as_bytes().to_vec()));
+
+                    format!(
+                        "(SELECT {value} FROM {prefix}postmeta AS orderby_meta WHERE orderby_meta.post_id = {prefix}posts.ID AND orderby_meta.meta_key = ? LIMIT 1)"
+                    )
+                }
+                _ => continue,
+            };
+            self.query.push_str(&format!(", {col} AS orderby_{i}"));
+            selected_orders.push(i);
+        }
+        self.query
+            .push_str(&format!("\n            FROM {prefix}posts"));
+        // JOINs and WHERE conditions are shared with the count query
+        let select_len = self.query.len();
+        let select_values_len = self.values.len();
+
         if join_meta {
-            self.query
-                .push_str(" INNER JOIN wp_postmeta ON wp_postmeta.post_id = wp_posts.ID");
+            self.query.push_str(&format!(
+                " INNER JOIN {prefix}postmeta ON {prefix}postmeta.post_id = {prefix}posts.ID"
+            ));
+
+            // Meta queries are ordered by the first clause like WordPress when no meta key is given,
+            // skipping NOT EXISTS clauses which would match no meta rows to join
+            let order_key = params
+                .meta_query
+                .as_ref()
+                .and_then(MetaQueryTree::first_key)
+                .filter(|_| params.meta_key.is_none());
+            if let Some(key) = order_key {
+                self.query
+                    .push_str(&format!(" AND {prefix}postmeta.meta_key = ?"));
+                self.values.push(Value::Bytes(key.as_bytes().to_vec()));
+            }
         }
 
         if join_term {
-            self.query.push_str(
-                " INNER JOIN wp_term_relationships
-            ON wp_posts.ID = wp_term_relationships.object_id
-            INNER JOIN wp_term_taxonomy ON wp_term_taxonomy.term_taxonomy_id = wp_term_relationships.term_taxonomy_id
-            INNER JOIN wp_terms ON wp_terms.term_id = wp_term_taxonomy.term_id",
-            );
+            self.query.push_str(&format!(
+                " INNER JOIN {prefix}term_relationships
+            ON {prefix}posts.ID = {prefix}term_relationships.object_id
+            INNER JOIN {prefix}term_taxonomy ON {prefix}term_taxonomy.term_taxonomy_id = {prefix}term_relationships.term_taxonomy_id
+            INNER JOIN {prefix}terms ON {prefix}terms.term_id = {prefix}term_taxonomy.term_id"
+            ));
         }
 
         if join_user {
-            self.query
-                .push_str(" INNER JOIN wp_users ON wp_users.ID = wp_posts.post_author");
+            self.query.push_str(&format!(
+                " INNER JOIN {prefix}users ON {prefix}users.ID = {prefix}posts.post_author"
+            ));
         }
 
         // Avoid dangling WHERE issue
@@ -88,7 +194,8 @@ impl<'a> QueryBuilder<'a> {
         add_if_some_id!(author, " AND post_author = ?");
 
         if let Some(author_name) = params.author_name {
-            self.query.push_str(" AND wp_users.user_nicename = ?");
+            self.query
+                .push_str(&format!(" AND {prefix}users.user_nicename = ?"));
             self.values.push(Value::Bytes(author_name.into()));
         }
 
@@ -107,7 +214,7 @@ impl<'a> QueryBuilder<'a> {
         /* Add category, tag, and term conditions */
         if let Some(term_slugs) = params.term_slug_and {
             for term_slug in term_slugs.into_iter() {
-                self.query.push_str(" AND wp_terms.slug = ?");
+                self.query.push_str(&format!(" AND {prefix}terms.slug = ?"));
                 self.values.push(Value::Bytes(term_slug.into()));
             }
         }
@@ -116,78 +223,61 @@ impl<'a> QueryBuilder<'a> {
             let q_marks = implode_to_question_mark(&term_slugs);
 
             self.query
-                .push_str(&format!(" AND wp_terms.slug IN ({})", q_marks));
+                .push_str(&format!(" AND {prefix}terms.slug IN ({})", q_marks));
 
             let values = term_slugs.into_iter().map(|slug| Value::Bytes(slug.into()));
 
             self.values.extend(values);
         }
 
-        add_multiple_if_some_ids!(term_in, " AND wp_terms.term_id IN ({})");
+        add_multiple_if_some_ids!(term_in, " AND {prefix}terms.term_id IN ({})");
 
-        add_multiple_if_some_ids!(term_not_in, " AND wp_terms.term_id NOT IN ({})");
+        add_multiple_if_some_ids!(term_not_in, " AND {prefix}terms.term_id NOT IN ({})");
 
         /* Tax Query */
-        if let Some(queries) = params.tax_query {
-            for (relationship, tax_queries) in queries.into_iter() {
-                self.query.push_str(" AND");
-
-                // Operator for comparing multiple tax queries
-                let rel_str = relationship.to_string();
-
-                for (i, tax_q) in tax_queries.into_iter().enumerate() {
-                    // Add internal operator if length is greater than 1
-                    if i > 0 {
-                        self.query.push_str(" ");
-                        self.query.push_str(&rel_str);
-                    }
-
-                    let q_marks = implode_to_question_mark(&tax_q.terms);
-
-                    let prefix = "wp";
-                    self.query.push_str(&format!(
-                        " {}_{} {} ({})",
-                        prefix, tax_q.field, tax_q.operator, q_marks
-                    ));
-                    self.values
-                        .extend(tax_q.terms.into_iter().map(|v| Value::Bytes(v.into())))
-                }
-            }
+        if let Some(tree) = params.tax_query {
+            self.query.push_str(" AND ");
+            push_tax_query(&mut self.query, &mut self.values, prefix, tree);
         }
 
         /* Add search conditions */
         if let Some(keyword) = params.s {
-            self.query
-                .push_str(" AND wp_posts.post_content LIKE CONCAT('%',?,'%') OR wp_posts.post_title LIKE CONCAT('%',?,'%')");
+            self.query.push_str(&format!(
+                " AND ({prefix}posts.post_content LIKE CONCAT('%',?,'%') OR {prefix}posts.post_title LIKE CONCAT('%',?,'%'))"
+            ));
             self.values.push(Value::Bytes(keyword.as_bytes().to_vec())); // Clone this so it can be used again
             self.values.push(Value::Bytes(keyword.into()));
         }
 
         /* Add page/post conditions */
-        add_if_some_id!(p, " AND wp_posts.ID = ?");
+        add_if_some_id!(p, &format!(" AND {prefix}posts.ID = ?"));
 
         if let Some(name) = params.name {
-            self.query.push_str(" AND wp_posts.post_name = ?");
+            self.query
+                .push_str(&format!(" AND {prefix}posts.post_name = ?"));
             self.values.push(Value::Bytes(name.into()));
         }
 
         /* Post types */
-        push_post_type(&mut self.query, &mut self.values, params.post_type);
+        push_post_type(&mut self.query, &mut self.values, prefix, params.post_type);
 
-        add_if_some_id!(post_parent, " AND wp_posts.post_parent = ?");
+        add_if_some_id!(post_parent, &format!(" AND {prefix}posts.post_parent = ?"));
 
-        add_multiple_if_some_ids!(post_parent__in, " AND wp_posts.post_parent IN ({})");
+        add_multiple_if_some_ids!(post_parent__in, " AND {prefix}posts.post_parent IN ({})");
 
-        add_multiple_if_some_ids!(post_parent__not_in, " AND wp_posts.post_parent NOT IN ({})");
+        add_multiple_if_some_ids!(
+            post_parent__not_in,
+            " AND {prefix}posts.post_parent NOT IN ({})"
+        );
 
-        add_multiple_if_some_ids!(post__in, " AND wp_posts.ID IN ({})");
+        add_multiple_if_some_ids!(post__in, " AND {prefix}posts.ID IN ({})");
 
-        add_multiple_if_some_ids!(post__not_in, " AND wp_posts.ID NOT IN ({})");
+        add_multiple_if_some_ids!(post__not_in, " AND {prefix}posts.ID NOT IN ({})");
 
         if let Some(p_names) = params.post_name__in {
             let q_marks = implode_to_question_mark(&p_names);
             self.query
-                .push_str(&format!(" AND wp_posts.post_name IN ({})", q_marks));
+                .push_str(&format!(" AND {prefix}posts.post_name IN ({})", q_marks));
 
             let ids = p_names.into_iter().map(|name| Value::Bytes(name.into()));
 
@@ -205,7 +295,8 @@ impl<'a> QueryBuilder<'a> {
                 params.second.unwrap_or(0),
                 0u32,
             );
-            self.query.push_str(" AND wp_posts.post_date = ?");
+            self.query
+                .push_str(&format!(" AND {prefix}posts.post_date = ?"));
             self.values.push(date);
         }
 
@@ -214,7 +305,8 @@ impl<'a> QueryBuilder<'a> {
             for dq in date_queries {
                 let col = &dq.column;
                 if dq.year.is_some() && dq.month.is_some() && dq.day.is_some() {
-                    self.query.push_str(&format!(" AND wp_posts.{} = ?", col));
+                    self.query
+                        .push_str(&format!(" AND {prefix}posts.{} = ?", col));
                     self.values.push(dq.to_value());
                 }
 
@@ -222,87 +314,155 @@ impl<'a> QueryBuilder<'a> {
                 if let Some(after) = dq.after {
                     let d_op = if dq.inclusive { ">=" } else { ">" };
                     self.query
-                        .push_str(&format!(" {} wp_posts.{} {} ?", &op, col, d_op));
+                        .push_str(&format!(" {} {prefix}posts.{} {} ?", &op, col, d_op));
                     self.values.push(after.to_value());
                 }
 
                 if let Some(before) = dq.before {
                     let d_op = if dq.inclusive { "<=" } else { "<" };
                     self.query
-                        .push_str(&format!(" {} wp_posts.{} {} ?", &op, col, d_op));
+                        .push_str(&format!(" {} {prefix}posts.{} {} ?", &op, col, d_op));
                     self.values.push(before.to_value());
                 }
             }
         }
 
         /* Add meta conditions */
-        let meta_op = params
+        let meta_compare = params
             .meta_compare
-            .as_ref()
-            .unwrap_or(&crate::sql::SqlSearchOperators::Equals)
-            .to_string();
+            .unwrap_or(crate::sql::SqlSearchOperators::Equals);
+        // Keys are matched exactly when comparing values against a list
+        let meta_op = if meta_compare.takes_multiple_values() {
+            SqlSearchOperators::Equals.to_string()
+        } else {
+            meta_compare.to_string()
+        };
         if let Some(meta_k) = params.meta_key {
             self.query
-                .push_str(&format!(" AND wp_postmeta.meta_key {} ?", meta_op));
+                .push_str(&format!(" AND {prefix}postmeta.meta_key {} ?", meta_op));
             self.values.push(Value::Bytes(meta_k.into()));
         }
 
         if let Some(meta_v) = params.meta_value {
-            self.query
-                .push_str(&format!(" AND wp_postmeta.meta_value {} ?", meta_op));
-            self.values.push(Value::Bytes(meta_v.into_bytes()));
+            let meta_q = MetaQuery::new("", meta_v, meta_compare);
+
+            self.query.push_str(" AND ");
+            push_compare(
+                &mut self.query,
+                &mut self.values,
+                &format!("{prefix}postmeta.meta_value"),
+                &meta_q.compare,
+                meta_q.compare_values(),
+            );
         }
 
         if let Some(meta_v) = params.meta_value_num {
             self.query
-                .push_str(&format!(" AND wp_postmeta.meta_value {} ?", meta_op));
+                .push_str(&format!(" AND {prefix}postmeta.meta_value {} ?", meta_op));
             self.values.push(Value::Int(meta_v));
         }
 
         if let Some(query_rel_map) = params.meta_query {
-            for (relation, queries) in query_rel_map {
-                self.query.push_str(" AND");
-                for (i, query) in queries.into_iter().enumerate() {
-                    // Print relation (OR/AND) if more than one query in the group, after first
-                    if i > 0 {
-                        self.query.push_str(" ");
-                        self.query.push_str(relation.to_string().as_str());
-                    }
+            push_meta_query(
+                &mut self.query,
+                &mut self.values,
+                MetaTable {
+                    table: &format!("{prefix}postmeta"),
+                    object_id_col: "post_id",
+                    parent_id_col: &format!("{prefix}posts.ID"),
+                },
+                query_rel_map,
+            );
+        }
 
-                    // If Exists or NOT EXISTS, we need to make a different query structure
-                    match query.compare {
-                        op @ (SqlSearchOperators::Exists | SqlSearchOperators::NotExists) => {
-                            self.query.push_str(&format!(
-                                // Must have AND post_id to ensure that we are not comparing all meta rows for exists/not exist
-                                " {} (SELECT * FROM wp_postmeta WHERE meta_key = ? AND wp_postmeta.post_id = wp_posts.ID)",
-                                op.to_string()
-                            ));
-                            // Only push keyname if exists query
-                            self.values.push(Value::Bytes(query.key.into_bytes()));
-                        }
-                        _ => {
-                            let op = query.compare.to_string();
-                            self.query.push_str(&format!(
-                                " (wp_postmeta.meta_value {} ? AND wp_postmeta.meta_key {} ?)",
-                                op, op
-                            ));
-                            self.values.push(Value::Bytes(query.value.into_bytes()));
-                            self.values.push(Value::Bytes(query.key.into_bytes()));
-                        }
-                    };
-                }
-            }
+        /* Count all matches before ordering and pagination */
+        let count = if params.no_found_rows.unwrap_or(false) || !paginated {
+            None
+        } else {
+            Some(QueryAndValues(
+                format!(
+                    "SELECT COUNT(DISTINCT({prefix}posts.ID)) FROM {prefix}posts{};",
+                    &self.query[select_len..]
+                ),
+                self.values[select_values_len..].to_vec(),
+            ))
+        };
+
+        if keyset {
+            self.query.push_str(&format!(" AND {prefix}posts.ID > ?"));
+            self.values.push(Value::UInt(0));
         }
 
         /* Add order conditions */
-        if let Some(orderby) = params.orderby {
-            let order = params.order.unwrap_or(SqlOrder::Desc).clone().to_string();
+        let mut orders: Vec<String> = vec![];
+        if keyset {
+            orders.push(format!("{prefix}posts.ID ASC"));
+        }
+        for (i, (ob, order)) in orderby.into_iter().enumerate() {
+            let order = order.unwrap_or(default_order).to_string();
+            match ob {
+                WpOrderBy::None => {}
+                WpOrderBy::Rand => orders.push(String::from("RAND()")),
+                WpOrderBy::PostIn => {
+                    if let Some(ids) = post_in.as_ref().filter(|ids| !ids.is_empty()) {
+                        orders.push(format!(
+                            "FIELD({prefix}posts.ID, {})",
+                            implode_to_question_mark(ids)
+                        ));
+                        self.values.extend(ids.iter().map(|id| Value::UInt(*id)));
+                    }
+                }
+                WpOrderBy::PostNameIn => {
+                    if let Some(names) = post_name_in.as_ref().filter(|n| !n.is_empty()) {
+                        orders.push(format!(
+                            "FIELD({prefix}posts.post_name, {})",
+                            implode_to_question_mark(names)
+                        ));
+                        self.values.extend(
+                            names
+                                .iter()
+                                .map(|name| Value::Bytes(name.as_bytes().to_vec())),
+                        );
+                    }
+                }
+                WpOrderBy::PostParentIn => {
+                    if let Some(ids) = post_parent_in.as_ref().filter(|ids| !ids.is_empty()) {
+                        orders.push(format!(
+                            "FIELD({prefix}posts.post_parent, {})",
+                            implode_to_question_mark(ids)
+                        ));
+                        self.values.extend(ids.iter().map(|id| Value::UInt(*id)));
+                    }
+                }
+                WpOrderBy::Relevance => {
+                    if let Some(keyword) = params.s {
+                        orders.push(format!(
+                            "{prefix}posts.post_title LIKE CONCAT('%',?,'%') DESC"
+                        ));
+                        orders.push(format!("{prefix}posts.post_date DESC"));
+                        self.values.push(Value::Bytes(keyword.as_bytes().to_vec()));
+                    }
+                }
+                WpOrderBy::MetaValue | WpOrderBy::MetaValueNum | WpOrderBy::MetaClause(_) => {
+                    if selected_orders.contains(&i) {
+                        orders.push(format!("orderby_{i} {order}"));
+                    }
+                }
+                col => orders.push(format!("{prefix}posts.{col} {order}")),
+            }
+        }
+        if !orders.is_empty() {
             self.query
-                .push_str(&format!(" ORDER BY {} {}", orderby.to_string(), order))
+                .push_str(&format!(" ORDER BY {}", orders.join(", ")));
         }
 
         /* Add pagination */
-        if let Some(page) = params.page {
+        if self.pagination == Pagination::All {
+            self.query.push(';');
+        } else if let Pagination::Keyset(batch_size) = self.pagination {
+            self.query.push_str(" LIMIT ?;");
+            self.values.push(Value::UInt(batch_size));
+        } else if let Some(page) = params.page {
             let LimitOffsetPair { offset, limit } = sql_paginatorr::for_page(
                 page as usize,
                 params.posts_per_page.unwrap_or(10) as usize,
@@ -317,7 +477,7 @@ impl<'a> QueryBuilder<'a> {
             self.values.push(Value::UInt(limit));
         }
 
-        QueryAndValues(self.query, self.values)
+        Ok((QueryAndValues(self.query, self.values), count))
     }
 }
 
@@ -327,14 +487,18 @@ fn check_if_term_join_necessary(params: &Params) -> bool {
         || params.term_not_in.is_some()
         || params.term_slug_and.is_some()
         || params.term_slug_in.is_some()
-        || params.tax_query.is_some()
 }
 
 fn check_if_meta_join_necessary(params: &Params) -> bool {
     params.meta_key.is_some()
         || params.meta_value.is_some()
         || params.meta_value_num.is_some()
-        || params.meta_query.is_some()
+        || (params.meta_query.is_some()
+            && params
+                .orderby
+                .iter()
+                .flatten()
+                .any(|(ob, _)| matches!(ob, WpOrderBy::MetaValue | WpOrderBy::MetaValueNum)))
 }
 
 fn check_if_user_join_necessary(p: &Params) -> bool {
@@ -354,19 +518,255 @@ fn push_post_status(s: &mut String, v: &mut StmtValues, post_status: &PostStatus
     v.push(Value::Bytes(post_status.to_string().into_bytes()));
 }
 
-fn push_post_type(s: &mut String, v: &mut StmtValues, post_type: Option<Vec<PostType>>) {
+fn push_post_type(
+    s: &mut String,
+    v: &mut StmtValues,
+    prefix: &str,
+    post_type: Option<Vec<PostType>>,
+) {
     if let Some(post_types) = post_type {
-        if post_types.len() == 0 {
+        if post_types.is_empty() {
             return;
         }
 
         let q_marks = implode_to_question_mark(&post_types);
-        s.push_str(&format!(" AND wp_posts.post_type IN ({})", q_marks));
+        s.push_str(&format!(" AND {prefix}posts.post_type IN ({})", q_marks));
         for post_type in post_types {
             v.push(Value::Bytes(post_type.into()));
         }
     } else {
-        s.push_str(" AND wp_posts.post_type = 'post'");
+        s.push_str(&format!(" AND {prefix}posts.post_type = 'post'"));
+    }
+}
+
+/// A meta table and how it is joined to the table of the objects it describes.
+pub struct MetaTable<'a> {
+    /// Meta table name including the prefix, e.g. `wp_postmeta`
+    pub table: &'a str,
+    /// Column in the meta table referencing the object, e.g. `post_id`
+    pub object_id_col: &'a str,
+    /// Fully qualified ID column of the object table, e.g. `wp_posts.ID`
+    pub parent_id_col: &'a str,
+}
+
+/// Adds a tax query tree as nested conditions on the post ID, each clause matching posts with a subquery
+/// so that clauses on different taxonomies do not share a join.
+fn push_tax_query(s: &mut String, v: &mut StmtValues, prefix: &str, tree: TaxQueryTree) {
+    match tree {
+        TaxQueryTree::Clause(tax_q) => push_tax_clause(s, v, prefix, tax_q),
+        TaxQueryTree::Group(_, queries) if queries.is_empty() => s.push_str("1 = 1"),
+        TaxQueryTree::Group(relation, queries) => {
+            s.push('(');
+            for (i, query) in queries.into_iter().enumerate() {
+                if i > 0 {
+                    s.push_str(&format!(" {relation} "));
+                }
+                push_tax_query(s, v, prefix, query);
+            }
+            s.push(')');
+        }
+    }
+}
+
+fn push_tax_clause(s: &mut String, v: &mut StmtValues, prefix: &str, tax_q: TaxQuery) {
+    let TaxQuery {
+        taxonomy,
+        field,
+        terms,
+        include_children,
+        operator,
+    } = tax_q;
+    let term_set = TermSet {
+        prefix,
+        taxonomy,
+        field: &field,
+        include_children,
+    };
+
+    match operator {
+        op @ (SqlConditionOperator::Exists | SqlConditionOperator::NotExists) => {
+            let relationships = format!("{prefix}term_relationships");
+            s.push_str(&format!(
+                "{op} (SELECT 1 FROM {relationships} INNER JOIN {prefix}term_taxonomy ON {prefix}term_taxonomy.term_taxonomy_id = {relationships}.term_taxonomy_id WHERE {prefix}term_taxonomy.taxonomy = ? AND {relationships}.object_id = {prefix}posts.ID)"
+            ));
+            v.push(Value::Bytes(taxonomy.into()));
+        }
+        // No posts are in an empty set of terms
+        SqlConditionOperator::NotIn if terms.is_empty() => s.push_str("1 = 1"),
+        _ if terms.is_empty() => s.push_str("0 = 1"),
+        // Posts must be in every term
+        SqlConditionOperator::And => {
+            s.push('(');
+            for (i, term) in terms.into_iter().enumerate() {
+                if i > 0 {
+                    s.push_str(" AND ");
+                }
+                term_set.push_post_in(s, v, "IN", vec![term]);
+            }
+            s.push(')');
+        }
+        SqlConditionOperator::NotIn => term_set.push_post_in(s, v, "NOT IN", terms),
+        SqlConditionOperator::In | SqlConditionOperator::Or => {
+            term_set.push_post_in(s, v, "IN", terms)
+        }
+    }
+}
+
+/// Terms of a taxonomy selected by a tax query clause
+struct TermSet<'a> {
+    prefix: &'a str,
+    taxonomy: &'a str,
+    field: &'a TaxField,
+    include_children: bool,
+}
+
+impl<'a> TermSet<'a> {
+    /// Adds a condition on the post ID being in the posts assigned any of the terms.
+    fn push_post_in(&self, s: &mut String, v: &mut StmtValues, op: &str, terms: Vec<String>) {
+        let prefix = self.prefix;
+        let relationships = format!("{prefix}term_relationships");
+
+        s.push_str(&format!(
+            "{prefix}posts.ID {op} (SELECT {relationships}.object_id FROM {relationships} WHERE {relationships}.term_taxonomy_id IN ("
+        ));
+        self.push_term_taxonomy_ids(s, v, terms);
+        s.push_str("))");
+    }
+
+    /// Selects the term taxonomy IDs of the terms.
+    ///
+    /// When including children, the terms are expanded to all of their descendants in the taxonomy
+    /// through `term_taxonomy.parent` with a recursive CTE, needing MySQL 8.0 or MariaDB 10.2.
+    fn push_term_taxonomy_ids(&self, s: &mut String, v: &mut StmtValues, terms: Vec<String>) {
+        let prefix = self.prefix;
+        let term_taxonomy = format!("{prefix}term_taxonomy");
+        let select_terms = format!(
+            "SELECT {term_taxonomy}.term_taxonomy_id, {term_taxonomy}.term_id FROM {term_taxonomy} INNER JOIN {prefix}terms ON {prefix}terms.term_id = {term_taxonomy}.term_id WHERE {term_taxonomy}.taxonomy = ? AND {prefix}{} IN ({})",
+            self.field,
+            implode_to_question_mark(&terms)
+        );
+
+        v.push(Value::Bytes(self.taxonomy.into()));
+        v.extend(terms.into_iter().map(|v| Value::Bytes(v.into())));
+
+        if !self.include_children {
+            s.push_str(&select_terms);
+
+            return;
+        }
+
+        s.push_str(&format!(
+            "WITH RECURSIVE tax_tree AS ({select_terms} UNION ALL SELECT {term_taxonomy}.term_taxonomy_id, {term_taxonomy}.term_id FROM {term_taxonomy} INNER JOIN tax_tree ON {term_taxonomy}.parent = tax_tree.term_id WHERE {term_taxonomy}.taxonomy = ?) SELECT term_taxonomy_id FROM tax_tree"
+        ));
+        v.push(Value::Bytes(self.taxonomy.into()));
+    }
+}
+
+/// Adds meta query conditions for a meta table, e.g. wp_postmeta or wp_commentmeta.
+///
+/// Each clause is matched with its own EXISTS subquery on the meta table, so that clauses on different keys
+/// can be combined with AND.
+pub fn push_meta_query(s: &mut String, v: &mut StmtValues, meta: MetaTable, tree: MetaQueryTree) {
+    s.push_str(" AND ");
+    push_meta_tree(s, v, &meta, tree);
+}
+
+fn push_meta_tree(s: &mut String, v: &mut StmtValues, meta: &MetaTable, tree: MetaQueryTree) {
+    match tree {
+        MetaQueryTree::Clause(query) => push_meta_clause(s, v, meta, query),
+        MetaQueryTree::Group(_, queries) if queries.is_empty() => s.push_str("1 = 1"),
+        MetaQueryTree::Group(relation, queries) => {
+            s.push('(');
+            for (i, query) in queries.into_iter().enumerate() {
+                if i > 0 {
+                    s.push_str(&format!(" {relation} "));
+                }
+                push_meta_tree(s, v, meta, query);
+            }
+            s.push(')');
+        }
+    }
+}
+
+fn push_meta_clause(s: &mut String, v: &mut StmtValues, meta: &MetaTable, query: MetaQuery) {
+    let MetaTable {
+        table,
+        object_id_col,
+        parent_id_col,
+    } = meta;
+
+    // Must match the object id to ensure that we are not comparing all meta rows
+    let select_meta = format!(
+        "SELECT 1 FROM {table} WHERE {table}.{object_id_col} = {parent_id_col} AND {table}.meta_key = ?"
+    );
+    v.push(Value::Bytes(query.key.as_bytes().to_vec()));
+
+    match query.compare {
+        op @ (SqlSearchOperators::Exists | SqlSearchOperators::NotExists) => {
+            s.push_str(&format!("{op} ({select_meta})"));
+        }
+        _ => {
+            let col = match query.cast {
+                CastType::Char => format!("{table}.meta_value"),
+                cast => format!("CAST({table}.meta_value AS {cast})"),
+            };
+
+            s.push_str(&format!("EXISTS ({select_meta} AND "));
+            push_compare(s, v, &col, &query.compare, query.compare_values());
+            s.push(')');
+        }
+    }
+}
+
+/// Adds a comparison of a column to values, with a list of values for IN and BETWEEN operators.
+pub fn push_compare(
+    s: &mut String,
+    v: &mut StmtValues,
+    col: &str,
+    op: &SqlSearchOperators,
+    values: Vec<String>,
+) {
+    match op {
+        SqlSearchOperators::In | SqlSearchOperators::NotIn if values.is_empty() => {
+            // Nothing is in an empty list
+            s.push_str(if *op == SqlSearchOperators::In {
+                "0 = 1"
+            } else {
+                "1 = 1"
+            });
+        }
+        SqlSearchOperators::In | SqlSearchOperators::NotIn => {
+            s.push_str(&format!(
+                "{col} {op} ({})",
+                implode_to_question_mark(&values)
+            ));
+            v.extend(values.into_iter().map(|v| Value::Bytes(v.into_bytes())));
+        }
+        SqlSearchOperators::Between | SqlSearchOperators::NotBetween => {
+            // Ranges without both bounds cannot match
+            if values.len() < 2 {
+                s.push_str("0 = 1");
+
+                return;
+            }
+
+            s.push_str(&format!("{col} {op} ? AND ?"));
+            v.extend(
+                values
+                    .into_iter()
+                    .take(2)
+                    .map(|v| Value::Bytes(v.into_bytes())),
+            );
+        }
+        op => {
+            s.push_str(&format!("{col} {op} ?"));
+            v.extend(
+                values
+                    .into_iter()
+                    .take(1)
+                    .map(|v| Value::Bytes(v.into_bytes())),
+            );
+        }
     }
 }
 
@@ -377,13 +777,46 @@ fn implode<T: std::fmt::Display>(v: &[T]) -> String {
         .unwrap_or(String::new())
 }
 
-fn implode_to_question_mark<T>(v: &[T]) -> String {
+pub fn implode_to_question_mark<T>(v: &[T]) -> String {
     let q_marks: Vec<char> = v.iter().map(|_| '?').collect();
     implode(&q_marks)
 }
 
+/// Replaces the placeholders of a statement with its values as escaped SQL literals.
+/// Question marks within quoted strings or identifiers are not placeholders.
+/// Placeholders without a value are kept.
+pub fn interpolate(q: &str, values: &[Value]) -> String {
+    let mut sql = String::with_capacity(q.len());
+    let mut values = values.iter();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for c in q.chars() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('\'') | Some('"') if c == '\\' => escaped = true,
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if matches!(c, '\'' | '"' | '`') => quote = Some(c),
+            None if c == '?' => {
+                if let Some(value) = values.next() {
+                    sql.push_str(&value.as_sql(false));
+                    continue;
+                }
+            }
+            None => {}
+        }
+
+        sql.push(c);
+    }
+
+    sql
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::{MetaQueryable, MetaRelation, ParamBuilder, PostQueryable, TaxRelation};
+
     use super::*;
 
     #[test]
@@ -393,6 +826,25 @@ mod tests {
         assert_eq!(&imploded, "1,2,3");
     }
 
+    #[test]
+    fn interpolates_values_outside_of_quotes() {
+        let sql = interpolate(
+            "SELECT * FROM `wp_posts?` WHERE post_title = ? AND post_name LIKE 'a?\\'?' AND ID IN (?,?) LIMIT ?",
+            &[
+                Value::from("it's"),
+                Value::UInt(1),
+                Value::Int(-2),
+                Value::NULL,
+            ],
+        );
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM `wp_posts?` WHERE post_title = 'it\\'s' AND post_name LIKE 'a?\\'?' AND ID IN (1,-2) LIMIT NULL"
+        );
+        assert_eq!(interpolate("LIMIT ?", &[]), "LIMIT ?");
+    }
+
     #[test]
     fn nothing_imploded_for_empty_list() {
         let v: Vec<i32> = vec![];
@@ -400,10 +852,423 @@ mod tests {
         assert_eq!(&imploded, "");
     }
 
+    #[test]
+    fn uses_table_prefix_from_params() {
+        let params: Params = crate::ParamBuilder::new()
+            .table_prefix("site7_")
+            .author_name("admin")
+            .into();
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params).query().unwrap();
+
+        assert!(q.contains("FROM site7_posts"));
+        assert!(q.contains("INNER JOIN site7_users"));
+        assert!(!q.contains("wp_"));
+    }
+
+    #[test]
+    fn invalid_table_prefix_is_an_error() {
+        let mut params = Params::new();
+        params.table_prefix = Some("wp_posts; --");
+
+        assert!(matches!(
+            QueryBuilder::new(params).query(),
+            Err(WpError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn count_query_shares_conditions() {
+        let params: Params = crate::ParamBuilder::new()
+            .author(1)
+            .meta_key("price")
+            .page(2)
+            .into();
+
+        let (QueryAndValues(q, values), count) = QueryBuilder::new(params).query().unwrap();
+        let QueryAndValues(count_q, count_values) = count.unwrap();
+
+        let conditions = &q[q.find(" INNER JOIN").unwrap()..q.find(" LIMIT").unwrap()];
+        assert!(count_q.starts_with("SELECT COUNT(DISTINCT(wp_posts.ID)) FROM wp_posts INNER JOIN"));
+        assert!(count_q.contains(conditions));
+        assert!(!count_q.contains("LIMIT"));
+        // Count query has no limit/offset values
+        assert_eq!(count_values.len(), values.len() - 2);
+    }
+
+    #[test]
+    fn no_count_query_for_no_found_rows() {
+        let params: Params = crate::ParamBuilder::new().no_found_rows(true).into();
+
+        let (_, count) = QueryBuilder::new(params).query().unwrap();
+
+        assert!(count.is_none());
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn stream_query_ignores_pagination() {
+        let params: Params = crate::ParamBuilder::new()
+            .author(1)
+            .page(3)
+            .posts_per_page(20)
+            .orderby(WpOrderBy::Title)
+            .into();
+
+        let QueryAndValues(q, values) = QueryBuilder::new(params).stream_query().unwrap();
+
+        assert!(q.ends_with(" ORDER BY wp_posts.post_title DESC;"));
+        assert!(!q.contains("LIMIT"));
+        assert_eq!(values, vec![Value::UInt(1)]);
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn keyset_query_continues_after_id() {
+        let params: Params = crate::ParamBuilder::new()
+            .s("Hello")
+            .page(3)
+            .orderby(WpOrderBy::Title)
+            .into();
+
+        let QueryAndValues(q, values) = QueryBuilder::new(params).keyset_query(500).unwrap();
+
+        assert!(q.contains("LIKE CONCAT('%',?,'%')) AND"));
+        assert!(q.ends_with(" AND wp_posts.ID > ? ORDER BY wp_posts.ID ASC LIMIT ?;"));
+        assert_eq!(
+            values[values.len() - 2..],
+            [Value::UInt(0), Value::UInt(500)]
+        );
+    }
+
     #[test]
     fn implodes_to_question_marks() {
         let v = vec![1, 2, 3];
         let imploded = implode_to_question_mark(&v);
         assert_eq!(&imploded, "?,?,?");
     }
+
+    #[test]
+    fn expands_tax_query_children() {
+        let tax = TaxQuery::new("category", vec!["news"])
+            .field(TaxField::Slug)
+            .include_children(true);
+        let params = ParamBuilder::new().tax_query(tax, None);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" AND wp_posts.ID IN (SELECT wp_term_relationships.object_id FROM wp_term_relationships WHERE wp_term_relationships.term_taxonomy_id IN (WITH RECURSIVE tax_tree AS ("));
+        assert!(q.contains("WHERE wp_term_taxonomy.taxonomy = ? AND wp_terms.slug IN (?)"));
+        assert!(q.contains("INNER JOIN tax_tree ON wp_term_taxonomy.parent = tax_tree.term_id"));
+        assert_eq!(
+            values[..3],
+            [
+                Value::Bytes(b"category".to_vec()),
+                Value::Bytes(b"news".to_vec()),
+                Value::Bytes(b"category".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_exclude_tax_query_children() {
+        let tax = TaxQuery::new("category", vec![1, 2]).include_children(false);
+        let params = ParamBuilder::new().tax_query(tax, None);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(!q.contains("tax_tree"));
+        assert!(q.contains("wp_term_taxonomy.taxonomy = ? AND wp_terms.term_id IN (?,?))"));
+        assert_eq!(
+            values[..3],
+            [
+                Value::Bytes(b"category".to_vec()),
+                Value::Bytes(b"1".to_vec()),
+                Value::Bytes(b"2".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn tax_query_does_not_share_term_join() {
+        let params = ParamBuilder::new()
+            .tax_query(
+                TaxQuery::new("category", vec![1]).include_children(false),
+                Some(TaxRelation::And),
+            )
+            .tax_query(
+                TaxQuery::new("post_tag", vec![2]).include_children(false),
+                Some(TaxRelation::And),
+            );
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(!q.contains("INNER JOIN wp_term_relationships"));
+        assert_eq!(q.matches("wp_posts.ID IN (SELECT").count(), 2);
+        assert!(q.contains(")) AND wp_posts.ID IN (SELECT"));
+    }
+
+    #[test]
+    fn can_nest_tax_queries() {
+        let tree = TaxQueryTree::group(TaxRelation::Or)
+            .push(
+                TaxQueryTree::group(TaxRelation::And)
+                    .push(TaxQuery::new("category", vec![1]).include_children(false))
+                    .push(
+                        TaxQuery::new("post_tag", vec![2])
+                            .include_children(false)
+                            .operator(SqlConditionOperator::NotIn),
+                    ),
+            )
+            .push(TaxQuery::new("post_format", vec![0]).operator(SqlConditionOperator::Exists));
+        let params = ParamBuilder::new().tax_query_tree(tree);
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" AND ((wp_posts.ID IN (SELECT"));
+        assert!(q.contains(")) AND wp_posts.ID NOT IN (SELECT"));
+        assert!(q.contains(")))) OR EXISTS (SELECT 1 FROM wp_term_relationships INNER JOIN wp_term_taxonomy ON wp_term_taxonomy.term_taxonomy_id = wp_term_relationships.term_taxonomy_id WHERE wp_term_taxonomy.taxonomy = ? AND wp_term_relationships.object_id = wp_posts.ID))"));
+    }
+
+    #[test]
+    fn tax_and_operator_requires_every_term() {
+        let tax = TaxQuery::new("post_tag", vec![1, 2])
+            .include_children(false)
+            .operator(SqlConditionOperator::And);
+        let params = ParamBuilder::new().tax_query(tax, None);
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert_eq!(q.matches("wp_posts.ID IN (SELECT").count(), 2);
+    }
+
+    #[test]
+    fn can_nest_meta_queries() {
+        let tree = MetaQueryTree::group(MetaRelation::Or)
+            .push(
+                MetaQueryTree::group(MetaRelation::And)
+                    .push(MetaQuery::new("color", "blue", SqlSearchOperators::Equals))
+                    .push(MetaQuery::new("size", "large", SqlSearchOperators::Equals)),
+            )
+            .push(MetaQuery::new("sale", "", SqlSearchOperators::NotExists));
+        let params = ParamBuilder::new().meta_query_tree(tree);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(!q.contains("INNER JOIN wp_postmeta"));
+        assert!(q.contains(" AND ((EXISTS (SELECT 1 FROM wp_postmeta WHERE wp_postmeta.post_id = wp_posts.ID AND wp_postmeta.meta_key = ? AND wp_postmeta.meta_value = ?) AND EXISTS ("));
+        assert!(q.contains(") OR NOT EXISTS (SELECT 1 FROM wp_postmeta WHERE wp_postmeta.post_id = wp_posts.ID AND wp_postmeta.meta_key = ?))"));
+        assert_eq!(
+            values[..5],
+            [
+                Value::Bytes(b"color".to_vec()),
+                Value::Bytes(b"blue".to_vec()),
+                Value::Bytes(b"size".to_vec()),
+                Value::Bytes(b"large".to_vec()),
+                Value::Bytes(b"sale".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_meta_query_by_first_key() {
+        let params = ParamBuilder::new()
+            .meta_query(
+                MetaQuery::new("price", 10, SqlSearchOperators::GreaterThan),
+                MetaRelation::And,
+            )
+            .orderby(WpOrderBy::MetaValueNum);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" INNER JOIN wp_postmeta ON wp_postmeta.post_id = wp_posts.ID AND wp_postmeta.meta_key = ?"));
+        assert_eq!(values[0], Value::Bytes(b"price".to_vec()));
+    }
+
+    #[test]
+    fn does_not_order_meta_query_by_not_exists_key() {
+        let params = ParamBuilder::new()
+            .meta_query(
+                MetaQuery::new("sale", "", SqlSearchOperators::NotExists),
+                MetaRelation::And,
+            )
+            .meta_query(
+                MetaQuery::new("price", 10, SqlSearchOperators::GreaterThan),
+                MetaRelation::And,
+            )
+            .orderby(WpOrderBy::MetaValueNum);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" INNER JOIN wp_postmeta ON wp_postmeta.post_id = wp_posts.ID AND wp_postmeta.meta_key = ?"));
+        assert_eq!(values[0], Value::Bytes(b"price".to_vec()));
+    }
+
+    #[test]
+    fn casts_typed_meta_queries() {
+        let params = ParamBuilder::new().meta_query(
+            MetaQuery::new("price", 100, SqlSearchOperators::GreaterThan).cast(CastType::Numeric),
+            MetaRelation::And,
+        );
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains("AND CAST(wp_postmeta.meta_value AS SIGNED) > ?)"));
+        assert_eq!(
+            values[..2],
+            [
+                Value::Bytes(b"price".to_vec()),
+                Value::Bytes(b"100".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn can_compare_meta_to_lists() {
+        let tree = MetaQueryTree::group(MetaRelation::And)
+            .push(MetaQuery::with_values(
+                "color",
+                vec!["red", "blue"],
+                SqlSearchOperators::NotIn,
+            ))
+            .push(
+                MetaQuery::with_values(
+                    "date",
+                    vec!["2023-01-01", "2023-12-31"],
+                    SqlSearchOperators::Between,
+                )
+                .cast(CastType::Date),
+            )
+            .push(MetaQuery::new("sku", "^A", SqlSearchOperators::Regexp));
+        let params = ParamBuilder::new().meta_query_tree(tree);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains("AND wp_postmeta.meta_value NOT IN (?,?))"));
+        assert!(q.contains("AND CAST(wp_postmeta.meta_value AS DATE) BETWEEN ? AND ?)"));
+        assert!(q.contains("AND wp_postmeta.meta_value REGEXP ?)"));
+        // Keys and values, then the limit
+        assert_eq!(values.len(), 9);
+    }
+
+    #[test]
+    fn can_compare_single_meta_value_to_list() {
+        let params = ParamBuilder::new()
+            .meta_key("color")
+            .meta_value("red,blue")
+            .meta_compare(SqlSearchOperators::In);
+
+        let (QueryAndValues(q, values), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" AND wp_postmeta.meta_key = ?"));
+        assert!(q.contains(" AND wp_postmeta.meta_value IN (?,?)"));
+        assert_eq!(
+            values[..3],
+            [
+                Value::Bytes(b"color".to_vec()),
+                Value::Bytes(b"red".to_vec()),
+                Value::Bytes(b"blue".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_by_multiple_keys() {
+        let params = ParamBuilder::new()
+            .add_orderby(WpOrderBy::MenuOrder, SqlOrder::Asc)
+            .add_orderby(WpOrderBy::Date, SqlOrder::Desc);
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" ORDER BY wp_posts.menu_order ASC, wp_posts.post_date DESC LIMIT ?"));
+    }
+
+    #[test]
+    fn orders_by_single_key_with_default_order() {
+        let params = ParamBuilder::new().orderby(WpOrderBy::Title);
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" ORDER BY wp_posts.post_title DESC"));
+    }
+
+    #[test]
+    fn keeps_order_of_post_in() {
+        let params = ParamBuilder::new()
+            .post__in(3)
+            .post__in(1)
+            .orderby(WpOrderBy::PostIn);
+
+        let (QueryAndValues(q, values), count) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(" ORDER BY FIELD(wp_posts.ID, ?,?) LIMIT ?"));
+        assert_eq!(
+            values[values.len() - 3..values.len() - 1],
+            [Value::UInt(3), Value::UInt(1)]
+        );
+        // The order values are not part of the count query
+        assert_eq!(count.unwrap().1.len(), values.len() - 3);
+    }
+
+    #[test]
+    fn orders_by_rand_and_relevance() {
+        let params = ParamBuilder::new().orderby(WpOrderBy::Rand);
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+        assert!(q.contains(" ORDER BY RAND()"));
+
+        let params = ParamBuilder::new().s("Hello").orderby(WpOrderBy::Relevance);
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+        assert!(q.contains(
+            " ORDER BY wp_posts.post_title LIKE CONCAT('%',?,'%') DESC, wp_posts.post_date DESC"
+        ));
+
+        // Relevance is ignored without a search
+        let params = ParamBuilder::new().orderby(WpOrderBy::Relevance);
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+        assert!(!q.contains("ORDER BY"));
+    }
+
+    #[test]
+    fn casts_meta_value_num_when_ordering() {
+        let params = ParamBuilder::new()
+            .meta_key("price")
+            .orderby(WpOrderBy::MetaValueNum)
+            .order(SqlOrder::Asc);
+
+        let (QueryAndValues(q, _), _) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(", wp_postmeta.meta_value+0 AS orderby_0"));
+        assert!(q.contains(" ORDER BY orderby_0 ASC"));
+    }
+
+    #[test]
+    fn orders_by_named_meta_clause() {
+        let params = ParamBuilder::new()
+            .meta_query(
+                MetaQuery::new("price", 0, SqlSearchOperators::GreaterThan)
+                    .cast(CastType::Numeric)
+                    .name("price_clause"),
+                MetaRelation::And,
+            )
+            .add_orderby(
+                WpOrderBy::MetaClause(String::from("price_clause")),
+                SqlOrder::Asc,
+            )
+            .add_orderby(
+                WpOrderBy::MetaClause(String::from("missing")),
+                SqlOrder::Asc,
+            );
+
+        let (QueryAndValues(q, values), count) = QueryBuilder::new(params.into()).query().unwrap();
+
+        assert!(q.contains(", (SELECT CAST(orderby_meta.meta_value AS SIGNED) FROM wp_postmeta AS orderby_meta WHERE orderby_meta.post_id = wp_posts.ID AND orderby_meta.meta_key = ? LIMIT 1) AS orderby_0"));
+        assert!(q.contains(" ORDER BY orderby_0 ASC LIMIT ?"));
+        assert!(!q.contains("orderby_1"));
+        assert!(!q.contains("INNER JOIN wp_postmeta"));
+        assert_eq!(values[0], Value::Bytes(b"price".to_vec()));
+
+        let QueryAndValues(count_q, count_values) = count.unwrap();
+        assert!(!count_q.contains("orderby_meta"));
+        assert_eq!(count_values.len(), values.len() - 2);
+    }
 }
@@ -1,21 +1,25 @@
 use std::fmt::Display;
 
+#[cfg(feature = "query_sync")]
+use crate::query_builder::QueryAndValues;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::WpError;
 use mysql_common::prelude::FromValue;
 
 pub mod cast_type;
 pub mod date;
 pub mod env_vars;
+pub mod executor;
+pub mod fake;
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
 pub mod pool;
+pub mod table_prefix;
 pub mod traits;
-use self::env_vars::EnvVars;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
-use self::pool::get_pool;
+#[cfg(feature = "query_sync")]
+use self::{env_vars::EnvVars, pool::get_pool, traits::Executor};
 #[cfg(feature = "query_sync")]
 use mysql::{OptsBuilder, PooledConn};
 
-#[cfg(feature = "query_async")]
-use mysql_async::OptsBuilder;
 #[cfg(feature = "query_sync")]
 fn build_opts_from_env(env_vars: EnvVars) -> OptsBuilder {
     OptsBuilder::new()
@@ -26,24 +30,72 @@ fn build_opts_from_env(env_vars: EnvVars) -> OptsBuilder {
         .tcp_port(env_vars.port.unwrap_or(3306))
         .prefer_socket(true)
 }
-#[cfg(feature = "query_async")]
-fn build_opts_from_env(env_vars: EnvVars) -> OptsBuilder {
-    OptsBuilder::default()
-        .user(env_vars.user)
-        .ip_or_hostname(env_vars.host.expect("must define mysql host"))
-        .pass(env_vars.password)
-        .db_name(env_vars.db_name)
-        .tcp_port(env_vars.port.unwrap_or(3306))
-        .prefer_socket(true)
+
+#[cfg(feature = "query_sync")]
+pub fn get_conn() -> Result<PooledConn, WpError> {
+    // Surface an invalid WORDPRESS_TABLE_PREFIX here rather than as a panic when building the query
+    table_prefix::try_get_table_prefix()?;
+
+    Ok(get_pool()?.get_conn()?)
 }
 
+/// Runs the statements in a single transaction on a connection of the global pool.
 #[cfg(feature = "query_sync")]
-pub fn get_conn() -> Result<PooledConn, mysql::Error> {
-    get_pool().get_conn()
+pub fn exec_transaction(statements: Vec<QueryAndValues>) -> Result<(), WpError> {
+    let mut conn = get_conn()?;
+    let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+    for QueryAndValues(q, values) in statements {
+        tx.execute(&q, values)?;
+    }
+
+    Ok(tx.commit()?)
+}
+
+/// The ID of the last inserted row, read with `SELECT LAST_INSERT_ID();`.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub fn last_insert_id(row: Option<u64>) -> Result<u64, WpError> {
+    row.ok_or_else(|| WpError::NotFound(String::from("LAST_INSERT_ID")))
 }
+
 #[cfg(feature = "query_async")]
-pub async fn get_conn() -> Result<mysql_async::Conn, mysql_async::Error> {
-    get_pool().get_conn().await
+pub mod r#async {
+    use mysql_async::OptsBuilder;
+
+    use super::{env_vars::EnvVars, pool::r#async::get_pool, traits::r#async::Executor};
+    use crate::{query_builder::QueryAndValues, WpError};
+
+    pub fn build_opts_from_env(env_vars: EnvVars) -> Result<OptsBuilder, WpError> {
+        let host = env_vars.host.ok_or_else(|| {
+            WpError::InvalidParams(String::from("must define mysql host in WORDPRESS_DB_HOST"))
+        })?;
+
+        Ok(OptsBuilder::default()
+            .user(env_vars.user)
+            .ip_or_hostname(host)
+            .pass(env_vars.password)
+            .db_name(env_vars.db_name)
+            .tcp_port(env_vars.port.unwrap_or(3306))
+            .prefer_socket(true))
+    }
+
+    pub async fn get_conn() -> Result<mysql_async::Conn, WpError> {
+        super::table_prefix::try_get_table_prefix()?;
+
+        Ok(get_pool()?.get_conn().await?)
+    }
+
+    /// Runs the statements in a single transaction on a connection of the global pool.
+    pub async fn exec_transaction(statements: Vec<QueryAndValues>) -> Result<(), WpError> {
+        let mut conn = get_conn().await?;
+        let mut tx = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await?;
+        for QueryAndValues(q, values) in statements {
+            tx.execute(&q, values).await?;
+        }
+
+        Ok(tx.commit().await?)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -83,6 +135,23 @@ pub enum SqlCompareOperator {
     LessThanOrEqualTo,
 }
 
+impl Display for SqlCompareOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Equals => "=",
+                Self::NotEquals => "!=",
+                Self::GreaterThan => ">",
+                Self::LessThan => "<",
+                Self::GreaterThanOrEqualTo => ">=",
+                Self::LessThanOrEqualTo => "<=",
+            }
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum SqlSearchOperators {
     Equals,
@@ -95,6 +164,23 @@ pub enum SqlSearchOperators {
     NotLike,
     Exists,
     NotExists,
+    In,
+    NotIn,
+    Between,
+    NotBetween,
+    Regexp,
+    NotRegexp,
+    Rlike,
+}
+
+impl SqlSearchOperators {
+    /// Whether the operator compares against a list of values, i.e. IN or BETWEEN.
+    pub fn takes_multiple_values(&self) -> bool {
+        matches!(
+            self,
+            Self::In | Self::NotIn | Self::Between | Self::NotBetween
+        )
+    }
 }
 
 impl Display for SqlSearchOperators {
@@ -113,6 +199,13 @@ impl Display for SqlSearchOperators {
                 Self::NotLike => "NOT LIKE",
                 Self::Exists => "EXISTS",
                 Self::NotExists => "NOT EXISTS",
+                Self::In => "IN",
+                Self::NotIn => "NOT IN",
+                Self::Between => "BETWEEN",
+                Self::NotBetween => "NOT BETWEEN",
+                Self::Regexp => "REGEXP",
+                Self::NotRegexp => "NOT REGEXP",
+                Self::Rlike => "RLIKE",
             }
         )
     }
@@ -124,18 +217,22 @@ pub enum SqlOrder {
     Desc,
 }
 
-impl ToString for SqlOrder {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Asc => String::from("ASC"),
-            Self::Desc => String::from("DESC"),
-        }
+impl Display for SqlOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Asc => "ASC",
+                Self::Desc => "DESC",
+            }
+        )
     }
 }
 
-impl Into<SqlOrder> for &str {
-    fn into(self) -> SqlOrder {
-        match self.to_uppercase().as_str() {
+impl From<&str> for SqlOrder {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
             "ASC" => SqlOrder::Asc,
             "DESC" => SqlOrder::Desc,
             _ => SqlOrder::Desc,
@@ -159,7 +256,7 @@ where
         .enumerate()
         .find(|(_, col)| col.name_str() == col_name)?;
 
-    row.take_opt(i).map(|r| r.ok()).flatten()
+    row.take_opt(i).and_then(|r| r.ok())
 }
 
 #[cfg(test)]
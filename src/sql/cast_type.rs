@@ -1,7 +1,10 @@
-#[derive(Debug)]
+/// Type a meta value is cast to before comparing, like `type` in a WordPress meta query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CastType {
+    /// Cast as SIGNED, as MySQL has no NUMERIC cast.
     Numeric,
     Binary,
+    #[default]
     Char,
     Date,
     Datetime,
@@ -17,7 +20,7 @@ impl std::fmt::Display for CastType {
             f,
             "{}",
             match self {
-                Self::Numeric => "NUMERIC",
+                Self::Numeric => "SIGNED",
                 Self::Binary => "BINARY",
                 Self::Char => "CHAR",
                 Self::Date => "DATE",
@@ -25,7 +28,7 @@ impl std::fmt::Display for CastType {
                 Self::Decimal => "DECIMAL",
                 Self::Signed => "SIGNED",
                 Self::Time => "TIME",
-                Self::Unsigned => "CHAR",
+                Self::Unsigned => "UNSIGNED",
             }
         )
     }
@@ -39,4 +42,11 @@ mod tests {
     fn can_convert_to_string() {
         assert_eq!(&CastType::Binary.to_string(), "BINARY")
     }
+
+    #[test]
+    fn renders_mysql_cast_types() {
+        assert_eq!(&CastType::Numeric.to_string(), "SIGNED");
+        assert_eq!(&CastType::Unsigned.to_string(), "UNSIGNED");
+        assert_eq!(CastType::default(), CastType::Char);
+    }
 }
@@ -7,6 +7,7 @@ pub struct EnvVars {
     pub password: Option<String>,
     pub db_name: Option<String>,
     pub port: Option<u16>,
+    pub table_prefix: Option<String>,
 }
 
 enum EnvVarKeys {
@@ -15,6 +16,7 @@ enum EnvVarKeys {
     Password,
     DbName,
     Port,
+    TablePrefix,
 }
 
 impl EnvVarKeys {
@@ -25,6 +27,7 @@ impl EnvVarKeys {
             Self::Password => "WORDPRESS_DB_PASSWORD",
             Self::DbName => "WORDPRESS_DB_NAME",
             Self::Port => "WORDPRESS_DB_PORT",
+            Self::TablePrefix => "WORDPRESS_TABLE_PREFIX",
         }
     }
 
@@ -47,18 +50,19 @@ impl EnvVars {
             password: Some(password),
             db_name: Some(db_name),
             port,
+            table_prefix: None,
         }
     }
 
-    pub fn from_env<'a>() -> EnvVars {
+    pub fn from_env() -> EnvVars {
         let host = EnvVarKeys::Host.get_env();
         let user = EnvVarKeys::User.get_env();
         let password = EnvVarKeys::Password.get_env();
         let db_name = EnvVarKeys::DbName.get_env();
         let port = EnvVarKeys::Port
             .get_env()
-            .map(|str| str.trim().parse().ok())
-            .flatten();
+            .and_then(|str| str.trim().parse().ok());
+        let table_prefix = EnvVarKeys::TablePrefix.get_env();
 
         EnvVars {
             host,
@@ -66,6 +70,7 @@ impl EnvVars {
             password,
             db_name,
             port,
+            table_prefix,
         }
     }
 }
@@ -0,0 +1,301 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::Queryable;
+#[cfg(feature = "query_sync")]
+use mysql_common::{Row, Value};
+
+#[cfg(feature = "query_sync")]
+use super::traits::Executor;
+#[cfg(feature = "query_sync")]
+use crate::WpError;
+
+#[cfg(feature = "query_sync")]
+macro_rules! impl_executor {
+    ($($conn: ty),*) => {$(
+        impl Executor for $conn {
+            type Statement = mysql::Statement;
+
+            fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+                Ok(self.prep(query)?)
+            }
+
+            fn exec(
+                &mut self,
+                stmt: &Self::Statement,
+                params: Vec<Value>,
+            ) -> Result<Vec<Row>, WpError> {
+                Ok(Queryable::exec(self, stmt, params)?)
+            }
+
+            fn exec_first(
+                &mut self,
+                stmt: &Self::Statement,
+                params: Vec<Value>,
+            ) -> Result<Option<Row>, WpError> {
+                Ok(Queryable::exec_first(self, stmt, params)?)
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "query_sync")]
+impl_executor!(mysql::Conn, mysql::PooledConn, mysql::Transaction<'_>);
+
+#[cfg(feature = "query_async")]
+mod r#async {
+    use mysql_async::prelude::Queryable;
+    use mysql_common::{Row, Value};
+
+    use crate::{sql::traits::r#async::Executor, WpError};
+
+    macro_rules! impl_executor {
+        ($($conn: ty),*) => {$(
+            impl Executor for $conn {
+                type Statement = mysql_async::Statement;
+
+                async fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+                    Ok(self.prep(query).await?)
+                }
+
+                async fn exec(
+                    &mut self,
+                    stmt: &Self::Statement,
+                    params: Vec<Value>,
+                ) -> Result<Vec<Row>, WpError> {
+                    Ok(Queryable::exec(self, stmt, params).await?)
+                }
+
+                async fn exec_first(
+                    &mut self,
+                    stmt: &Self::Statement,
+                    params: Vec<Value>,
+                ) -> Result<Option<Row>, WpError> {
+                    Ok(Queryable::exec_first(self, stmt, params).await?)
+                }
+            }
+        )*};
+    }
+
+    impl_executor!(mysql_async::Conn, mysql_async::Transaction<'_>);
+}
+
+#[cfg(feature = "query_sqlx")]
+mod sqlx_mysql {
+    use std::sync::Arc;
+
+    use mysql_common::{
+        constants::{ColumnFlags, ColumnType},
+        io::ParseBuf,
+        packets::Column,
+        proto::MyDeserialize,
+        row::new_row,
+        value::{BinValue, ValueDeserializer},
+        Row, Value,
+    };
+    use sqlx::{
+        mysql::{MySqlArguments, MySqlRow},
+        query::Query,
+        Column as _, MySql, MySqlConnection, Row as _, TypeInfo,
+    };
+
+    use crate::{sql::traits::r#async::Executor, WpError};
+
+    /// Statements are prepared and cached by the connection, so the SQL is kept to execute them.
+    impl Executor for MySqlConnection {
+        type Statement = String;
+
+        async fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+            sqlx::Executor::prepare(&mut *self, query).await?;
+
+            Ok(query.to_string())
+        }
+
+        async fn exec(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> Result<Vec<Row>, WpError> {
+            let rows = bind_all(sqlx::query(stmt), params)
+                .fetch_all(&mut *self)
+                .await?;
+
+            rows_from_sqlx(&rows)
+        }
+
+        async fn exec_first(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> Result<Option<Row>, WpError> {
+            let row = bind_all(sqlx::query(stmt), params)
+                .fetch_optional(&mut *self)
+                .await?;
+
+            Ok(rows_from_sqlx(row.as_slice())?.pop())
+        }
+    }
+
+    fn bind_all(
+        mut query: Query<'_, MySql, MySqlArguments>,
+        params: Vec<Value>,
+    ) -> Query<'_, MySql, MySqlArguments> {
+        for value in params {
+            query = match value {
+                Value::NULL => query.bind(None::<String>),
+                Value::Bytes(bytes) => match String::from_utf8(bytes) {
+                    Ok(text) => query.bind(text),
+                    Err(err) => query.bind(err.into_bytes()),
+                },
+                Value::Int(n) => query.bind(n),
+                Value::UInt(n) => query.bind(n),
+                Value::Float(n) => query.bind(n),
+                Value::Double(n) => query.bind(n),
+                Value::Date(y, m, d, h, i, s, us) => {
+                    query.bind(format!("{y:04}-{m:02}-{d:02} {h:02}:{i:02}:{s:02}.{us:06}"))
+                }
+                Value::Time(neg, d, h, i, s, us) => query.bind(format!(
+                    "{}{:02}:{i:02}:{s:02}.{us:06}",
+                    if neg { "-" } else { "" },
+                    d * 24 + u32::from(h)
+                )),
+            };
+        }
+
+        query
+    }
+
+    /// Converts the rows of sqlx to `mysql_common` rows, so they are mapped like the rows of the mysql drivers.
+    fn rows_from_sqlx(rows: &[MySqlRow]) -> Result<Vec<Row>, WpError> {
+        let Some(first) = rows.first() else {
+            return Ok(vec![]);
+        };
+
+        let types: Vec<(ColumnType, ColumnFlags)> = first
+            .columns()
+            .iter()
+            .map(|col| column_type(col.type_info().name()))
+            .collect();
+        let columns: Arc<[Column]> = first
+            .columns()
+            .iter()
+            .zip(&types)
+            .map(|(col, (col_type, flags))| {
+                Column::new(*col_type)
+                    .with_name(col.name().as_bytes())
+                    .with_flags(*flags)
+            })
+            .collect();
+
+        rows.iter()
+            .map(|row| {
+                let values = types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (col_type, flags))| decode_value(row, i, *col_type, *flags))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(new_row(values, columns.clone()))
+            })
+            .collect()
+    }
+
+    fn column_type(type_name: &str) -> (ColumnType, ColumnFlags) {
+        let unsigned = if type_name.ends_with("UNSIGNED") {
+            ColumnFlags::UNSIGNED_FLAG
+        } else {
+            ColumnFlags::empty()
+        };
+
+        let col_type = match type_name.trim_end_matches(" UNSIGNED") {
+            "BOOLEAN" | "TINYINT" | "SMALLINT" | "INT" | "MEDIUMINT" | "BIGINT" => {
+                ColumnType::MYSQL_TYPE_LONGLONG
+            }
+            "YEAR" => ColumnType::MYSQL_TYPE_YEAR,
+            "BIT" => ColumnType::MYSQL_TYPE_BIT,
+            "FLOAT" => ColumnType::MYSQL_TYPE_FLOAT,
+            "DOUBLE" => ColumnType::MYSQL_TYPE_DOUBLE,
+            "DATETIME" | "TIMESTAMP" => ColumnType::MYSQL_TYPE_DATETIME,
+            "DATE" => ColumnType::MYSQL_TYPE_DATE,
+            "TIME" => ColumnType::MYSQL_TYPE_TIME,
+            "NULL" => ColumnType::MYSQL_TYPE_NULL,
+            _ => ColumnType::MYSQL_TYPE_VAR_STRING,
+        };
+
+        (col_type, unsigned)
+    }
+
+    fn decode_value(
+        row: &MySqlRow,
+        i: usize,
+        col_type: ColumnType,
+        flags: ColumnFlags,
+    ) -> Result<Value, sqlx::Error> {
+        Ok(match col_type {
+            ColumnType::MYSQL_TYPE_LONGLONG if flags.contains(ColumnFlags::UNSIGNED_FLAG) => row
+                .try_get_unchecked::<Option<u64>, _>(i)?
+                .map_or(Value::NULL, Value::UInt),
+            ColumnType::MYSQL_TYPE_YEAR | ColumnType::MYSQL_TYPE_BIT => row
+                .try_get_unchecked::<Option<u64>, _>(i)?
+                .map_or(Value::NULL, Value::UInt),
+            ColumnType::MYSQL_TYPE_LONGLONG => row
+                .try_get_unchecked::<Option<i64>, _>(i)?
+                .map_or(Value::NULL, Value::Int),
+            ColumnType::MYSQL_TYPE_FLOAT => row
+                .try_get_unchecked::<Option<f32>, _>(i)?
+                .map_or(Value::NULL, Value::Float),
+            ColumnType::MYSQL_TYPE_DOUBLE => row
+                .try_get_unchecked::<Option<f64>, _>(i)?
+                .map_or(Value::NULL, Value::Double),
+            ColumnType::MYSQL_TYPE_DATETIME
+            | ColumnType::MYSQL_TYPE_DATE
+            | ColumnType::MYSQL_TYPE_TIME => row
+                .try_get_unchecked::<Option<Vec<u8>>, _>(i)?
+                .map(|raw| temporal_value(col_type, &raw))
+                .transpose()?
+                .unwrap_or(Value::NULL),
+            ColumnType::MYSQL_TYPE_NULL => Value::NULL,
+            _ => row
+                .try_get_unchecked::<Option<Vec<u8>>, _>(i)?
+                .map_or(Value::NULL, Value::Bytes),
+        })
+    }
+
+    /// Decodes a date or time in the binary protocol the way `mysql_common` does, as chrono types can
+    /// hold neither zero dates, i.e. `0000-00-00 00:00:00`, nor negative times or times of more than 24 hours.
+    fn temporal_value(col_type: ColumnType, raw: &[u8]) -> Result<Value, sqlx::Error> {
+        ValueDeserializer::<BinValue>::deserialize(
+            (col_type, ColumnFlags::empty()),
+            &mut ParseBuf(raw),
+        )
+        .map(|value| value.0)
+        .map_err(|err| sqlx::Error::Decode(Box::new(err)))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn decodes_zero_dates() {
+            let value = temporal_value(ColumnType::MYSQL_TYPE_DATETIME, &[0]).unwrap();
+
+            assert_eq!(value, Value::Date(0, 0, 0, 0, 0, 0, 0));
+        }
+
+        #[test]
+        fn decodes_dates() {
+            let raw = [7, 0xE8, 0x07, 2, 29, 13, 5, 9];
+            let value = temporal_value(ColumnType::MYSQL_TYPE_DATETIME, &raw).unwrap();
+
+            assert_eq!(value, Value::Date(2024, 2, 29, 13, 5, 9, 0));
+        }
+
+        #[test]
+        fn decodes_negative_times_over_a_day() {
+            // -2 days 03:04:05
+            let raw = [8, 1, 2, 0, 0, 0, 3, 4, 5];
+            let value = temporal_value(ColumnType::MYSQL_TYPE_TIME, &raw).unwrap();
+
+            assert_eq!(value, Value::Time(true, 2, 3, 4, 5, 0));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use mysql_common::{
+    constants::{ColumnFlags, ColumnType},
+    packets::Column,
+    row::new_row,
+    Row, Value,
+};
+
+use super::traits::Executor;
+use crate::WpError;
+
+/// An executor serving canned rows instead of querying a database, to unit test code using this crate.
+///
+/// Every executed statement returns the next queued rows, or no rows once the queue is empty.
+///
+/// # Example
+/// ```rust
+/// use wp_query_rs::{Executor, FakeExecutor};
+///
+/// let mut fake = FakeExecutor::new();
+/// fake.push_rows(vec![FakeExecutor::row(&[("option_value", "Blog".into())])]);
+///
+/// let name: Option<String> = fake
+///     .fetch_first("SELECT option_value FROM wp_options WHERE option_name = ?", vec!["blogname".into()])
+///     .unwrap();
+///
+/// assert_eq!(name.as_deref(), Some("Blog"));
+/// assert_eq!(fake.executed()[0].1, vec!["blogname".into()]);
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeExecutor {
+    results: VecDeque<Vec<Row>>,
+    executed: Vec<(String, Vec<Value>)>,
+}
+
+impl FakeExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the rows returned by the next executed statement.
+    pub fn push_rows(&mut self, rows: Vec<Row>) {
+        self.results.push_back(rows);
+    }
+
+    /// Builds a row with the named columns, e.g. `FakeExecutor::row(&[("ID", 1u64.into())])`.
+    pub fn row(columns: &[(&str, Value)]) -> Row {
+        let (names, values): (Vec<&str>, Vec<Value>) = columns.iter().cloned().unzip();
+
+        let columns: Arc<[Column]> = names
+            .iter()
+            .zip(&values)
+            .map(|(name, value)| column(name, value))
+            .collect();
+
+        new_row(values, columns)
+    }
+
+    /// The executed statements with their values, in order.
+    pub fn executed(&self) -> &[(String, Vec<Value>)] {
+        &self.executed
+    }
+
+    fn next_rows(&mut self, stmt: &str, params: Vec<Value>) -> Vec<Row> {
+        self.executed.push((stmt.to_string(), params));
+
+        self.results.pop_front().unwrap_or_default()
+    }
+}
+
+impl Executor for FakeExecutor {
+    type Statement = String;
+
+    fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+        Ok(query.to_string())
+    }
+
+    fn exec(&mut self, stmt: &Self::Statement, params: Vec<Value>) -> Result<Vec<Row>, WpError> {
+        Ok(self.next_rows(stmt, params))
+    }
+
+    fn exec_first(
+        &mut self,
+        stmt: &Self::Statement,
+        params: Vec<Value>,
+    ) -> Result<Option<Row>, WpError> {
+        Ok(self.next_rows(stmt, params).into_iter().next())
+    }
+}
+
+#[cfg(feature = "query_async")]
+impl super::traits::r#async::Executor for FakeExecutor {
+    type Statement = String;
+
+    async fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+        Ok(query.to_string())
+    }
+
+    async fn exec(
+        &mut self,
+        stmt: &Self::Statement,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>, WpError> {
+        Ok(self.next_rows(stmt, params))
+    }
+
+    async fn exec_first(
+        &mut self,
+        stmt: &Self::Statement,
+        params: Vec<Value>,
+    ) -> Result<Option<Row>, WpError> {
+        Ok(self.next_rows(stmt, params).into_iter().next())
+    }
+}
+
+fn column(name: &str, value: &Value) -> Column {
+    let (col_type, flags) = match value {
+        Value::NULL => (ColumnType::MYSQL_TYPE_NULL, ColumnFlags::empty()),
+        Value::Bytes(_) => (ColumnType::MYSQL_TYPE_VAR_STRING, ColumnFlags::empty()),
+        Value::Int(_) => (ColumnType::MYSQL_TYPE_LONGLONG, ColumnFlags::empty()),
+        Value::UInt(_) => (ColumnType::MYSQL_TYPE_LONGLONG, ColumnFlags::UNSIGNED_FLAG),
+        Value::Float(_) => (ColumnType::MYSQL_TYPE_FLOAT, ColumnFlags::empty()),
+        Value::Double(_) => (ColumnType::MYSQL_TYPE_DOUBLE, ColumnFlags::empty()),
+        Value::Date(..) => (ColumnType::MYSQL_TYPE_DATETIME, ColumnFlags::empty()),
+        Value::Time(..) => (ColumnType::MYSQL_TYPE_TIME, ColumnFlags::empty()),
+    };
+
+    Column::new(col_type)
+        .with_name(name.as_bytes())
+        .with_flags(flags)
+}
+
+/// A row of `wp_posts` with the given ID, for tests.
+#[cfg(test)]
+pub fn post_row(id: u64) -> Row {
+    let text = |value: &str| Value::from(value);
+
+    FakeExecutor::row(&[
+        ("ID", id.into()),
+        ("post_author", 1u64.into()),
+        ("post_content", text("Content")),
+        ("post_title", text("Title")),
+        ("post_excerpt", text("")),
+        ("post_status", text("publish")),
+        ("comment_status", text("open")),
+        ("ping_status", text("open")),
+        ("post_password", text("")),
+        ("post_name", text("title")),
+        ("to_ping", text("")),
+        ("pinged", text("")),
+        ("post_content_filtered", text("")),
+        ("post_parent", 0u64.into()),
+        ("guid", text("")),
+        ("menu_order", 0i64.into()),
+        ("post_type", text("post")),
+        ("post_mime_type", text("")),
+        ("comment_count", 0u64.into()),
+    ])
+}
+
+/// A row of `wp_comments` with the given ID and parent, for tests.
+#[cfg(all(test, feature = "query_sync"))]
+pub fn comment_row(id: u64, parent: u64) -> Row {
+    let text = |value: &str| Value::from(value);
+
+    FakeExecutor::row(&[
+        ("comment_ID", id.into()),
+        ("comment_post_ID", 1u64.into()),
+        ("comment_author", text("Author")),
+        ("comment_author_email", text("")),
+        ("comment_author_url", text("")),
+        ("comment_content", text("Comment")),
+        ("comment_approved", text("1")),
+        ("comment_agent", text("")),
+        ("comment_type", text("comment")),
+        ("comment_parent", parent.into()),
+        ("user_id", 0u64.into()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_queued_rows_in_order() {
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![
+            FakeExecutor::row(&[("ID", 1u64.into())]),
+            FakeExecutor::row(&[("ID", 2u64.into())]),
+        ]);
+        fake.push_rows(vec![FakeExecutor::row(&[("ID", 3u64.into())])]);
+
+        let ids: Vec<u64> = fake.fetch("SELECT ID FROM wp_posts", vec![]).unwrap();
+        assert_eq!(ids, vec![1, 2]);
+
+        let id: Option<u64> = fake.fetch_first("SELECT ID FROM wp_posts", vec![]).unwrap();
+        assert_eq!(id, Some(3));
+
+        let ids: Vec<u64> = fake.fetch("SELECT ID FROM wp_posts", vec![]).unwrap();
+        assert!(ids.is_empty());
+        assert_eq!(fake.executed().len(), 3);
+    }
+
+    #[test]
+    fn rows_are_found_by_column_name() {
+        let mut row = FakeExecutor::row(&[("meta_key", "color".into()), ("post_id", 7u64.into())]);
+
+        assert_eq!(crate::sql::find_col::<u64>(&mut row, "post_id"), Some(7));
+        assert_eq!(
+            crate::sql::find_col::<String>(&mut row, "meta_key").as_deref(),
+            Some("color")
+        );
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn can_query_posts_without_database() {
+        use crate::{ParamBuilder, WpQuery};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![post_row(7)]);
+
+        let query = WpQuery::with_connection(&mut fake, ParamBuilder::new().p(7)).unwrap();
+
+        assert_eq!(query.posts.len(), 1);
+        assert_eq!(query.posts[0].ID, 7);
+        assert!(fake.executed()[0].0.contains("wp_posts"));
+        assert!(fake.executed()[0].1.contains(&7u64.into()));
+    }
+
+    #[cfg(feature = "query_async")]
+    #[tokio::test]
+    async fn can_query_posts_without_database_async() {
+        use crate::{r#async::WpQuery, ParamBuilder};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![post_row(7), post_row(8)]);
+
+        let query = WpQuery::with_connection(&mut fake, ParamBuilder::new())
+            .await
+            .unwrap();
+
+        assert_eq!(query.posts.len(), 2);
+        assert_eq!(query.posts[1].ID, 8);
+    }
+
+    #[test]
+    fn conversion_errors_are_decode_errors() {
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![FakeExecutor::row(&[("ID", "not a number".into())])]);
+
+        let res: Result<Vec<u64>, WpError> = fake.fetch("SELECT ID FROM wp_posts", vec![]);
+        assert!(matches!(res, Err(WpError::Decode(_))));
+    }
+}
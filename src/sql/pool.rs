@@ -1,44 +1,39 @@
 #[cfg(feature = "query_sync")]
 use mysql::Pool;
-#[cfg(feature = "query_async")]
-use mysql_async::Pool;
+#[cfg(feature = "query_sync")]
 use std::sync::OnceLock;
 
-use super::{build_opts_from_env, EnvVars};
+#[cfg(feature = "query_sync")]
+use super::{build_opts_from_env, table_prefix::set_table_prefix, EnvVars};
+#[cfg(feature = "query_sync")]
+use crate::WpError;
 
-// SAFETY okay to use OnceLock in async as there is no internal thread blocking
-// and OnceLock implements Sync
+#[cfg(feature = "query_sync")]
 static POOL_INSTANCE: OnceLock<Pool> = OnceLock::new();
 
 #[cfg(feature = "query_sync")]
-pub fn get_pool() -> &'static Pool {
-    POOL_INSTANCE.get_or_init(|| {
-        let env_vars = EnvVars::from_env();
-        let opts = build_opts_from_env(env_vars);
-
-        Pool::new(opts).expect("DatabaseConnectionFailed")
-    })
-}
+pub fn get_pool() -> Result<&'static Pool, WpError> {
+    if let Some(pool) = POOL_INSTANCE.get() {
+        return Ok(pool);
+    }
 
-#[cfg(feature = "query_async")]
-pub fn get_pool() -> &'static Pool {
-    POOL_INSTANCE.get_or_init(|| {
-        let env_vars = EnvVars::from_env();
-        let opts = build_opts_from_env(env_vars);
+    let env_vars = EnvVars::from_env();
+    let pool = Pool::new(build_opts_from_env(env_vars))?;
 
-        Pool::new(opts)
-    })
+    Ok(POOL_INSTANCE.get_or_init(|| pool))
 }
 
+#[cfg(feature = "query_sync")]
 pub struct PoolInit;
 
+#[cfg(feature = "query_sync")]
 impl PoolInit {
     /// Initializes the global mysql connection pool with options prepared by the package user.
     ///
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// use wp_query_rs::PoolInit;
-    /// use mysql_async::OptsBuilder;
+    /// use mysql::OptsBuilder;
     ///
     /// let opts = OptsBuilder::default()
     /// .user(Some("root"))
@@ -52,48 +47,110 @@ impl PoolInit {
     /// # Errors
     /// Will error if you call a WpQuery database function before initializing the database as the default functionality is to
     /// use environment variables to initialize a pool.
-    #[cfg(feature = "query_async")]
-    pub fn with_opts(opts: mysql_async::Opts) -> Result<(), Pool> {
-        POOL_INSTANCE.set(Pool::new(opts))
+    ///
+    /// # Panics
+    /// If the pool cannot be created with the options.
+    pub fn with_opts(opts: mysql::Opts) -> Result<(), Pool> {
+        POOL_INSTANCE.set(Pool::new(opts).expect("DatabaseConnectionFailed"))
     }
 
-    /// Initializes the global mysql connection pool with options prepared by the package user.
+    /// Initializes the global pool with a reference to an already configured pool.
+    pub fn with_pool(pool: &mysql::Pool) -> Result<(), Pool> {
+        // Cloning just creates a new Arc::clone to the actual pool provided, so there is no major performance penalty here.
+        // The reference provided in the params does not need to be 'static because Arc will hold on to the pool as long as necessary.
+        POOL_INSTANCE.set(pool.clone())
+    }
+
+    /// Sets the table prefix used for all queries, i.e. `$table_prefix` in wp-config.php.
     ///
     /// # Example
-    /// ```rust,ignore
+    /// ```rust
     /// use wp_query_rs::PoolInit;
-    /// use mysql::OptsBuilder;
     ///
-    /// let opts = OptsBuilder::default()
-    /// .user(Some("root"))
-    /// .ip_or_hostname("localhost")
-    /// .pass(Some("password"))
-    /// .db_name(Some("wordpress"));
-    ///
-    /// PoolInit::with_opts(opts.into());
+    /// PoolInit::with_table_prefix("site7_");
     /// ```
     ///
     /// # Errors
-    /// Will error if you call a WpQuery database function before initializing the database as the default functionality is to
-    /// use environment variables to initialize a pool.
-    #[cfg(feature = "query_sync")]
-    pub fn with_opts(opts: mysql::Opts) -> Result<(), Pool> {
-        POOL_INSTANCE.set(Pool::new(opts).expect("DatabaseConnectionFailed"))
+    /// Will error if the prefix contains characters other than letters, numbers and underscores,
+    /// or if called after a query was built, as the default functionality is to use the WORDPRESS_TABLE_PREFIX
+    /// environment variable, or `wp_` if it is not set.
+    pub fn with_table_prefix(prefix: &str) -> Result<(), WpError> {
+        set_table_prefix(prefix.to_string())
     }
+}
 
-    /// Initializes the global pool with a reference to an already configured pool.
-    #[cfg(feature = "query_async")]
-    pub fn with_pool(pool: &mysql_async::Pool) -> Result<(), Pool> {
-        // Cloning just creates a new Arc::clone to the actual pool provided, so there is no major performance penalty here.
-        // The reference provided in the params does not need to be 'static because Arc will hold on to the pool as long as necessary.
-        POOL_INSTANCE.set(pool.clone())
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use mysql_async::Pool;
+    use std::sync::OnceLock;
+
+    use crate::{
+        sql::{env_vars::EnvVars, r#async::build_opts_from_env, table_prefix::set_table_prefix},
+        WpError,
+    };
+
+    // SAFETY okay to use OnceLock in async as there is no internal thread blocking
+    // and OnceLock implements Sync
+    static POOL_INSTANCE: OnceLock<Pool> = OnceLock::new();
+
+    pub fn get_pool() -> Result<&'static Pool, WpError> {
+        if let Some(pool) = POOL_INSTANCE.get() {
+            return Ok(pool);
+        }
+
+        let env_vars = EnvVars::from_env();
+        let opts = build_opts_from_env(env_vars)?;
+
+        Ok(POOL_INSTANCE.get_or_init(|| Pool::new(opts)))
     }
 
-    /// Initializes the global pool with a reference to an already configured pool.
-    #[cfg(feature = "query_sync")]
-    pub fn with_pool(pool: &mysql::Pool) -> Result<(), Pool> {
-        // Cloning just creates a new Arc::clone to the actual pool provided, so there is no major performance penalty here.
-        // The reference provided in the params does not need to be 'static because Arc will hold on to the pool as long as necessary.
-        POOL_INSTANCE.set(pool.clone())
+    /// Initializes the global pool of the async API, which is separate from the pool of the blocking API.
+    pub struct PoolInit;
+
+    impl PoolInit {
+        /// Initializes the global mysql connection pool with options prepared by the package user.
+        ///
+        /// # Example
+        /// ```rust
+        /// use wp_query_rs::r#async::PoolInit;
+        /// use mysql_async::OptsBuilder;
+        ///
+        /// let opts = OptsBuilder::default()
+        /// .user(Some("root"))
+        /// .ip_or_hostname("localhost")
+        /// .pass(Some("password"))
+        /// .db_name(Some("wordpress"));
+        ///
+        /// PoolInit::with_opts(opts.into());
+        /// ```
+        ///
+        /// # Errors
+        /// Will error if you call a WpQuery database function before initializing the database as the default functionality is to
+        /// use environment variables to initialize a pool.
+        pub fn with_opts(opts: mysql_async::Opts) -> Result<(), Pool> {
+            POOL_INSTANCE.set(Pool::new(opts))
+        }
+
+        /// Initializes the global pool with a reference to an already configured pool.
+        pub fn with_pool(pool: &mysql_async::Pool) -> Result<(), Pool> {
+            // Cloning just creates a new Arc::clone to the actual pool provided, so there is no major performance penalty here.
+            // The reference provided in the params does not need to be 'static because Arc will hold on to the pool as long as necessary.
+            POOL_INSTANCE.set(pool.clone())
+        }
+
+        /// Sets the table prefix used for all queries, shared with the blocking API.
+        ///
+        /// # Example
+        /// ```rust
+        /// use wp_query_rs::r#async::PoolInit;
+        ///
+        /// PoolInit::with_table_prefix("site7_");
+        /// ```
+        ///
+        /// # Errors
+        /// Will error if the prefix is invalid or if called after a query was built.
+        pub fn with_table_prefix(prefix: &str) -> Result<(), WpError> {
+            set_table_prefix(prefix.to_string())
+        }
     }
 }
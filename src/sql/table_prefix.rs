@@ -0,0 +1,130 @@
+use std::sync::OnceLock;
+
+use super::env_vars::EnvVars;
+use crate::WpError;
+
+/// The table prefix used by a default WordPress installation.
+pub const DEFAULT_TABLE_PREFIX: &str = "wp_";
+
+// Set once for the life of the application, same as the global pool
+static TABLE_PREFIX: OnceLock<String> = OnceLock::new();
+
+/// Gets the table prefix used when building queries, i.e. `$table_prefix` in wp-config.php.
+///
+/// Will use the WORDPRESS_TABLE_PREFIX environment variable if the prefix was not set before the first call,
+/// falling back to `wp_`.
+///
+/// # Panics
+/// When WORDPRESS_TABLE_PREFIX contains characters other than letters, numbers and underscores.
+/// Connections of the global pool check the prefix first, returning an error instead.
+#[cfg_attr(
+    not(any(feature = "query_sync", feature = "query_async")),
+    allow(dead_code)
+)]
+pub fn get_table_prefix() -> &'static str {
+    try_get_table_prefix().expect("InvalidTablePrefix")
+}
+
+/// Like [`get_table_prefix`], returning an error instead of panicking if WORDPRESS_TABLE_PREFIX is invalid.
+pub fn try_get_table_prefix() -> Result<&'static str, WpError> {
+    if let Some(prefix) = TABLE_PREFIX.get() {
+        return Ok(prefix);
+    }
+
+    let prefix = EnvVars::from_env()
+        .table_prefix
+        .unwrap_or(String::from(DEFAULT_TABLE_PREFIX));
+    validate_table_prefix(&prefix)?;
+
+    Ok(TABLE_PREFIX.get_or_init(|| prefix))
+}
+
+/// Sets the global table prefix.
+///
+/// # Errors
+/// When the prefix is invalid, see [`validate_table_prefix`], or the table prefix was already set or read from the environment.
+pub fn set_table_prefix(prefix: String) -> Result<(), WpError> {
+    validate_table_prefix(&prefix)?;
+
+    TABLE_PREFIX.set(prefix).map_err(|prefix| {
+        WpError::InvalidParams(format!(
+            "table prefix {prefix} cannot be set, the table prefix was already set"
+        ))
+    })
+}
+
+/// The table prefix of a query, or the global prefix if none was given.
+///
+/// # Errors
+/// When the prefix contains characters other than letters, numbers and underscores.
+pub fn table_prefix_or_global(prefix: Option<&str>) -> Result<&str, WpError> {
+    match prefix {
+        Some(prefix) => {
+            validate_table_prefix(prefix)?;
+
+            Ok(prefix)
+        }
+        None => try_get_table_prefix(),
+    }
+}
+
+/// Checks the prefix only contains letters, numbers and underscores like wp-config.php does,
+/// as it is written into the SQL statements as is.
+///
+/// # Errors
+/// When the prefix contains any other character.
+pub fn validate_table_prefix(prefix: &str) -> Result<(), WpError> {
+    if prefix
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(WpError::InvalidParams(String::from(
+            "table prefix can only contain numbers, letters, and underscores",
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cannot_set_prefix_after_init() {
+        let prefix = get_table_prefix();
+
+        assert!(set_table_prefix(String::from("site7_")).is_err());
+        assert_eq!(get_table_prefix(), prefix);
+    }
+
+    #[test]
+    fn only_letters_numbers_and_underscores_are_valid() {
+        assert!(validate_table_prefix("wp_").is_ok());
+        assert!(validate_table_prefix("Site_7_").is_ok());
+
+        for prefix in ["wp-", "wp_ ", "wp_;DROP TABLE x;", "wp`", "wp_é"] {
+            assert!(matches!(
+                validate_table_prefix(prefix),
+                Err(WpError::InvalidParams(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_prefix_is_not_set() {
+        assert!(matches!(
+            set_table_prefix(String::from("wp_'; --")),
+            Err(WpError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_query_prefix_is_an_error() {
+        assert!(matches!(
+            table_prefix_or_global(Some("wp_posts;")),
+            Err(WpError::InvalidParams(_))
+        ));
+        assert_eq!(table_prefix_or_global(Some("site7_")).unwrap(), "site7_");
+    }
+}
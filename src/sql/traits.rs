@@ -1,30 +1,251 @@
-#[cfg(feature = "query_async")]
-use std::{future::Future, pin::Pin};
+use mysql_common::{prelude::FromRow, Row, Value};
+
+use crate::WpError;
 
 #[cfg(feature = "query_sync")]
 pub trait Insertable: Into<mysql::Params> {
     /// Consumes the object and inserts into the database.
     /// Returns the ID of the inserted object.
-    fn insert(self) -> Result<u64, mysql::Error>;
+    fn insert(self) -> Result<u64, WpError>;
 
     /// Consumes the iterable and inserts objects into database.
     /// This has better performance as it reuses the prepared statement.
-    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), mysql::Error>
+    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), WpError>
     where
         Self: Sized;
 }
 
-#[cfg(feature = "query_async")]
-pub trait Insertable: Into<mysql_async::Params> {
-    /// Consumes the object and inserts into the database.
-    /// Returns the ID of the inserted object.
-    fn insert(self) -> Pin<Box<dyn Future<Output = Result<u64, mysql_async::Error>>>>;
+/// Runs the statements of the queries on a database backend, e.g. a `mysql::Conn` or a [`crate::FakeExecutor`].
+///
+/// Rows are returned as `mysql_common` rows whatever the backend, so they are mapped to posts, meta,
+/// users and rewrite rules the same way.
+pub trait Executor {
+    /// A statement prepared by the backend.
+    type Statement;
 
-    /// Consumes the iterable and inserts objects into database.
-    /// This has better performance as it reuses the prepared statement.
-    fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), mysql_async::Error>>>>
+    /// Prepares a statement with `?` placeholders.
+    ///
+    /// # Errors
+    /// When the backend fails to prepare the statement.
+    fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError>;
+
+    /// Executes a prepared statement with the positional values, returning all rows.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    fn exec(&mut self, stmt: &Self::Statement, params: Vec<Value>) -> Result<Vec<Row>, WpError>;
+
+    /// Executes a prepared statement with the positional values, returning the first row.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    fn exec_first(
+        &mut self,
+        stmt: &Self::Statement,
+        params: Vec<Value>,
+    ) -> Result<Option<Row>, WpError>;
+
+    /// Prepares and executes the query, converting all rows.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query or a row cannot be converted.
+    fn fetch<T: FromRow>(&mut self, query: &str, params: Vec<Value>) -> Result<Vec<T>, WpError>
     where
-        T: IntoIterator<Item = Self> + Send + 'static,
-        T::IntoIter: Send,
-        Self: Sized;
+        Self: Sized,
+    {
+        let stmt = self.prepare(query)?;
+
+        self.exec(&stmt, params)?
+            .into_iter()
+            .map(|row| Ok(T::from_row_opt(row)?))
+            .collect()
+    }
+
+    /// Prepares and executes the query, converting the first row.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query or the row cannot be converted.
+    fn fetch_first<T: FromRow>(
+        &mut self,
+        query: &str,
+        params: Vec<Value>,
+    ) -> Result<Option<T>, WpError>
+    where
+        Self: Sized,
+    {
+        let stmt = self.prepare(query)?;
+
+        self.exec_first(&stmt, params)?
+            .map(|row| Ok(T::from_row_opt(row)?))
+            .transpose()
+    }
+
+    /// Prepares and executes a statement which returns no rows, e.g. an `INSERT` or `UPDATE`.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    fn execute(&mut self, query: &str, params: Vec<Value>) -> Result<(), WpError>
+    where
+        Self: Sized,
+    {
+        let stmt = self.prepare(query)?;
+
+        self.exec(&stmt, params)?;
+
+        Ok(())
+    }
+}
+
+impl<E: Executor> Executor for &mut E {
+    type Statement = E::Statement;
+
+    fn prepare(&mut self, query: &str) -> Result<Self::Statement, WpError> {
+        (**self).prepare(query)
+    }
+
+    fn exec(&mut self, stmt: &Self::Statement, params: Vec<Value>) -> Result<Vec<Row>, WpError> {
+        (**self).exec(stmt, params)
+    }
+
+    fn exec_first(
+        &mut self,
+        stmt: &Self::Statement,
+        params: Vec<Value>,
+    ) -> Result<Option<Row>, WpError> {
+        (**self).exec_first(stmt, params)
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{future::Future, pin::Pin};
+
+    use mysql_common::{prelude::FromRow, Row, Value};
+
+    use crate::WpError;
+
+    pub trait Insertable: Into<mysql_async::Params> {
+        /// Consumes the object and inserts into the database.
+        /// Returns the ID of the inserted object.
+        fn insert(self) -> Pin<Box<dyn Future<Output = Result<u64, WpError>>>>;
+
+        /// Consumes the iterable and inserts objects into database.
+        /// This has better performance as it reuses the prepared statement.
+        fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), WpError>>>>
+        where
+            T: IntoIterator<Item = Self> + Send + 'static,
+            T::IntoIter: Send,
+            Self: Sized;
+    }
+
+    /// Runs the statements of the queries on an async database backend, e.g. a `mysql_async::Conn`,
+    /// a `sqlx::MySqlConnection` or a [`crate::FakeExecutor`].
+    pub trait Executor: Send {
+        /// A statement prepared by the backend.
+        type Statement: Send + Sync;
+
+        /// Prepares a statement with `?` placeholders.
+        fn prepare(
+            &mut self,
+            query: &str,
+        ) -> impl Future<Output = Result<Self::Statement, WpError>> + Send;
+
+        /// Executes a prepared statement with the positional values, returning all rows.
+        fn exec(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Vec<Row>, WpError>> + Send;
+
+        /// Executes a prepared statement with the positional values, returning the first row.
+        fn exec_first(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Option<Row>, WpError>> + Send;
+
+        /// Prepares and executes the query, converting all rows.
+        fn fetch<T: FromRow>(
+            &mut self,
+            query: &str,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Vec<T>, WpError>> + Send
+        where
+            Self: Sized,
+        {
+            async move {
+                let stmt = self.prepare(query).await?;
+
+                self.exec(&stmt, params)
+                    .await?
+                    .into_iter()
+                    .map(|row| Ok(T::from_row_opt(row)?))
+                    .collect()
+            }
+        }
+
+        /// Prepares and executes the query, converting the first row.
+        fn fetch_first<T: FromRow>(
+            &mut self,
+            query: &str,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Option<T>, WpError>> + Send
+        where
+            Self: Sized,
+        {
+            async move {
+                let stmt = self.prepare(query).await?;
+
+                self.exec_first(&stmt, params)
+                    .await?
+                    .map(|row| Ok(T::from_row_opt(row)?))
+                    .transpose()
+            }
+        }
+
+        /// Prepares and executes a statement which returns no rows, e.g. an `INSERT` or `UPDATE`.
+        fn execute(
+            &mut self,
+            query: &str,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<(), WpError>> + Send
+        where
+            Self: Sized,
+        {
+            async move {
+                let stmt = self.prepare(query).await?;
+
+                self.exec(&stmt, params).await?;
+
+                Ok(())
+            }
+        }
+    }
+
+    impl<E: Executor> Executor for &mut E {
+        type Statement = E::Statement;
+
+        fn prepare(
+            &mut self,
+            query: &str,
+        ) -> impl Future<Output = Result<Self::Statement, WpError>> + Send {
+            (**self).prepare(query)
+        }
+
+        fn exec(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Vec<Row>, WpError>> + Send {
+            (**self).exec(stmt, params)
+        }
+
+        fn exec_first(
+            &mut self,
+            stmt: &Self::Statement,
+            params: Vec<Value>,
+        ) -> impl Future<Output = Result<Option<Row>, WpError>> + Send {
+            (**self).exec_first(stmt, params)
+        }
+    }
 }
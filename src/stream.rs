@@ -0,0 +1,487 @@
+use mysql_common::{prelude::FromRow, Row, Value};
+
+use crate::{
+    query_builder::{QueryAndValues, QueryBuilder},
+    wp_post::WpPost,
+    Params, WpError,
+};
+
+#[cfg(feature = "query_sync")]
+use crate::{sql::get_conn, sql::traits::Executor, WpQuery};
+#[cfg(feature = "query_sync")]
+use mysql::prelude::{BinQuery, Queryable, WithParams};
+
+fn post_from_row(row: Row) -> Result<WpPost, WpError> {
+    Ok(WpPost::from_row_opt(row)?)
+}
+
+/// The statement of a keyset stream and the ID to continue after, shared by the blocking and the async API.
+#[derive(Debug)]
+struct Keyset {
+    query: String,
+    values: Vec<Value>,
+    batch_size: u64,
+    done: bool,
+}
+
+impl Keyset {
+    fn new(params: Params, batch_size: u64) -> Result<Self, WpError> {
+        // An empty batch would never move past the last ID
+        let batch_size = batch_size.max(1);
+        let QueryAndValues(query, values) = QueryBuilder::new(params).keyset_query(batch_size)?;
+
+        Ok(Self {
+            query,
+            values,
+            batch_size,
+            done: false,
+        })
+    }
+
+    /// Maps the rows of a batch, continuing the next batch after the ID of the last post.
+    fn next_batch(&mut self, rows: Vec<Row>) -> Result<Vec<WpPost>, WpError> {
+        self.done = (rows.len() as u64) < self.batch_size;
+
+        let posts = rows
+            .into_iter()
+            .map(post_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(post) = posts.last() {
+            let after = self.values.len() - 2;
+            self.values[after] = Value::UInt(post.ID);
+        }
+
+        Ok(posts)
+    }
+}
+
+#[cfg(feature = "query_sync")]
+impl WpQuery {
+    /// Streams every post matching the query, reading one row at a time from the connection
+    /// instead of collecting all posts in memory.
+    ///
+    /// `page` and `posts_per_page` are ignored, use [`Iterator::take`] to limit the posts.
+    /// Sticky posts are not prepended and meta data is not loaded.
+    ///
+    /// The connection is taken from the global pool until the stream is dropped.
+    /// Dropping the stream early still reads the remaining rows, see [`WpQuery::stream_keyset`] to stop at any time.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use wp_query_rs::{ParamBuilder, WP_Query, PostType, PostQueryable};
+    ///
+    /// let params = ParamBuilder::new().post_type(PostType::Post);
+    ///
+    /// for post in WP_Query::stream(params)? {
+    ///     let post = post?;
+    ///     println!("{}", post.post_title);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid or an error occurs in the SQL query.
+    /// Rows failing to be read are returned as errors by the iterator.
+    pub fn stream<'a, T>(params: T) -> Result<PostStream<'static>, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let QueryAndValues(q, values) = QueryBuilder::new(params.into()).stream_query()?;
+
+        let result = q.with(mysql::Params::Positional(values)).run(get_conn()?)?;
+
+        Ok(PostStream { result })
+    }
+
+    /// Streams every post matching the query like [`WpQuery::stream`], on a mysql connection or transaction.
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid or an error occurs in the SQL query.
+    pub fn stream_with_connection<'a, 'c, T>(
+        conn: &'c mut impl Queryable,
+        params: T,
+    ) -> Result<PostStream<'c>, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        let QueryAndValues(q, values) = QueryBuilder::new(params.into()).stream_query()?;
+
+        let result = conn.exec_iter(q, values)?;
+
+        Ok(PostStream { result })
+    }
+
+    /// Streams every post matching the query in batches ordered by ID.
+    /// Each batch continues after the last ID of the previous one ("ID > last_seen") instead of scanning
+    /// past an OFFSET, so reading deep into a large table stays fast.
+    ///
+    /// Only `batch_size` posts are held in memory, and the stream can be dropped at any time.
+    /// The ordering, `page` and `posts_per_page` of the query are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let params = ParamBuilder::new().post_type(PostType::Any);
+    ///
+    /// for post in WP_Query::stream_keyset(params, 500)? {
+    ///     export(post?);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid or the statement fails to be prepared.
+    /// Failing batches are returned as errors by the iterator.
+    pub fn stream_keyset<'a, T>(
+        params: T,
+        batch_size: u64,
+    ) -> Result<KeysetStream<mysql::PooledConn>, WpError>
+    where
+        T: Into<Params<'a>>,
+    {
+        KeysetStream::new(get_conn()?, params.into(), batch_size)
+    }
+
+    /// Streams every post matching the query in batches ordered by ID like [`WpQuery::stream_keyset`],
+    /// with a mysql connection or any other [`Executor`].
+    ///
+    /// # Errors
+    /// When the table prefix of the params is invalid or the statement fails to be prepared.
+    pub fn stream_keyset_with_connection<'a, E, T>(
+        conn: &mut E,
+        params: T,
+        batch_size: u64,
+    ) -> Result<KeysetStream<&mut E>, WpError>
+    where
+        E: Executor,
+        T: Into<Params<'a>>,
+    {
+        KeysetStream::new(conn, params.into(), batch_size)
+    }
+}
+
+/// Posts read one row at a time from the result set of the query, see [`WpQuery::stream`].
+#[cfg(feature = "query_sync")]
+#[derive(Debug)]
+pub struct PostStream<'c> {
+    result: mysql::QueryResult<'c, 'c, 'c, mysql::Binary>,
+}
+
+#[cfg(feature = "query_sync")]
+impl Iterator for PostStream<'_> {
+    type Item = Result<WpPost, WpError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.result
+            .next()
+            .map(|row| row.map_err(WpError::from).and_then(post_from_row))
+    }
+}
+
+/// Posts read in batches ordered by ID, each continuing after the last ID of the previous batch,
+/// see [`WpQuery::stream_keyset`].
+#[cfg(feature = "query_sync")]
+pub struct KeysetStream<E: Executor> {
+    conn: E,
+    stmt: E::Statement,
+    keyset: Keyset,
+    batch: std::vec::IntoIter<WpPost>,
+}
+
+#[cfg(feature = "query_sync")]
+impl<E: Executor> KeysetStream<E> {
+    fn new(mut conn: E, params: Params, batch_size: u64) -> Result<Self, WpError> {
+        let keyset = Keyset::new(params, batch_size)?;
+        let stmt = conn.prepare(&keyset.query)?;
+
+        Ok(Self {
+            conn,
+            stmt,
+            keyset,
+            batch: Vec::new().into_iter(),
+        })
+    }
+}
+
+#[cfg(feature = "query_sync")]
+impl<E: Executor> Iterator for KeysetStream<E> {
+    type Item = Result<WpPost, WpError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(post) = self.batch.next() {
+            return Some(Ok(post));
+        }
+        if self.keyset.done {
+            return None;
+        }
+
+        let batch = self
+            .conn
+            .exec(&self.stmt, self.keyset.values.clone())
+            .and_then(|rows| self.keyset.next_batch(rows));
+
+        match batch {
+            Ok(posts) => {
+                self.batch = posts.into_iter();
+                self.batch.next().map(Ok)
+            }
+            Err(e) => {
+                self.keyset.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use futures_util::stream::{self, Stream};
+    use mysql_async::prelude::{Query, ToConnection, WithParams};
+
+    use super::{post_from_row, Keyset};
+    use crate::{
+        query_builder::{QueryAndValues, QueryBuilder},
+        r#async::WpQuery,
+        sql::{r#async::get_conn, traits::r#async::Executor},
+        wp_post::WpPost,
+        Params, WpError,
+    };
+
+    impl WpQuery {
+        /// Streams every post matching the query, reading one row at a time from the connection
+        /// instead of collecting all posts in memory.
+        ///
+        /// `page` and `posts_per_page` are ignored. Sticky posts are not prepended and meta data is not loaded.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// use futures_util::TryStreamExt;
+        /// use wp_query_rs::{r#async::WpQuery, ParamBuilder, PostQueryable, PostType};
+        ///
+        /// let posts = WpQuery::stream(ParamBuilder::new().post_type(PostType::Post)).await?;
+        /// futures_util::pin_mut!(posts);
+        ///
+        /// while let Some(post) = posts.try_next().await? {
+        ///     println!("{}", post.post_title);
+        /// }
+        /// ```
+        ///
+        /// # Errors
+        /// When the table prefix of the params is invalid or an error occurs in the SQL query.
+        /// Rows failing to be read are returned as errors by the stream.
+        pub async fn stream<'a, T>(
+            params: T,
+        ) -> Result<impl Stream<Item = Result<WpPost, WpError>> + Send + 'static, WpError>
+        where
+            T: Into<Params<'a>>,
+        {
+            let QueryAndValues(q, values) = QueryBuilder::new(params.into()).stream_query()?;
+
+            let result = q
+                .with(mysql_async::Params::Positional(values))
+                .run(get_conn().await?)
+                .await?;
+
+            Ok(posts(result))
+        }
+
+        /// Streams every post matching the query like [`WpQuery::stream`], on a mysql connection,
+        /// transaction or pool.
+        ///
+        /// # Errors
+        /// When the table prefix of the params is invalid or an error occurs in the SQL query.
+        pub async fn stream_with_connection<'a, 'c, 't: 'c, C, T>(
+            conn: C,
+            params: T,
+        ) -> Result<impl Stream<Item = Result<WpPost, WpError>> + Send + use<'c, 't, C, T>, WpError>
+        where
+            C: ToConnection<'c, 't> + 'c,
+            T: Into<Params<'a>>,
+        {
+            let QueryAndValues(q, values) = QueryBuilder::new(params.into()).stream_query()?;
+
+            let result = q
+                .with(mysql_async::Params::Positional(values))
+                .run(conn)
+                .await?;
+
+            Ok(posts(result))
+        }
+
+        /// Streams every post matching the query in batches ordered by ID.
+        /// Each batch continues after the last ID of the previous one ("ID > last_seen") instead of scanning
+        /// past an OFFSET, so reading deep into a large table stays fast.
+        ///
+        /// Only `batch_size` posts are held in memory. The ordering, `page` and `posts_per_page` of the query are ignored.
+        ///
+        /// # Errors
+        /// When the table prefix of the params is invalid or no connection can be taken from the global pool.
+        /// Failing batches are returned as errors by the stream.
+        pub async fn stream_keyset<'a, T>(
+            params: T,
+            batch_size: u64,
+        ) -> Result<impl Stream<Item = Result<WpPost, WpError>> + Send + 'static, WpError>
+        where
+            T: Into<Params<'a>>,
+        {
+            let keyset = Keyset::new(params.into(), batch_size)?;
+
+            Ok(keyset_posts(get_conn().await?, keyset))
+        }
+
+        /// Streams every post matching the query in batches ordered by ID like [`WpQuery::stream_keyset`],
+        /// with a mysql connection or any other [`Executor`].
+        ///
+        /// # Errors
+        /// When the table prefix of the params is invalid. Failing batches are returned as errors by the stream.
+        pub fn stream_keyset_with_connection<'a, 'c, E, T>(
+            conn: &'c mut E,
+            params: T,
+            batch_size: u64,
+        ) -> Result<impl Stream<Item = Result<WpPost, WpError>> + Send + 'c, WpError>
+        where
+            E: Executor,
+            T: Into<Params<'a>>,
+        {
+            Ok(keyset_posts(conn, Keyset::new(params.into(), batch_size)?))
+        }
+    }
+
+    fn posts<'c, 't: 'c>(
+        result: mysql_async::QueryResult<'c, 't, mysql_async::BinaryProtocol>,
+    ) -> impl Stream<Item = Result<WpPost, WpError>> + Send + use<'c, 't> {
+        stream::try_unfold(result, |mut result| async move {
+            match result.next().await? {
+                Some(row) => Ok(Some((post_from_row(row)?, result))),
+                None => Ok(None),
+            }
+        })
+    }
+
+    struct KeysetState<E: Executor> {
+        conn: E,
+        stmt: Option<E::Statement>,
+        keyset: Keyset,
+        batch: std::vec::IntoIter<WpPost>,
+    }
+
+    fn keyset_posts<E: Executor>(
+        conn: E,
+        keyset: Keyset,
+    ) -> impl Stream<Item = Result<WpPost, WpError>> + Send {
+        let state = KeysetState {
+            conn,
+            stmt: None,
+            keyset,
+            batch: Vec::new().into_iter(),
+        };
+
+        stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(post) = state.batch.next() {
+                    return Ok(Some((post, state)));
+                }
+                if state.keyset.done {
+                    return Ok(None);
+                }
+
+                let stmt = match state.stmt.take() {
+                    Some(stmt) => stmt,
+                    None => state.conn.prepare(&state.keyset.query).await?,
+                };
+                let rows = state.conn.exec(&stmt, state.keyset.values.clone()).await?;
+
+                state.stmt = Some(stmt);
+                state.batch = state.keyset.next_batch(rows)?.into_iter();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sql::fake::post_row, FakeExecutor, ParamBuilder};
+
+    #[test]
+    fn keyset_continues_after_last_id() {
+        let mut keyset = Keyset::new(ParamBuilder::new().into(), 2).unwrap();
+
+        let posts = keyset.next_batch(vec![post_row(3), post_row(8)]).unwrap();
+        assert_eq!(posts.len(), 2);
+        assert!(!keyset.done);
+        assert_eq!(keyset.values[keyset.values.len() - 2], Value::UInt(8));
+
+        keyset.next_batch(vec![post_row(9)]).unwrap();
+        assert!(keyset.done);
+    }
+
+    #[test]
+    fn keyset_batches_are_not_empty() {
+        let keyset = Keyset::new(ParamBuilder::new().into(), 0).unwrap();
+
+        assert_eq!(keyset.values.last(), Some(&Value::UInt(1)));
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn streams_posts_by_keyset() {
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![post_row(1), post_row(2)]);
+        fake.push_rows(vec![post_row(5), post_row(7)]);
+
+        let ids: Vec<u64> =
+            WpQuery::stream_keyset_with_connection(&mut fake, ParamBuilder::new(), 2)
+                .unwrap()
+                .map(|post| post.unwrap().ID)
+                .collect();
+
+        assert_eq!(ids, vec![1, 2, 5, 7]);
+        // The last batch was full, so an empty one ends the stream
+        let executed = fake.executed();
+        assert_eq!(executed.len(), 3);
+        assert!(executed[0].0.contains("wp_posts.ID > ?"));
+        assert_eq!(executed[1].1[executed[1].1.len() - 2], Value::UInt(2));
+        assert_eq!(executed[2].1[executed[2].1.len() - 2], Value::UInt(7));
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn keyset_stream_ends_after_error() {
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![FakeExecutor::row(&[("ID", "not a number".into())])]);
+
+        let mut posts =
+            WpQuery::stream_keyset_with_connection(&mut fake, ParamBuilder::new(), 2).unwrap();
+
+        assert!(matches!(posts.next(), Some(Err(WpError::Decode(_)))));
+        assert!(posts.next().is_none());
+    }
+
+    #[cfg(feature = "query_async")]
+    #[tokio::test]
+    async fn streams_posts_by_keyset_async() {
+        use futures_util::TryStreamExt;
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![post_row(1), post_row(2)]);
+        fake.push_rows(vec![post_row(5)]);
+
+        let posts: Vec<WpPost> = crate::r#async::WpQuery::stream_keyset_with_connection(
+            &mut fake,
+            ParamBuilder::new(),
+            2,
+        )
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(
+            posts.iter().map(|post| post.ID).collect::<Vec<_>>(),
+            vec![1, 2, 5]
+        );
+        assert_eq!(fake.executed().len(), 2);
+    }
+}
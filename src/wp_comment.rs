@@ -1,6 +1,14 @@
-mod arg_builder;
-mod comment_approved;
-mod comment_type;
+pub mod arg_builder;
+pub mod comment_approved;
+mod comment_query;
+pub mod comment_type;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod meta;
+mod query_builder;
+mod sql;
+mod thread;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod update;
 
 use std::net;
 
@@ -8,9 +16,12 @@ use mysql_common::time::PrimitiveDateTime;
 
 use crate::sql::date::{get_date_now, get_utc_date_now};
 
+pub use self::comment_query::{WpCommentQuery, WpCommentResults};
+#[cfg(feature = "query_sync")]
+pub use self::meta::{delete_comment_meta, get_comment_meta, update_comment_meta};
 use self::{comment_approved::CommentApproved, comment_type::CommentType};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(non_snake_case)]
 pub struct WpComment {
     pub comment_ID: u64,
@@ -18,7 +29,8 @@ pub struct WpComment {
     pub comment_author: String,
     pub comment_author_email: String,
     pub comment_author_url: String,
-    pub comment_author_IP: net::Ipv4Addr,
+    /// IPv4 or IPv6 address of the author, None if it was not saved or cannot be parsed.
+    pub comment_author_IP: Option<net::IpAddr>,
     pub comment_date: PrimitiveDateTime,
     pub comment_date_gmt: PrimitiveDateTime,
     pub comment_content: String,
@@ -39,7 +51,7 @@ impl WpComment {
             comment_author: String::new(),
             comment_author_email: String::new(),
             comment_author_url: String::new(),
-            comment_author_IP: net::Ipv4Addr::new(0, 0, 0, 0),
+            comment_author_IP: None,
             comment_date: get_date_now(),
             comment_date_gmt: get_utc_date_now(),
             comment_content: String::new(),
@@ -52,6 +64,72 @@ impl WpComment {
             children: None,
         }
     }
+
+    /// Replies to this comment. Empty if the comment has no replies or they were not loaded.
+    pub fn children(&self) -> &[WpComment] {
+        self.children.as_deref().unwrap_or_default()
+    }
+
+    /// Mutable access to the replies of this comment.
+    pub fn children_mut(&mut self) -> &mut [WpComment] {
+        self.children.as_deref_mut().unwrap_or_default()
+    }
+
+    /// Whether the replies were retrieved, i.e. this comment is within the depth of a threaded query.
+    pub fn has_children_loaded(&self) -> bool {
+        self.children.is_some()
+    }
+
+    /// Adds a reply to this comment.
+    pub fn add_child(&mut self, child: WpComment) {
+        self.children.get_or_insert_with(Vec::new).push(child);
+    }
+
+    /// Takes the replies out of this comment, leaving them unloaded.
+    pub fn take_children(&mut self) -> Vec<WpComment> {
+        self.children.take().unwrap_or_default()
+    }
+
+    /// All loaded replies to this comment and their replies, depth first.
+    pub fn descendants(&self) -> Vec<&WpComment> {
+        let mut descendants = vec![];
+
+        for child in self.children() {
+            descendants.push(child);
+            descendants.extend(child.descendants());
+        }
+
+        descendants
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::future::Future;
+
+    use crate::WpError;
+
+    pub use super::comment_query::r#async::WpCommentQuery;
+    pub use super::meta::r#async::{delete_comment_meta, get_comment_meta, update_comment_meta};
+
+    /// Async moderation of comments, see the blocking methods of [`super::WpComment`].
+    pub trait WpCommentExt {
+        /// Approves the comment like `wp_set_comment_status($id, 'approve')`.
+        fn approve(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Unapproves the comment, holding it for moderation.
+        fn hold(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Marks the comment as spam like `wp_spam_comment`, saving its current status in the `_wp_trash_meta_status` meta.
+        fn spam(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Moves the comment to the trash like `wp_trash_comment`, saving its current status in the `_wp_trash_meta_status` meta.
+        fn trash(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Permanently deletes the comment and its meta like `wp_delete_comment`.
+        /// Replies are moved to the comment's parent.
+        fn delete(self) -> impl Future<Output = Result<(), WpError>> + Send;
+    }
 }
 
 #[cfg(test)]
@@ -65,4 +143,25 @@ mod tests {
         assert_eq!(comment.user_id, 1);
         assert_eq!(comment.comment_approved, CommentApproved::Approved);
     }
+
+    #[test]
+    fn can_walk_children() {
+        let mut comment = WpComment::new(1);
+        assert!(!comment.has_children_loaded());
+        assert!(comment.children().is_empty());
+
+        let mut child = WpComment::new(1);
+        child.comment_ID = 2;
+        let mut grandchild = WpComment::new(1);
+        grandchild.comment_ID = 3;
+        child.add_child(grandchild);
+        comment.add_child(child);
+
+        assert_eq!(comment.children().len(), 1);
+        let ids: Vec<u64> = comment.descendants().iter().map(|c| c.comment_ID).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        assert_eq!(comment.take_children().len(), 1);
+        assert!(!comment.has_children_loaded());
+    }
 }
@@ -2,11 +2,11 @@ pub mod fields;
 pub mod hierarchical;
 pub mod orderby;
 
-use std::{collections::HashMap, ops::DerefMut};
+use std::ops::DerefMut;
 
 use crate::{
     sql::{cast_type::CastType, SqlCompareOperator},
-    DateQuery, MetaQuery, MetaRelation, PostStatus, SqlOrder,
+    DateQuery, MetaQueryTree, PostStatus, SqlOrder,
 };
 
 use self::{fields::Fields, hierarchical::Hierarchy, orderby::Orderby};
@@ -33,11 +33,9 @@ pub struct WpCommentQueryArgs {
     pub meta_compare: SqlCompareOperator,
     pub meta_type: CastType,
     /**
-     * HashMap key is relation, value is the meta query
-     * relation: The logical relationship between each inner meta_query array when there is more than one.
-     * Possible values are ‘AND’, ‘OR’. Do not use with a single inner meta_query array
+     * A single custom field clause, or nested groups of clauses each with their logical relationship, ‘AND’ or ‘OR’.
      */
-    pub meta_query: Option<HashMap<MetaRelation, Vec<MetaQuery>>>,
+    pub meta_query: Option<MetaQueryTree>,
     pub number: Option<u64>,
     pub paged: Option<u64>,
     pub offset: Option<u64>,
@@ -63,6 +61,8 @@ pub struct WpCommentQueryArgs {
     pub comment_type__not_in: Option<Vec<CommentType>>,
     pub user_id: Option<u64>,
     pub hierarchical: Option<Hierarchy>,
+    /// Overrides the global table prefix for this query only. Writes always use the global prefix.
+    pub table_prefix: Option<String>,
 }
 
 pub struct WpCommentArgBuilder {
@@ -114,11 +114,24 @@ impl WpCommentArgBuilder {
                 comment_type__not_in: None,
                 user_id: None,
                 hierarchical: None,
+                table_prefix: None,
             },
         }
     }
 }
 
+impl Default for WpCommentArgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<WpCommentArgBuilder> for WpCommentQueryArgs {
+    fn from(value: WpCommentArgBuilder) -> Self {
+        value.args
+    }
+}
+
 impl std::ops::Deref for WpCommentArgBuilder {
     type Target = WpCommentQueryArgs;
 
@@ -1,6 +1,6 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Hierarchy {
-    /// Retrieves comment tree to the nth depth.
+    /// Retrieves comment tree to the nth depth. Top level comments are at depth 1, 0 retrieves all descendants.
     Threaded(u8),
     /// 'flat' returns a flat array of found comments plus their children.
     Flat,
@@ -8,5 +8,32 @@ pub enum Hierarchy {
     False,
 }
 
+impl Hierarchy {
+    /// Whether descendants of the matched comments are retrieved.
+    pub fn is_hierarchical(&self) -> bool {
+        !matches!(self, Self::False)
+    }
+
+    /// Levels of descendants to retrieve below the matched comments. None if unlimited.
+    pub fn descendant_depth(&self) -> Option<u8> {
+        match self {
+            Self::Threaded(0) | Self::Flat => None,
+            Self::Threaded(n) => Some(n - 1),
+            Self::False => Some(0),
+        }
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_get_descendant_depth() {
+        assert_eq!(Hierarchy::Threaded(3).descendant_depth(), Some(2));
+        assert_eq!(Hierarchy::Threaded(1).descendant_depth(), Some(0));
+        assert_eq!(Hierarchy::Threaded(0).descendant_depth(), None);
+        assert_eq!(Hierarchy::Flat.descendant_depth(), None);
+        assert_eq!(Hierarchy::False.descendant_depth(), Some(0));
+    }
+}
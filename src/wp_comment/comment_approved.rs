@@ -1,13 +1,55 @@
-#[derive(Debug, PartialEq, Eq)]
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommentApproved {
     Approved,
     Hold,
+    Spam,
+    Trash,
     All,
     Custom(String),
 }
 
+impl Display for CommentApproved {
+    /// Writes the value stored in the comment_approved column.
+    /// All is not stored in the database and includes both approved and held comments when querying.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Approved => "1",
+                Self::Hold => "0",
+                Self::Spam => "spam",
+                Self::Trash => "trash",
+                Self::All => "all",
+                Self::Custom(s) => s.as_str(),
+            }
+        )
+    }
+}
+
+impl From<String> for CommentApproved {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "1" | "approve" => Self::Approved,
+            "0" | "hold" => Self::Hold,
+            "spam" => Self::Spam,
+            "trash" => Self::Trash,
+            "all" => Self::All,
+            _ => Self::Custom(value),
+        }
+    }
+}
+
+impl mysql_common::prelude::FromValue for CommentApproved {
+    type Intermediate = String;
+}
+
 #[cfg(test)]
 mod tests {
+    use mysql_common::prelude::FromValue;
+
     use super::*;
 
     #[test]
@@ -19,4 +61,22 @@ mod tests {
             _ => panic!("Did not match!"),
         }
     }
+
+    #[test]
+    fn can_convert_from_mysql_value() {
+        let value = mysql_common::Value::Bytes(String::from("1").into_bytes());
+        assert_eq!(
+            CommentApproved::from_value(value),
+            CommentApproved::Approved
+        );
+
+        let value = mysql_common::Value::Bytes(String::from("spam").into_bytes());
+        assert_eq!(CommentApproved::from_value(value), CommentApproved::Spam);
+
+        let value = mysql_common::Value::Bytes(String::from("post-trashed").into_bytes());
+        assert_eq!(
+            CommentApproved::from_value(value),
+            CommentApproved::Custom(String::from("post-trashed"))
+        );
+    }
 }
@@ -0,0 +1,411 @@
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use mysql_common::{prelude::FromRow, Row};
+
+#[cfg(feature = "query_sync")]
+use crate::sql::{get_conn, traits::Executor};
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::{query_builder::QueryAndValues, WpError};
+
+use super::WpComment;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use super::{
+    arg_builder::{fields::Fields, hierarchical::Hierarchy, WpCommentQueryArgs},
+    query_builder::{CommentQueryBuilder, DescendantsQueryBuilder},
+    thread::{flatten_comments, thread_comments},
+};
+
+/// Results of a comment query, depending on the `fields` and `count` args.
+#[derive(Debug)]
+pub enum WpCommentResults {
+    Comments(Vec<WpComment>),
+    Ids(Vec<u64>),
+    Count(u64),
+}
+
+#[derive(Debug)]
+pub struct WpCommentQuery {
+    pub comments: WpCommentResults,
+}
+
+impl WpCommentQuery {
+    /// Queries the WordPress Database for comments using the global connection pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use wp_query_rs::{WpCommentArgBuilder, WpCommentQuery};
+    ///
+    /// let mut args = WpCommentArgBuilder::new();
+    /// args.post_id = Some(1);
+    ///
+    /// let comment_q = WpCommentQuery::new(args);
+    /// ```
+    ///
+    /// # Errors
+    /// Will return an error if there is an error in the mysql query.
+    #[cfg(feature = "query_sync")]
+    pub fn new<T>(args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpCommentQueryArgs>,
+    {
+        let mut conn = get_conn()?;
+
+        Self::query(&mut conn, args)
+    }
+
+    /// Queries the WordPress database for comments with a mysql connection, or any other [`Executor`].
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn with_connection<T>(conn: &mut impl Executor, args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpCommentQueryArgs>,
+    {
+        Self::query(conn, args)
+    }
+
+    #[cfg(feature = "query_sync")]
+    fn query<T>(conn: &mut impl Executor, args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpCommentQueryArgs>,
+    {
+        let CommentsQuery {
+            comments: QueryAndValues(q, values),
+            results,
+        } = CommentsQuery::new(args.into())?;
+
+        let found = results.found(conn.fetch(&q, values)?)?;
+
+        let comments = match results.descendants_query(&found) {
+            Some(QueryAndValues(q, values)) => results.thread(found, conn.fetch(&q, values)?),
+            None => found,
+        };
+
+        Ok(Self { comments })
+    }
+
+    /// Number of comments found. For count queries, this is the count returned by the database.
+    pub fn comment_count(&self) -> usize {
+        match &self.comments {
+            WpCommentResults::Comments(comments) => comments.len(),
+            WpCommentResults::Ids(ids) => ids.len(),
+            WpCommentResults::Count(n) => *n as usize,
+        }
+    }
+
+    /// Consumes the query returning the comments found. Empty for ID and count queries.
+    pub fn to_vec(self) -> Vec<WpComment> {
+        match self.comments {
+            WpCommentResults::Comments(comments) => comments,
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::ops::Deref;
+
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{r#async::get_conn, traits::r#async::Executor},
+        WpError,
+    };
+
+    use super::{super::arg_builder::WpCommentQueryArgs, CommentsQuery};
+
+    /// Queries comments like [`crate::WpCommentQuery`], dereferencing to its results.
+    #[derive(Debug)]
+    pub struct WpCommentQuery(super::WpCommentQuery);
+
+    impl WpCommentQuery {
+        /// Queries the WordPress Database for comments using the global connection pool.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// use wp_query_rs::{WpCommentArgBuilder, WpCommentQuery};
+        ///
+        /// let mut args = WpCommentArgBuilder::new();
+        /// args.post_id = Some(1);
+        ///
+        /// let comment_q = WpCommentQuery::new(args);
+        /// ```
+        ///
+        /// # Errors
+        /// Will return an error if there is an error in the mysql query.
+        pub async fn new<T>(args: T) -> Result<Self, WpError>
+        where
+            T: Into<WpCommentQueryArgs>,
+        {
+            let mut conn = get_conn().await?;
+
+            Self::query(&mut conn, args).await
+        }
+
+        /// Queries the WordPress database for comments with a mysql connection, or any other [`Executor`].
+        ///
+        /// # Errors
+        /// When an error occurs in the SQL query.
+        pub async fn with_connection<T>(conn: &mut impl Executor, args: T) -> Result<Self, WpError>
+        where
+            T: Into<WpCommentQueryArgs>,
+        {
+            Self::query(conn, args).await
+        }
+
+        async fn query<T>(conn: &mut impl Executor, args: T) -> Result<Self, WpError>
+        where
+            T: Into<WpCommentQueryArgs>,
+        {
+            let CommentsQuery {
+                comments: QueryAndValues(q, values),
+                results,
+            } = CommentsQuery::new(args.into())?;
+
+            let found = results.found(conn.fetch(&q, values).await?)?;
+
+            let comments = match results.descendants_query(&found) {
+                Some(QueryAndValues(q, values)) => {
+                    results.thread(found, conn.fetch(&q, values).await?)
+                }
+                None => found,
+            };
+
+            Ok(Self(super::WpCommentQuery { comments }))
+        }
+
+        /// Consumes the wrapper returning the results of the query.
+        pub fn into_inner(self) -> super::WpCommentQuery {
+            self.0
+        }
+    }
+
+    impl Deref for WpCommentQuery {
+        type Target = super::WpCommentQuery;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl From<WpCommentQuery> for super::WpCommentQuery {
+        fn from(query: WpCommentQuery) -> Self {
+            query.0
+        }
+    }
+}
+
+/// The statement of a comment query and how its rows become the results,
+/// shared by the blocking and the async API.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+struct CommentsQuery {
+    comments: QueryAndValues,
+    results: CommentsResults,
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl CommentsQuery {
+    fn new(args: WpCommentQueryArgs) -> Result<Self, WpError> {
+        let results = CommentsResults {
+            count: args.count,
+            fields_ids: matches!(args.fields, Fields::Ids),
+            hierarchy: args.hierarchical,
+            descendants: DescendantsQueryBuilder::new(&args)?,
+        };
+
+        Ok(Self {
+            comments: CommentQueryBuilder::new(args).query()?,
+            results,
+        })
+    }
+}
+
+/// Converts the rows of a comment query to its results, then threads in the descendants if any were queried.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+struct CommentsResults {
+    count: bool,
+    fields_ids: bool,
+    hierarchy: Option<Hierarchy>,
+    descendants: Option<DescendantsQueryBuilder>,
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl CommentsResults {
+    /// The comments, IDs or count found by the comment query, without descendants.
+    fn found(&self, rows: Vec<Row>) -> Result<WpCommentResults, WpError> {
+        Ok(if self.count {
+            let count = rows.into_iter().next().map(u64::from_row_opt).transpose()?;
+
+            WpCommentResults::Count(count.unwrap_or(0))
+        } else if self.fields_ids {
+            WpCommentResults::Ids(rows.into_iter().map(comment_id).collect::<Result<_, _>>()?)
+        } else {
+            WpCommentResults::Comments(
+                rows.into_iter()
+                    .map(WpComment::from_row_opt)
+                    .collect::<Result<_, _>>()?,
+            )
+        })
+    }
+
+    /// The query for the descendants of the found comments, None if they are not needed.
+    fn descendants_query(&self, found: &WpCommentResults) -> Option<QueryAndValues> {
+        let descendants_q = self.descendants.as_ref()?;
+
+        let ids: Vec<u64> = match found {
+            // Only flat queries can include the descendants in a list of IDs
+            WpCommentResults::Ids(ids) if self.hierarchy == Some(Hierarchy::Flat) => ids.clone(),
+            WpCommentResults::Comments(comments) if descendants_q.depth() != Some(0) => {
+                comments.iter().map(|c| c.comment_ID).collect()
+            }
+            _ => return None,
+        };
+
+        (!ids.is_empty()).then(|| descendants_q.query(&ids))
+    }
+
+    /// Orders or nests the descendants with the found comments.
+    fn thread(&self, found: WpCommentResults, descendants: Vec<WpComment>) -> WpCommentResults {
+        match found {
+            WpCommentResults::Ids(ids) => {
+                let comments = ids.into_iter().map(comment_with_id).collect();
+
+                WpCommentResults::Ids(
+                    flatten_comments(comments, descendants)
+                        .into_iter()
+                        .map(|c| c.comment_ID)
+                        .collect(),
+                )
+            }
+            WpCommentResults::Comments(comments) => {
+                WpCommentResults::Comments(match self.hierarchy {
+                    Some(Hierarchy::Flat) => flatten_comments(comments, descendants),
+                    _ => thread_comments(
+                        comments,
+                        descendants,
+                        self.descendants.as_ref().and_then(|d| d.depth()),
+                    ),
+                })
+            }
+            count => count,
+        }
+    }
+}
+
+/// Placeholder used to order IDs with their descendants
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn comment_with_id(id: u64) -> WpComment {
+    let mut comment = WpComment::new(0);
+    comment.comment_ID = id;
+    comment
+}
+
+/// ID queries may select the ordered by column as well, so the ID must be found by name
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn comment_id(mut row: Row) -> Result<u64, mysql_common::FromRowError> {
+    crate::sql::find_col(&mut row, "comment_ID").ok_or_else(|| mysql_common::FromRowError(row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_count_comments() {
+        let q = WpCommentQuery {
+            comments: WpCommentResults::Comments(vec![WpComment::new(1), WpComment::new(2)]),
+        };
+        assert_eq!(q.comment_count(), 2);
+
+        let q = WpCommentQuery {
+            comments: WpCommentResults::Count(42),
+        };
+        assert_eq!(q.comment_count(), 42);
+        assert!(q.to_vec().is_empty());
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn threads_descendants_under_found_comments() {
+        use crate::{sql::fake::comment_row, FakeExecutor, WpCommentArgBuilder};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![comment_row(1, 0), comment_row(2, 0)]);
+        fake.push_rows(vec![comment_row(3, 1), comment_row(4, 3)]);
+
+        let mut args: WpCommentQueryArgs = WpCommentArgBuilder::new().into();
+        args.hierarchical = Some(Hierarchy::Threaded(0));
+
+        let comments = WpCommentQuery::with_connection(&mut fake, args)
+            .unwrap()
+            .to_vec();
+
+        assert_eq!(comments.len(), 2);
+        let children = comments[0].children.as_ref().unwrap();
+        assert_eq!(children[0].comment_ID, 3);
+        assert_eq!(children[0].children.as_ref().unwrap()[0].comment_ID, 4);
+        assert_eq!(fake.executed()[1].1[..2], [1u64.into(), 2u64.into()]);
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn flat_id_queries_include_descendants() {
+        use crate::{FakeExecutor, WpCommentArgBuilder};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![
+            FakeExecutor::row(&[("comment_ID", 1u64.into())]),
+            FakeExecutor::row(&[("comment_ID", 2u64.into())]),
+        ]);
+        fake.push_rows(vec![crate::sql::fake::comment_row(5, 1)]);
+
+        let mut args: WpCommentQueryArgs = WpCommentArgBuilder::new().into();
+        args.fields = Fields::Ids;
+        args.hierarchical = Some(Hierarchy::Flat);
+
+        let q = WpCommentQuery::with_connection(&mut fake, args).unwrap();
+
+        assert!(matches!(q.comments, WpCommentResults::Ids(ids) if ids == vec![1, 5, 2]));
+    }
+
+    #[cfg(feature = "query_sync")]
+    #[test]
+    fn undecodable_ids_are_an_error() {
+        use crate::{FakeExecutor, WpCommentArgBuilder, WpError};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![
+            FakeExecutor::row(&[("comment_ID", 1u64.into())]),
+            FakeExecutor::row(&[("comment_ID", "first".into())]),
+        ]);
+
+        let mut args: WpCommentQueryArgs = WpCommentArgBuilder::new().into();
+        args.fields = Fields::Ids;
+
+        let res = WpCommentQuery::with_connection(&mut fake, args);
+
+        assert!(matches!(res, Err(WpError::Decode(_))));
+    }
+
+    #[cfg(feature = "query_async")]
+    #[tokio::test]
+    async fn counts_comments_async() {
+        use crate::{FakeExecutor, WpCommentArgBuilder};
+
+        let mut fake = FakeExecutor::new();
+        fake.push_rows(vec![FakeExecutor::row(&[("COUNT(*)", 3u64.into())])]);
+
+        let mut args: WpCommentQueryArgs = WpCommentArgBuilder::new().into();
+        args.count = true;
+        args.hierarchical = Some(Hierarchy::Threaded(0));
+
+        let q = r#async::WpCommentQuery::with_connection(&mut fake, args)
+            .await
+            .unwrap();
+
+        assert_eq!(q.comment_count(), 3);
+        assert_eq!(fake.executed().len(), 1);
+    }
+}
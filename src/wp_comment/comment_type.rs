@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq, Eq)]
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommentType {
     Comment,
     Pingback,
@@ -6,8 +8,51 @@ pub enum CommentType {
     Custom(String),
 }
 
+impl CommentType {
+    /// Values of the comment_type column matching this type.
+    /// Comments saved before WordPress 5.5 have an empty comment_type.
+    pub fn column_values(&self) -> Vec<String> {
+        match self {
+            Self::Comment => vec![String::new(), self.to_string()],
+            _ => vec![self.to_string()],
+        }
+    }
+}
+
+impl Display for CommentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Comment => "comment",
+                Self::Pingback => "pingback",
+                Self::Trackback => "trackback",
+                Self::Custom(s) => s.as_str(),
+            }
+        )
+    }
+}
+
+impl From<String> for CommentType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "" | "comment" => Self::Comment,
+            "pingback" => Self::Pingback,
+            "trackback" => Self::Trackback,
+            _ => Self::Custom(value),
+        }
+    }
+}
+
+impl mysql_common::prelude::FromValue for CommentType {
+    type Intermediate = String;
+}
+
 #[cfg(test)]
 mod tests {
+    use mysql_common::prelude::FromValue;
+
     use super::*;
 
     #[test]
@@ -19,4 +64,21 @@ mod tests {
             _ => panic!("Did not match!"),
         }
     }
+
+    #[test]
+    fn can_convert_from_mysql_value() {
+        let value = mysql_common::Value::Bytes(Vec::new());
+        assert_eq!(CommentType::from_value(value), CommentType::Comment);
+
+        let value = mysql_common::Value::Bytes(String::from("pingback").into_bytes());
+        assert_eq!(CommentType::from_value(value), CommentType::Pingback);
+    }
+
+    #[test]
+    fn comment_includes_legacy_empty_type() {
+        assert_eq!(
+            CommentType::Comment.column_values(),
+            vec![String::new(), String::from("comment")]
+        );
+    }
 }
@@ -0,0 +1,224 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::*;
+use mysql_common::Value;
+#[cfg(feature = "query_sync")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "query_sync")]
+use crate::{maybe_serialize, maybe_unserialize, sql::get_conn, WpError};
+use crate::{query_builder::QueryAndValues, sql::table_prefix::get_table_prefix};
+
+/// Retrieves the first value of a comment meta key, decoding it if serialized.
+///
+/// Returns None if the comment has no meta with the key.
+#[cfg(feature = "query_sync")]
+pub fn get_comment_meta<T>(comment_id: u64, meta_key: &str) -> Result<Option<T>, WpError>
+where
+    T: DeserializeOwned,
+{
+    let mut conn = get_conn()?;
+
+    let value: Option<String> = conn.exec_first(select_query(), (comment_id, meta_key))?;
+
+    Ok(value.as_deref().map(maybe_unserialize).transpose()?)
+}
+
+/// Updates all comment meta with the key, or only those with `prev_value`, adding the meta if the comment has no meta with the key.
+/// Non scalar values are PHP serialized. Returns false if nothing was changed.
+#[cfg(feature = "query_sync")]
+pub fn update_comment_meta<T>(
+    comment_id: u64,
+    meta_key: &str,
+    meta_value: T,
+    prev_value: Option<&str>,
+) -> Result<bool, WpError>
+where
+    T: Serialize,
+{
+    let meta_value = maybe_serialize(&meta_value)?;
+    let mut conn = get_conn()?;
+
+    let existing: Vec<String> = conn.exec(select_query(), (comment_id, meta_key))?;
+
+    if existing.is_empty() {
+        conn.exec_drop(insert_query(), (comment_id, meta_key, meta_value))?;
+
+        return Ok(true);
+    }
+    if prev_value.is_none() && existing.iter().all(|value| value == &meta_value) {
+        return Ok(false);
+    }
+
+    let QueryAndValues(q, values) = update_query(comment_id, meta_key, &meta_value, prev_value);
+    conn.exec_drop(q, values)?;
+
+    Ok(conn.affected_rows() > 0)
+}
+
+/// Deletes all comment meta with the key, or only those with the value given. Returns false if nothing was deleted.
+#[cfg(feature = "query_sync")]
+pub fn delete_comment_meta(
+    comment_id: u64,
+    meta_key: &str,
+    meta_value: Option<&str>,
+) -> Result<bool, WpError> {
+    let mut conn = get_conn()?;
+
+    let QueryAndValues(q, values) = delete_query(comment_id, meta_key, meta_value);
+    conn.exec_drop(q, values)?;
+
+    Ok(conn.affected_rows() > 0)
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use mysql_async::prelude::*;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use crate::{
+        maybe_serialize, maybe_unserialize, query_builder::QueryAndValues, sql::r#async::get_conn,
+        WpError,
+    };
+
+    use super::{delete_query, insert_query, select_query, update_query};
+
+    /// Retrieves the first value of a comment meta key, decoding it if serialized.
+    ///
+    /// Returns None if the comment has no meta with the key.
+    pub async fn get_comment_meta<T>(comment_id: u64, meta_key: &str) -> Result<Option<T>, WpError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut conn = get_conn().await?;
+
+        let value: Option<String> = conn
+            .exec_first(select_query(), (comment_id, meta_key))
+            .await?;
+
+        Ok(value.as_deref().map(maybe_unserialize).transpose()?)
+    }
+
+    /// Updates all comment meta with the key, or only those with `prev_value`, adding the meta if the comment has no meta with the key.
+    /// Non scalar values are PHP serialized. Returns false if nothing was changed.
+    pub async fn update_comment_meta<T>(
+        comment_id: u64,
+        meta_key: &str,
+        meta_value: T,
+        prev_value: Option<&str>,
+    ) -> Result<bool, WpError>
+    where
+        T: Serialize,
+    {
+        let meta_value = maybe_serialize(&meta_value)?;
+        let mut conn = get_conn().await?;
+
+        let existing: Vec<String> = conn.exec(select_query(), (comment_id, meta_key)).await?;
+
+        if existing.is_empty() {
+            conn.exec_drop(insert_query(), (comment_id, meta_key, meta_value))
+                .await?;
+
+            return Ok(true);
+        }
+        if prev_value.is_none() && existing.iter().all(|value| value == &meta_value) {
+            return Ok(false);
+        }
+
+        let QueryAndValues(q, values) = update_query(comment_id, meta_key, &meta_value, prev_value);
+        conn.exec_drop(q, values).await?;
+
+        Ok(conn.affected_rows() > 0)
+    }
+
+    /// Deletes all comment meta with the key, or only those with the value given. Returns false if nothing was deleted.
+    pub async fn delete_comment_meta(
+        comment_id: u64,
+        meta_key: &str,
+        meta_value: Option<&str>,
+    ) -> Result<bool, WpError> {
+        let mut conn = get_conn().await?;
+
+        let QueryAndValues(q, values) = delete_query(comment_id, meta_key, meta_value);
+        conn.exec_drop(q, values).await?;
+
+        Ok(conn.affected_rows() > 0)
+    }
+}
+
+fn select_query() -> String {
+    format!(
+        "SELECT meta_value FROM {}commentmeta WHERE comment_id = ? AND meta_key = ? ORDER BY meta_id ASC;",
+        get_table_prefix()
+    )
+}
+
+fn insert_query() -> String {
+    format!(
+        "INSERT INTO {}commentmeta (comment_id, meta_key, meta_value) VALUES (?, ?, ?);",
+        get_table_prefix()
+    )
+}
+
+fn update_query(
+    comment_id: u64,
+    meta_key: &str,
+    meta_value: &str,
+    prev_value: Option<&str>,
+) -> QueryAndValues {
+    let mut q = format!(
+        "UPDATE {}commentmeta SET meta_value = ? WHERE comment_id = ? AND meta_key = ?",
+        get_table_prefix()
+    );
+    let mut values: Vec<Value> = vec![meta_value.into(), comment_id.into(), meta_key.into()];
+
+    if let Some(prev_value) = prev_value {
+        q.push_str(" AND meta_value = ?");
+        values.push(prev_value.into());
+    }
+    q.push(';');
+
+    QueryAndValues(q, values)
+}
+
+fn delete_query(comment_id: u64, meta_key: &str, meta_value: Option<&str>) -> QueryAndValues {
+    let mut q = format!(
+        "DELETE FROM {}commentmeta WHERE comment_id = ? AND meta_key = ?",
+        get_table_prefix()
+    );
+    let mut values: Vec<Value> = vec![comment_id.into(), meta_key.into()];
+
+    if let Some(meta_value) = meta_value {
+        q.push_str(" AND meta_value = ?");
+        values.push(meta_value.into());
+    }
+    q.push(';');
+
+    QueryAndValues(q, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_update_only_previous_value() {
+        let QueryAndValues(q, values) = update_query(1, "rating", "5", Some("4"));
+
+        assert_eq!(
+            q,
+            "UPDATE wp_commentmeta SET meta_value = ? WHERE comment_id = ? AND meta_key = ? AND meta_value = ?;"
+        );
+        assert_eq!(values.len(), 4);
+    }
+
+    #[test]
+    fn can_delete_all_values() {
+        let QueryAndValues(q, values) = delete_query(1, "rating", None);
+
+        assert_eq!(
+            q,
+            "DELETE FROM wp_commentmeta WHERE comment_id = ? AND meta_key = ?;"
+        );
+        assert_eq!(values, vec![Value::UInt(1), Value::from("rating")]);
+    }
+}
@@ -0,0 +1,682 @@
+use mysql_common::{prelude::ToValue, Value};
+use sql_paginatorr::LimitOffsetPair;
+
+use crate::{
+    query_builder::{
+        implode_to_question_mark, push_meta_query, MetaTable, QueryAndValues, StmtValues,
+    },
+    sql::{cast_type::CastType, table_prefix::table_prefix_or_global},
+    PostStatus, WpError,
+};
+
+use super::{
+    arg_builder::{fields::Fields, orderby::Orderby, WpCommentQueryArgs},
+    comment_approved::CommentApproved,
+    comment_type::CommentType,
+};
+
+pub struct CommentQueryBuilder {
+    args: WpCommentQueryArgs,
+    query: String,
+    values: StmtValues,
+}
+
+impl CommentQueryBuilder {
+    pub fn new(args: WpCommentQueryArgs) -> Self {
+        Self {
+            args,
+            query: String::new(),
+            values: vec![],
+        }
+    }
+
+    /// Builds the comments query. Selects all comment columns, only the comment IDs, or the count of matches
+    /// depending on the `fields` and `count` args.
+    ///
+    /// # Errors
+    /// When the table prefix of the args is invalid.
+    pub fn query(mut self) -> Result<QueryAndValues, WpError> {
+        let args = self.args;
+        let prefix = table_prefix_or_global(args.table_prefix.as_deref())?.to_string();
+        let comments = format!("{prefix}comments");
+
+        macro_rules! add_if_some {
+            ($prop: ident, $col: expr) => {
+                if let Some(v) = args.$prop {
+                    self.query.push_str(&format!(" AND {} = ?", $col));
+                    self.values.push(v.to_value());
+                }
+            };
+        }
+
+        macro_rules! add_if_some_vec {
+            ($prop: ident, $col: expr, $op: expr) => {
+                if let Some(v) = args.$prop {
+                    push_in(&mut self.query, &mut self.values, $col, $op, v);
+                }
+            };
+        }
+
+        // Ordering by meta needs the meta key in the join
+        let order_meta_key = match &args.orderby {
+            Orderby::MetaValue(key) | Orderby::MetaKey(key) if !args.count => Some(key.clone()),
+            _ => None,
+        };
+        let order_col = match &args.orderby {
+            Orderby::None => None,
+            Orderby::MetaValue(_) => Some(format!("{prefix}commentmeta.meta_value")),
+            Orderby::MetaKey(_) => Some(format!("{prefix}commentmeta.meta_key")),
+            col => Some(format!("{comments}.{col}")),
+        };
+
+        let select = if args.count {
+            format!("COUNT(DISTINCT({comments}.comment_ID))")
+        } else {
+            let cols = match args.fields {
+                Fields::All => format!("{comments}.*"),
+                Fields::Ids => format!("{comments}.comment_ID"),
+            };
+
+            // Columns ordered by must be selected when using DISTINCT
+            match (&order_meta_key, &order_col) {
+                (Some(_), Some(order_col)) => format!("DISTINCT {cols},{order_col}"),
+                _ => format!("DISTINCT {cols}"),
+            }
+        };
+
+        self.query
+            .push_str(&format!("SELECT {select} FROM {comments}"));
+
+        if check_if_post_join_necessary(&args) {
+            self.query.push_str(&format!(
+                " INNER JOIN {prefix}posts ON {prefix}posts.ID = {comments}.comment_post_ID"
+            ));
+        }
+
+        if args.meta_key.is_some() || args.meta_value.is_some() || order_meta_key.is_some() {
+            self.query.push_str(&format!(
+                " INNER JOIN {prefix}commentmeta ON {prefix}commentmeta.comment_id = {comments}.comment_ID"
+            ));
+        }
+
+        // Avoid dangling WHERE issue
+        self.query.push_str(" WHERE 1 = 1");
+
+        /* Comment author conditions */
+        add_if_some!(author_email, format!("{comments}.comment_author_email"));
+        add_if_some!(author_url, format!("{comments}.comment_author_url"));
+        add_if_some_vec!(author__in, format!("{comments}.user_id"), "IN");
+        add_if_some_vec!(author__not_in, format!("{comments}.user_id"), "NOT IN");
+        add_if_some!(user_id, format!("{comments}.user_id"));
+
+        /* Comment conditions */
+        add_if_some_vec!(comment__in, format!("{comments}.comment_ID"), "IN");
+        add_if_some_vec!(comment__not_in, format!("{comments}.comment_ID"), "NOT IN");
+        add_if_some!(karma, format!("{comments}.comment_karma"));
+        // Hierarchical queries match top level comments unless a parent is given
+        let hierarchical = args.hierarchical.is_some_and(|h| h.is_hierarchical());
+        if hierarchical && args.parent.is_none() && args.parent__in.is_none() {
+            self.query
+                .push_str(&format!(" AND {comments}.comment_parent = 0"));
+        }
+        add_if_some!(parent, format!("{comments}.comment_parent"));
+        add_if_some_vec!(parent__in, format!("{comments}.comment_parent"), "IN");
+        add_if_some_vec!(
+            parent__not_in,
+            format!("{comments}.comment_parent"),
+            "NOT IN"
+        );
+
+        /* Status, including unapproved comments for the given users */
+        push_status(
+            &mut self.query,
+            &mut self.values,
+            &comments,
+            args.status.unwrap_or(CommentApproved::All),
+            args.include_unapproved,
+        );
+
+        /* Comment types */
+        if let Some(comment_type) = args.comment_type {
+            push_in(
+                &mut self.query,
+                &mut self.values,
+                format!("{comments}.comment_type"),
+                "IN",
+                comment_type.column_values(),
+            );
+        }
+        add_if_some_vec!(comment_type__in, format!("{comments}.comment_type"), "IN");
+        add_if_some_vec!(
+            comment_type__not_in,
+            format!("{comments}.comment_type"),
+            "NOT IN"
+        );
+
+        /* Post conditions */
+        add_if_some!(post_id, format!("{comments}.comment_post_ID"));
+        add_if_some_vec!(post__in, format!("{comments}.comment_post_ID"), "IN");
+        add_if_some_vec!(
+            post__not_in,
+            format!("{comments}.comment_post_ID"),
+            "NOT IN"
+        );
+        add_if_some!(post_author, format!("{prefix}posts.post_author"));
+        add_if_some_vec!(post_author__in, format!("{prefix}posts.post_author"), "IN");
+        add_if_some_vec!(
+            post_author__not_in,
+            format!("{prefix}posts.post_author"),
+            "NOT IN"
+        );
+        add_if_some!(post_name, format!("{prefix}posts.post_name"));
+        add_if_some!(post_parent, format!("{prefix}posts.post_parent"));
+        add_if_some_vec!(post_type, format!("{prefix}posts.post_type"), "IN");
+
+        if let Some(post_status) = args.post_status {
+            if post_status != PostStatus::Any {
+                self.query
+                    .push_str(&format!(" AND {prefix}posts.post_status = ?"));
+                self.values.push(post_status.into());
+            }
+        }
+
+        /* Search */
+        if let Some(search) = args.search {
+            let cols = [
+                "comment_author",
+                "comment_author_email",
+                "comment_author_url",
+                "comment_author_IP",
+                "comment_content",
+            ];
+
+            let q = cols
+                .iter()
+                .map(|col| format!("{comments}.{col} LIKE CONCAT('%',?,'%')"))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+
+            self.query.push_str(&format!(" AND ({q})"));
+            self.values.extend(
+                cols.iter()
+                    .map(|_| Value::Bytes(search.as_bytes().to_vec())),
+            );
+        }
+
+        /* Meta conditions */
+        if let Some(keys) = args.meta_key {
+            push_in(
+                &mut self.query,
+                &mut self.values,
+                format!("{prefix}commentmeta.meta_key"),
+                "IN",
+                keys,
+            );
+        }
+
+        if let Some(key) = order_meta_key {
+            self.query
+                .push_str(&format!(" AND {prefix}commentmeta.meta_key = ?"));
+            self.values.push(Value::Bytes(key.into_bytes()));
+        }
+
+        if let Some(meta_values) = args.meta_value {
+            let col = match args.meta_type {
+                CastType::Char => format!("{prefix}commentmeta.meta_value"),
+                cast => format!("CAST({prefix}commentmeta.meta_value AS {cast})"),
+            };
+            let q = meta_values
+                .iter()
+                .map(|_| format!("{col} {} ?", args.meta_compare))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+
+            self.query.push_str(&format!(" AND ({q})"));
+            self.values.extend(
+                meta_values
+                    .into_iter()
+                    .map(|v| Value::Bytes(v.into_bytes())),
+            );
+        }
+
+        if let Some(query_rel_map) = args.meta_query {
+            push_meta_query(
+                &mut self.query,
+                &mut self.values,
+                MetaTable {
+                    table: &format!("{prefix}commentmeta"),
+                    object_id_col: "comment_id",
+                    parent_id_col: &format!("{comments}.comment_ID"),
+                },
+                query_rel_map,
+            );
+        }
+
+        // Counting ignores order and pagination
+        if args.count {
+            self.query.push(';');
+
+            return Ok(QueryAndValues(self.query, self.values));
+        }
+
+        /* Add order conditions */
+        if let Some(col) = order_col {
+            self.query
+                .push_str(&format!(" ORDER BY {col} {}", args.order));
+        }
+
+        /* Add pagination, no limit if number is not set */
+        if let Some(number) = args.number {
+            let LimitOffsetPair { offset, limit } = sql_paginatorr::for_page(
+                args.paged.unwrap_or(1).saturating_sub(1) as usize,
+                number as usize,
+            );
+            // Offset takes priority over paged
+            let offset = args.offset.unwrap_or(offset as u64);
+
+            self.query.push_str(" LIMIT ? OFFSET ?");
+            self.values.push(Value::UInt(limit as u64));
+            self.values.push(Value::UInt(offset));
+        }
+
+        self.query.push(';');
+
+        Ok(QueryAndValues(self.query, self.values))
+    }
+}
+
+/// Builds the query for the descendants of comments matched by a hierarchical query.
+/// Descendants are filtered by the same status conditions as the matched comments.
+pub struct DescendantsQueryBuilder {
+    prefix: String,
+    status: CommentApproved,
+    include_unapproved: Option<Vec<u64>>,
+    depth: Option<u8>,
+}
+
+impl DescendantsQueryBuilder {
+    /// Returns None if the args do not retrieve descendants.
+    ///
+    /// # Errors
+    /// When the table prefix of the args is invalid.
+    pub fn new(args: &WpCommentQueryArgs) -> Result<Option<Self>, WpError> {
+        let Some(hierarchy) = args.hierarchical.filter(|h| h.is_hierarchical()) else {
+            return Ok(None);
+        };
+
+        if args.count {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            prefix: table_prefix_or_global(args.table_prefix.as_deref())?.to_string(),
+            status: args.status.clone().unwrap_or(CommentApproved::All),
+            include_unapproved: args.include_unapproved.clone(),
+            depth: hierarchy.descendant_depth(),
+        }))
+    }
+
+    /// Levels of descendants to retrieve. None if unlimited.
+    pub fn depth(&self) -> Option<u8> {
+        self.depth
+    }
+
+    /// Selects all descendants of the parent comments up to the depth in a single recursive query,
+    /// ordered oldest first so threads read in order.
+    pub fn query(&self, parent_ids: &[u64]) -> QueryAndValues {
+        let comments = format!("{}comments", self.prefix);
+        let mut s = String::new();
+        let mut v: StmtValues = vec![];
+
+        s.push_str(&format!(
+            "WITH RECURSIVE descendants AS (SELECT {comments}.*, 1 AS comment_depth FROM {comments} WHERE {comments}.comment_parent IN ({})",
+            implode_to_question_mark(parent_ids)
+        ));
+        v.extend(parent_ids.iter().map(|id| Value::UInt(*id)));
+        push_status(
+            &mut s,
+            &mut v,
+            &comments,
+            self.status.clone(),
+            self.include_unapproved.clone(),
+        );
+
+        s.push_str(&format!(
+            " UNION ALL SELECT {comments}.*, descendants.comment_depth + 1 FROM {comments} INNER JOIN descendants ON {comments}.comment_parent = descendants.comment_ID WHERE 1 = 1"
+        ));
+        push_status(
+            &mut s,
+            &mut v,
+            &comments,
+            self.status.clone(),
+            self.include_unapproved.clone(),
+        );
+        if let Some(depth) = self.depth {
+            s.push_str(" AND descendants.comment_depth < ?");
+            v.push(Value::UInt(depth as u64));
+        }
+
+        s.push_str(") SELECT * FROM descendants ORDER BY comment_date_gmt ASC, comment_ID ASC;");
+
+        QueryAndValues(s, v)
+    }
+}
+
+/// Adds the comment status condition, optionally including unapproved comments for the given users.
+fn push_status(
+    s: &mut String,
+    v: &mut StmtValues,
+    comments: &str,
+    status: CommentApproved,
+    include_unapproved: Option<Vec<u64>>,
+) {
+    let status_q = match status {
+        CommentApproved::All => format!("{comments}.comment_approved IN ('0','1')"),
+        status => {
+            v.push(Value::Bytes(status.to_string().into_bytes()));
+            format!("{comments}.comment_approved = ?")
+        }
+    };
+    if let Some(user_ids) = include_unapproved {
+        s.push_str(&format!(
+            " AND ({status_q} OR ({comments}.comment_approved = '0' AND {comments}.user_id IN ({})))",
+            implode_to_question_mark(&user_ids)
+        ));
+        v.extend(user_ids.into_iter().map(Value::UInt));
+    } else {
+        s.push_str(&format!(" AND {status_q}"));
+    }
+}
+
+fn check_if_post_join_necessary(args: &WpCommentQueryArgs) -> bool {
+    args.post_author.is_some()
+        || args.post_author__in.is_some()
+        || args.post_author__not_in.is_some()
+        || args.post_status.is_some()
+        || args.post_type.is_some()
+        || args.post_name.is_some()
+        || args.post_parent.is_some()
+}
+
+/// Adds an IN or NOT IN condition for a column. Comment types are expanded to all column values matching the type.
+fn push_in<T>(s: &mut String, v: &mut StmtValues, col: String, op: &str, values: Vec<T>)
+where
+    T: InValues,
+{
+    let values: Vec<Value> = values.into_iter().flat_map(InValues::in_values).collect();
+
+    // Nothing is in an empty list
+    if values.is_empty() {
+        if op == "IN" {
+            s.push_str(" AND 0 = 1");
+        }
+
+        return;
+    }
+
+    s.push_str(&format!(
+        " AND {col} {op} ({})",
+        implode_to_question_mark(&values)
+    ));
+    v.extend(values);
+}
+
+trait InValues {
+    fn in_values(self) -> Vec<Value>;
+}
+
+impl InValues for u64 {
+    fn in_values(self) -> Vec<Value> {
+        vec![Value::UInt(self)]
+    }
+}
+
+impl InValues for String {
+    fn in_values(self) -> Vec<Value> {
+        vec![Value::Bytes(self.into_bytes())]
+    }
+}
+
+impl InValues for CommentType {
+    fn in_values(self) -> Vec<Value> {
+        self.column_values().in_values()
+    }
+}
+
+impl InValues for Vec<String> {
+    fn in_values(self) -> Vec<Value> {
+        self.into_iter().flat_map(InValues::in_values).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::SqlCompareOperator;
+
+    use super::*;
+    use crate::wp_comment::arg_builder::{hierarchical::Hierarchy, WpCommentArgBuilder};
+
+    fn build(args: WpCommentArgBuilder) -> QueryAndValues {
+        CommentQueryBuilder::new(args.into()).query().unwrap()
+    }
+
+    #[test]
+    fn default_selects_approved_and_held() {
+        let QueryAndValues(q, values) = build(WpCommentArgBuilder::new());
+
+        assert!(q.starts_with("SELECT DISTINCT wp_comments.* FROM wp_comments WHERE 1 = 1"));
+        assert!(q.contains("wp_comments.comment_approved IN ('0','1')"));
+        assert!(q.contains("ORDER BY wp_comments.comment_date_gmt DESC"));
+        assert!(!q.contains("LIMIT"));
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn can_count() {
+        let mut args = WpCommentArgBuilder::new();
+        args.count = true;
+        args.post_id = Some(1);
+        args.number = Some(5);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.starts_with("SELECT COUNT(DISTINCT(wp_comments.comment_ID))"));
+        assert!(!q.contains("ORDER BY"));
+        assert!(!q.contains("LIMIT"));
+        assert_eq!(values, vec![Value::UInt(1)]);
+    }
+
+    #[test]
+    fn can_select_ids() {
+        let mut args = WpCommentArgBuilder::new();
+        args.fields = Fields::Ids;
+
+        let QueryAndValues(q, _) = build(args);
+
+        assert!(q.starts_with("SELECT DISTINCT wp_comments.comment_ID FROM wp_comments"));
+    }
+
+    #[test]
+    fn can_paginate() {
+        let mut args = WpCommentArgBuilder::new();
+        args.number = Some(10);
+        args.paged = Some(3);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.ends_with(" LIMIT ? OFFSET ?;"));
+        assert_eq!(values, vec![Value::UInt(10), Value::UInt(20)]);
+    }
+
+    #[test]
+    fn offset_overrides_paged() {
+        let mut args = WpCommentArgBuilder::new();
+        args.number = Some(10);
+        args.paged = Some(3);
+        args.offset = Some(5);
+
+        let QueryAndValues(_, values) = build(args);
+
+        assert_eq!(values, vec![Value::UInt(10), Value::UInt(5)]);
+    }
+
+    #[test]
+    fn empty_lists_are_valid_sql() {
+        let mut args = WpCommentArgBuilder::new();
+        args.post__in = Some(vec![]);
+        args.author__not_in = Some(vec![]);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.contains(" AND 0 = 1"));
+        assert!(!q.contains("()"));
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn joins_posts_for_post_args() {
+        let mut args = WpCommentArgBuilder::new();
+        args.post_type = Some(vec![String::from("page")]);
+        args.post_status = Some(PostStatus::Publish);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.contains("INNER JOIN wp_posts ON wp_posts.ID = wp_comments.comment_post_ID"));
+        assert!(q.contains("wp_posts.post_type IN (?)"));
+        assert!(q.contains("wp_posts.post_status = ?"));
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn can_include_unapproved() {
+        let mut args = WpCommentArgBuilder::new();
+        args.status = Some(CommentApproved::Approved);
+        args.include_unapproved = Some(vec![3]);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.contains(" AND (wp_comments.comment_approved = ? OR (wp_comments.comment_approved = '0' AND wp_comments.user_id IN (?)))"));
+        assert_eq!(values, vec![Value::Bytes(b"1".to_vec()), Value::UInt(3)]);
+    }
+
+    #[test]
+    fn comment_type_includes_empty_type() {
+        let mut args = WpCommentArgBuilder::new();
+        args.comment_type = Some(CommentType::Comment);
+        args.comment_type__not_in = Some(vec![CommentType::Pingback]);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.contains("wp_comments.comment_type IN (?,?)"));
+        assert!(q.contains("wp_comments.comment_type NOT IN (?)"));
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    fn can_order_by_meta_value() {
+        let mut args = WpCommentArgBuilder::new();
+        args.orderby = Orderby::MetaValue(String::from("rating"));
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(q.starts_with(
+            "SELECT DISTINCT wp_comments.*,wp_commentmeta.meta_value FROM wp_comments INNER JOIN wp_commentmeta"
+        ));
+        assert!(q.contains("AND wp_commentmeta.meta_key = ?"));
+        assert!(q.contains("ORDER BY wp_commentmeta.meta_value DESC"));
+        assert_eq!(values, vec![Value::Bytes(b"rating".to_vec())]);
+    }
+
+    #[test]
+    fn can_cast_meta_value() {
+        let mut args = WpCommentArgBuilder::new();
+        args.meta_key = Some(vec![String::from("rating")]);
+        args.meta_value = Some(vec![String::from("3")]);
+        args.meta_compare = SqlCompareOperator::GreaterThan;
+        args.meta_type = CastType::Signed;
+
+        let QueryAndValues(q, _) = build(args);
+
+        assert!(q.contains(
+            "INNER JOIN wp_commentmeta ON wp_commentmeta.comment_id = wp_comments.comment_ID"
+        ));
+        assert!(q.contains("AND (CAST(wp_commentmeta.meta_value AS SIGNED) > ?)"));
+    }
+
+    #[test]
+    fn hierarchical_matches_top_level() {
+        let mut args = WpCommentArgBuilder::new();
+        args.hierarchical = Some(Hierarchy::Threaded(3));
+
+        let QueryAndValues(q, _) = build(args);
+
+        assert!(q.contains("AND wp_comments.comment_parent = 0"));
+    }
+
+    #[test]
+    fn hierarchical_keeps_given_parent() {
+        let mut args = WpCommentArgBuilder::new();
+        args.hierarchical = Some(Hierarchy::Flat);
+        args.parent = Some(4);
+
+        let QueryAndValues(q, values) = build(args);
+
+        assert!(!q.contains("comment_parent = 0"));
+        assert_eq!(values, vec![Value::UInt(4)]);
+    }
+
+    #[test]
+    fn no_descendants_unless_hierarchical() {
+        let mut args = WpCommentArgBuilder::new();
+        assert!(DescendantsQueryBuilder::new(&args).unwrap().is_none());
+
+        args.hierarchical = Some(Hierarchy::False);
+        assert!(DescendantsQueryBuilder::new(&args).unwrap().is_none());
+
+        args.hierarchical = Some(Hierarchy::Flat);
+        args.count = true;
+        assert!(DescendantsQueryBuilder::new(&args).unwrap().is_none());
+    }
+
+    #[test]
+    fn can_query_descendants_to_depth() {
+        let mut args = WpCommentArgBuilder::new();
+        args.hierarchical = Some(Hierarchy::Threaded(3));
+        args.status = Some(CommentApproved::Approved);
+
+        let QueryAndValues(q, values) = DescendantsQueryBuilder::new(&args)
+            .unwrap()
+            .unwrap()
+            .query(&[1, 2]);
+
+        assert!(q.starts_with("WITH RECURSIVE descendants AS (SELECT wp_comments.*, 1 AS comment_depth FROM wp_comments WHERE wp_comments.comment_parent IN (?,?) AND wp_comments.comment_approved = ?"));
+        assert!(q.contains(
+            "INNER JOIN descendants ON wp_comments.comment_parent = descendants.comment_ID"
+        ));
+        assert!(q.contains("AND descendants.comment_depth < ?"));
+        assert_eq!(
+            values,
+            vec![
+                Value::UInt(1),
+                Value::UInt(2),
+                Value::Bytes(b"1".to_vec()),
+                Value::Bytes(b"1".to_vec()),
+                Value::UInt(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn flat_descendants_are_unlimited() {
+        let mut args = WpCommentArgBuilder::new();
+        args.hierarchical = Some(Hierarchy::Flat);
+
+        let QueryAndValues(q, values) = DescendantsQueryBuilder::new(&args)
+            .unwrap()
+            .unwrap()
+            .query(&[1]);
+
+        assert!(!q.contains("comment_depth <"));
+        assert_eq!(values, vec![Value::UInt(1)]);
+    }
+}
@@ -0,0 +1,388 @@
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use mysql_common::{prelude::ToValue, Value};
+
+use crate::{
+    ok_or_row_error,
+    sql::{
+        date::{get_date_now, get_utc_date_now},
+        find_col,
+    },
+};
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::{query_builder::QueryAndValues, sql::table_prefix::get_table_prefix};
+#[cfg(feature = "query_sync")]
+use crate::{
+    sql::{
+        get_conn, last_insert_id,
+        traits::{Executor, Insertable},
+    },
+    WpError,
+};
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use super::update::comment_count_query;
+use super::WpComment;
+
+/// Statement inserting a comment, with the values of `From<WpComment> for Params` as placeholders.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn insert_query() -> String {
+    format!(
+        "INSERT INTO `{}comments` (
+            /* For new comments, comment_ID will be 0 so MySQL will create an ID for us */
+            `comment_ID`,
+            `comment_post_ID`,
+            `comment_author`,
+            `comment_author_email`,
+            `comment_author_url`,
+            `comment_author_IP`,
+            `comment_date`,
+            `comment_date_gmt`,
+            `comment_content`,
+            `comment_karma`,
+            `comment_approved`,
+            `comment_agent`,
+            `comment_type`,
+            `comment_parent`,
+            `user_id`
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);",
+        get_table_prefix()
+    )
+}
+
+#[cfg(feature = "query_sync")]
+impl WpComment {
+    /// Inserts the comment like `wp_insert_comment`, returning the new ID.
+    /// The `comment_count` of the post is updated to the number of approved comments.
+    pub fn insert(self) -> Result<u64, WpError> {
+        <Self as Insertable>::insert(self)
+    }
+}
+
+/// The statements inserting comments and updating the `comment_count` of their posts,
+/// shared by the blocking and the async API.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+struct CommentsInsert {
+    insert: String,
+    comments: Vec<Vec<Value>>,
+    comment_counts: Vec<QueryAndValues>,
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl CommentsInsert {
+    fn new(comments: Vec<WpComment>) -> Self {
+        let comment_counts = post_ids(&comments)
+            .into_iter()
+            .map(comment_count_query)
+            .collect();
+
+        Self {
+            insert: insert_query(),
+            comments: comments.into_iter().map(insert_values).collect(),
+            comment_counts,
+        }
+    }
+}
+
+/// Inserts the comments reusing the prepared statement, then updates the comment count of their posts.
+/// Callers run it in a transaction, so the counts are never left out of sync with the comments.
+#[cfg(feature = "query_sync")]
+fn insert_comments(conn: &mut impl Executor, comments: Vec<WpComment>) -> Result<(), WpError> {
+    let plan = CommentsInsert::new(comments);
+
+    let stmt = conn.prepare(&plan.insert)?;
+    for values in plan.comments {
+        conn.exec(&stmt, values)?;
+    }
+
+    for QueryAndValues(q, values) in plan.comment_counts {
+        conn.execute(&q, values)?;
+    }
+
+    Ok(())
+}
+
+/// The values of the insert statement's placeholders.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn insert_values(comment: WpComment) -> Vec<Value> {
+    vec![
+        comment.comment_ID.to_value(),
+        comment.comment_post_ID.to_value(),
+        comment.comment_author.to_value(),
+        comment.comment_author_email.to_value(),
+        comment.comment_author_url.to_value(),
+        comment
+            .comment_author_IP
+            .map(|ip| ip.to_string())
+            .unwrap_or_default()
+            .to_value(),
+        comment.comment_date.to_value(),
+        comment.comment_date_gmt.to_value(),
+        comment.comment_content.to_value(),
+        comment.comment_karma.to_value(),
+        comment.comment_approved.to_string().to_value(),
+        comment.comment_agent.to_value(),
+        comment.comment_type.to_string().to_value(),
+        comment.comment_parent.to_value(),
+        comment.user_id.to_value(),
+    ]
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl From<WpComment> for mysql_common::params::Params {
+    fn from(comment: WpComment) -> Self {
+        mysql_common::params::Params::Positional(insert_values(comment))
+    }
+}
+
+impl mysql_common::prelude::FromRow for WpComment {
+    fn from_row_opt(mut row: mysql_common::Row) -> Result<Self, mysql_common::FromRowError>
+    where
+        Self: Sized,
+    {
+        let author_ip: String = find_col(&mut row, "comment_author_IP").unwrap_or_default();
+
+        Ok(Self {
+            comment_ID: ok_or_row_error!(row, "comment_ID"),
+            comment_post_ID: ok_or_row_error!(row, "comment_post_ID"),
+            comment_author: ok_or_row_error!(row, "comment_author"),
+            comment_author_email: ok_or_row_error!(row, "comment_author_email"),
+            comment_author_url: ok_or_row_error!(row, "comment_author_url"),
+            comment_author_IP: author_ip.parse().ok(),
+            comment_date: find_col(&mut row, "comment_date").unwrap_or(get_date_now()),
+            comment_date_gmt: find_col(&mut row, "comment_date_gmt").unwrap_or(get_utc_date_now()),
+            comment_content: ok_or_row_error!(row, "comment_content"),
+            comment_karma: find_col(&mut row, "comment_karma").unwrap_or(0),
+            comment_approved: ok_or_row_error!(row, "comment_approved"),
+            comment_agent: ok_or_row_error!(row, "comment_agent"),
+            comment_type: ok_or_row_error!(row, "comment_type"),
+            comment_parent: ok_or_row_error!(row, "comment_parent"),
+            user_id: ok_or_row_error!(row, "user_id"),
+            children: None,
+        })
+    }
+}
+
+#[cfg(feature = "query_sync")]
+impl Insertable for WpComment {
+    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), WpError> {
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        insert_comments(&mut tx, values.into_iter().collect())?;
+
+        Ok(tx.commit()?)
+    }
+
+    fn insert(self) -> Result<u64, WpError> {
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        insert_comments(&mut tx, vec![self])?;
+        let id = tx.fetch_first("SELECT LAST_INSERT_ID();", vec![])?;
+        tx.commit()?;
+
+        last_insert_id(id)
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{future::Future, pin::Pin};
+
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{
+            last_insert_id,
+            r#async::get_conn,
+            traits::r#async::{Executor, Insertable},
+        },
+        WpError,
+    };
+
+    use super::{CommentsInsert, WpComment};
+
+    impl Insertable for WpComment {
+        fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), WpError>>>>
+        where
+            T: IntoIterator<Item = Self> + Send + 'static,
+            T::IntoIter: Send,
+            Self: Sized,
+        {
+            let fut = async {
+                let mut conn = get_conn().await?;
+                let mut tx = conn
+                    .start_transaction(mysql_async::TxOpts::default())
+                    .await?;
+
+                insert_comments(&mut tx, values.into_iter().collect()).await?;
+
+                Ok(tx.commit().await?)
+            };
+
+            Box::pin(fut)
+        }
+
+        fn insert(self) -> Pin<Box<dyn Future<Output = Result<u64, WpError>>>> {
+            let fut = async {
+                let mut conn = get_conn().await?;
+                let mut tx = conn
+                    .start_transaction(mysql_async::TxOpts::default())
+                    .await?;
+
+                insert_comments(&mut tx, vec![self]).await?;
+                let id = tx.fetch_first("SELECT LAST_INSERT_ID();", vec![]).await?;
+                tx.commit().await?;
+
+                last_insert_id(id)
+            };
+
+            Box::pin(fut)
+        }
+    }
+
+    /// Inserts the comments reusing the prepared statement, then updates the comment count of their posts.
+    /// Callers run it in a transaction, so the counts are never left out of sync with the comments.
+    async fn insert_comments(
+        conn: &mut impl Executor,
+        comments: Vec<WpComment>,
+    ) -> Result<(), WpError> {
+        let plan = CommentsInsert::new(comments);
+
+        let stmt = conn.prepare(&plan.insert).await?;
+        for values in plan.comments {
+            conn.exec(&stmt, values).await?;
+        }
+
+        for QueryAndValues(q, values) in plan.comment_counts {
+            conn.execute(&q, values).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Distinct posts of the comments, whose comment count must be updated.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn post_ids(comments: &[WpComment]) -> Vec<u64> {
+    let mut post_ids: Vec<u64> = comments.iter().map(|c| c.comment_post_ID).collect();
+    post_ids.sort_unstable();
+    post_ids.dedup();
+
+    post_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net;
+
+    use mysql_common::{
+        constants::ColumnType, packets::Column, prelude::FromRow, row::new_row, Value,
+    };
+
+    use crate::wp_comment::{comment_approved::CommentApproved, comment_type::CommentType};
+
+    use super::*;
+
+    #[test]
+    fn can_convert_from_row() {
+        let cols = [
+            ("comment_ID", Value::UInt(4)),
+            ("comment_post_ID", Value::UInt(1)),
+            (
+                "comment_author",
+                Value::Bytes(b"A WordPress Commenter".to_vec()),
+            ),
+            (
+                "comment_author_email",
+                Value::Bytes(b"wapuu@wordpress.example".to_vec()),
+            ),
+            (
+                "comment_author_url",
+                Value::Bytes(b"https://wordpress.org/".to_vec()),
+            ),
+            ("comment_author_IP", Value::Bytes(b"127.0.0.1".to_vec())),
+            ("comment_date", Value::Date(2023, 9, 1, 12, 0, 0, 0)),
+            ("comment_date_gmt", Value::Date(2023, 9, 1, 3, 0, 0, 0)),
+            (
+                "comment_content",
+                Value::Bytes(b"Hi, this is a comment.".to_vec()),
+            ),
+            ("comment_karma", Value::Int(0)),
+            ("comment_approved", Value::Bytes(b"1".to_vec())),
+            ("comment_agent", Value::Bytes(Vec::new())),
+            ("comment_type", Value::Bytes(b"comment".to_vec())),
+            ("comment_parent", Value::UInt(0)),
+            ("user_id", Value::UInt(0)),
+        ];
+
+        let columns = cols
+            .iter()
+            .map(|(name, _)| {
+                Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(name.as_bytes())
+            })
+            .collect();
+        let row = new_row(cols.into_iter().map(|(_, v)| v).collect(), columns);
+
+        let comment = WpComment::from_row(row);
+
+        assert_eq!(comment.comment_ID, 4);
+        assert_eq!(
+            comment.comment_author_IP,
+            Some(net::IpAddr::V4(net::Ipv4Addr::new(127, 0, 0, 1)))
+        );
+        assert_eq!(comment.comment_approved, CommentApproved::Approved);
+        assert_eq!(comment.comment_type, CommentType::Comment);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn can_convert_comment_to_params() {
+        let mut comment = WpComment::new(1);
+        comment.comment_post_ID = 3;
+        comment.comment_author_IP = Some("2001:db8::1".parse().unwrap());
+        comment.comment_approved = CommentApproved::Spam;
+
+        let params: mysql_common::params::Params = comment.into();
+        match params {
+            mysql_common::params::Params::Positional(data) => {
+                assert_eq!(data.len(), 15);
+                assert_eq!(data[1], Value::UInt(3));
+                assert_eq!(data[5], Value::Bytes(b"2001:db8::1".to_vec()));
+                assert_eq!(data[10], Value::Bytes(b"spam".to_vec()));
+                assert_eq!(data[12], Value::Bytes(b"comment".to_vec()));
+            }
+            _ => panic!("Not positional"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "query_sync")]
+    fn inserts_comments_then_counts_their_posts() {
+        let mut first = WpComment::new(1);
+        first.comment_post_ID = 5;
+        let mut second = WpComment::new(1);
+        second.comment_post_ID = 5;
+
+        let mut fake = crate::FakeExecutor::new();
+        insert_comments(&mut fake, vec![first, second]).unwrap();
+
+        let executed = fake.executed();
+        assert_eq!(executed.len(), 3);
+        assert!(executed[0].0.starts_with("INSERT INTO `wp_comments`"));
+        assert_eq!(executed[1].1[1], Value::UInt(5));
+        assert!(executed[2]
+            .0
+            .starts_with("UPDATE wp_posts SET comment_count"));
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn counts_each_post_once() {
+        let mut first = WpComment::new(1);
+        first.comment_post_ID = 5;
+        let mut second = WpComment::new(1);
+        second.comment_post_ID = 2;
+
+        assert_eq!(post_ids(&[first.clone(), second, first]), vec![2, 5]);
+    }
+}
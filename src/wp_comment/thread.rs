@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use super::WpComment;
+
+/// Nests descendants under their parent comments, loading children for `depth` levels below the top level comments.
+/// Comments below the depth are left without children. Unlimited if depth is None.
+pub fn thread_comments(
+    comments: Vec<WpComment>,
+    descendants: Vec<WpComment>,
+    depth: Option<u8>,
+) -> Vec<WpComment> {
+    let mut by_parent = group_by_parent(descendants);
+
+    comments
+        .into_iter()
+        .map(|comment| attach_children(comment, &mut by_parent, depth))
+        .collect()
+}
+
+/// Places the descendants of each comment directly after it, depth first.
+pub fn flatten_comments(comments: Vec<WpComment>, descendants: Vec<WpComment>) -> Vec<WpComment> {
+    let mut by_parent = group_by_parent(descendants);
+    let mut flat = Vec::with_capacity(comments.len());
+
+    for comment in comments {
+        push_with_descendants(comment, &mut by_parent, &mut flat);
+    }
+
+    flat
+}
+
+fn group_by_parent(descendants: Vec<WpComment>) -> HashMap<u64, Vec<WpComment>> {
+    let mut by_parent: HashMap<u64, Vec<WpComment>> = HashMap::new();
+
+    for comment in descendants {
+        by_parent
+            .entry(comment.comment_parent)
+            .or_default()
+            .push(comment);
+    }
+
+    by_parent
+}
+
+fn attach_children(
+    mut comment: WpComment,
+    by_parent: &mut HashMap<u64, Vec<WpComment>>,
+    depth: Option<u8>,
+) -> WpComment {
+    if depth == Some(0) {
+        return comment;
+    }
+
+    let children = by_parent.remove(&comment.comment_ID).unwrap_or_default();
+    let child_depth = depth.map(|d| d - 1);
+
+    comment.children = Some(
+        children
+            .into_iter()
+            .map(|child| attach_children(child, by_parent, child_depth))
+            .collect(),
+    );
+
+    comment
+}
+
+fn push_with_descendants(
+    comment: WpComment,
+    by_parent: &mut HashMap<u64, Vec<WpComment>>,
+    flat: &mut Vec<WpComment>,
+) {
+    let children = by_parent.remove(&comment.comment_ID).unwrap_or_default();
+    flat.push(comment);
+
+    for child in children {
+        push_with_descendants(child, by_parent, flat);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u64, parent: u64) -> WpComment {
+        let mut comment = WpComment::new(1);
+        comment.comment_ID = id;
+        comment.comment_parent = parent;
+        comment
+    }
+
+    fn descendants() -> Vec<WpComment> {
+        vec![comment(3, 1), comment(4, 3), comment(5, 1), comment(6, 2)]
+    }
+
+    #[test]
+    fn can_thread_comments() {
+        let threaded = thread_comments(vec![comment(1, 0), comment(2, 0)], descendants(), None);
+
+        assert_eq!(threaded.len(), 2);
+        let first = &threaded[0];
+        let ids: Vec<u64> = first.children().iter().map(|c| c.comment_ID).collect();
+        assert_eq!(ids, vec![3, 5]);
+        assert_eq!(first.children()[0].children()[0].comment_ID, 4);
+        assert!(first.children()[1].children().is_empty());
+        assert_eq!(threaded[1].children()[0].comment_ID, 6);
+    }
+
+    #[test]
+    fn threading_stops_at_depth() {
+        let threaded = thread_comments(vec![comment(1, 0)], descendants(), Some(1));
+
+        let child = &threaded[0].children()[0];
+        assert_eq!(child.comment_ID, 3);
+        assert!(!child.has_children_loaded());
+    }
+
+    #[test]
+    fn can_flatten_comments() {
+        let flat = flatten_comments(vec![comment(1, 0), comment(2, 0)], descendants());
+
+        let ids: Vec<u64> = flat.iter().map(|c| c.comment_ID).collect();
+        assert_eq!(ids, vec![1, 3, 4, 5, 2, 6]);
+        assert!(flat.iter().all(|c| !c.has_children_loaded()));
+    }
+}
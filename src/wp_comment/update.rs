@@ -0,0 +1,343 @@
+use mysql_common::{prelude::ToValue, Value};
+
+#[cfg(feature = "query_sync")]
+use crate::sql::{exec_transaction, get_conn, traits::Executor};
+use crate::{
+    query_builder::QueryAndValues,
+    sql::table_prefix::get_table_prefix,
+    wp_post::{TRASH_META_STATUS, TRASH_META_TIME},
+    WpError,
+};
+
+use super::{comment_approved::CommentApproved, WpComment};
+
+#[cfg(feature = "query_sync")]
+impl WpComment {
+    /// Approves the comment like `wp_set_comment_status($id, 'approve')`.
+    pub fn approve(&mut self) -> Result<(), WpError> {
+        self.set_status(CommentApproved::Approved)
+    }
+
+    /// Unapproves the comment, holding it for moderation.
+    pub fn hold(&mut self) -> Result<(), WpError> {
+        self.set_status(CommentApproved::Hold)
+    }
+
+    /// Marks the comment as spam like `wp_spam_comment`, saving its current status in the `_wp_trash_meta_status` meta.
+    pub fn spam(&mut self) -> Result<(), WpError> {
+        self.set_status(CommentApproved::Spam)
+    }
+
+    /// Moves the comment to the trash like `wp_trash_comment`, saving its current status in the `_wp_trash_meta_status` meta.
+    pub fn trash(&mut self) -> Result<(), WpError> {
+        self.set_status(CommentApproved::Trash)
+    }
+
+    /// Permanently deletes the comment and its meta like `wp_delete_comment`.
+    /// Replies are moved to the comment's parent.
+    ///
+    /// All rows are changed in a single transaction, which also updates the `comment_count` of the post.
+    pub fn delete(self) -> Result<(), WpError> {
+        exec_transaction(self.delete_queries())
+    }
+
+    /// Changes the status in a single transaction, which also updates the `comment_count` of the post.
+    /// Does nothing if the comment already has the status.
+    ///
+    /// The current status is read from the database in the transaction, not from the struct.
+    fn set_status(&mut self, status: CommentApproved) -> Result<(), WpError> {
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        let QueryAndValues(q, values) = self.current_status_query();
+        let current = current_status(self.comment_ID, tx.fetch_first(&q, values)?)?;
+
+        for QueryAndValues(q, values) in self.status_queries(&current, &status).unwrap_or_default()
+        {
+            tx.execute(&q, values)?;
+        }
+        tx.commit()?;
+
+        self.comment_approved = status;
+
+        Ok(())
+    }
+}
+
+impl WpComment {
+    /// Selects the status of the comment's row, locking it until the end of the transaction.
+    fn current_status_query(&self) -> QueryAndValues {
+        QueryAndValues(
+            format!(
+                "SELECT comment_approved FROM {}comments WHERE comment_ID = ? FOR UPDATE;",
+                get_table_prefix()
+            ),
+            vec![Value::UInt(self.comment_ID)],
+        )
+    }
+
+    /// The statements changing the current status, None if the comment already has the status.
+    fn status_queries(
+        &self,
+        current: &CommentApproved,
+        status: &CommentApproved,
+    ) -> Option<Vec<QueryAndValues>> {
+        if current == status {
+            return None;
+        }
+
+        let prefix = get_table_prefix();
+        let id = Value::UInt(self.comment_ID);
+        let mut queries = vec![];
+
+        if is_trashed(current) {
+            queries.push(QueryAndValues(
+                format!(
+                    "DELETE FROM {prefix}commentmeta WHERE comment_id = ? AND meta_key IN (?, ?);"
+                ),
+                vec![
+                    id.clone(),
+                    TRASH_META_STATUS.to_value(),
+                    TRASH_META_TIME.to_value(),
+                ],
+            ));
+        }
+        if is_trashed(status) {
+            let now = chrono::Utc::now().timestamp();
+
+            queries.push(QueryAndValues(
+                format!(
+                    "INSERT INTO {prefix}commentmeta (comment_id, meta_key, meta_value) VALUES (?, ?, ?), (?, ?, ?);"
+                ),
+                vec![
+                    id.clone(),
+                    TRASH_META_STATUS.to_value(),
+                    current.to_string().to_value(),
+                    id.clone(),
+                    TRASH_META_TIME.to_value(),
+                    now.to_string().to_value(),
+                ],
+            ));
+        }
+
+        queries.push(QueryAndValues(
+            format!("UPDATE {prefix}comments SET comment_approved = ? WHERE comment_ID = ?;"),
+            vec![status.to_string().to_value(), id],
+        ));
+        queries.push(comment_count_query(self.comment_post_ID));
+
+        Some(queries)
+    }
+
+    fn delete_queries(&self) -> Vec<QueryAndValues> {
+        let prefix = get_table_prefix();
+        let id = Value::UInt(self.comment_ID);
+
+        vec![
+            QueryAndValues(
+                format!("UPDATE {prefix}comments SET comment_parent = ? WHERE comment_parent = ?;"),
+                vec![Value::UInt(self.comment_parent), id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}commentmeta WHERE comment_id = ?;"),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}comments WHERE comment_ID = ?;"),
+                vec![id],
+            ),
+            comment_count_query(self.comment_post_ID),
+        ]
+    }
+}
+
+/// Sets the `comment_count` of a post to its number of approved comments, like `wp_update_comment_count`.
+pub fn comment_count_query(post_id: u64) -> QueryAndValues {
+    let prefix = get_table_prefix();
+
+    QueryAndValues(
+        format!(
+            "UPDATE {prefix}posts SET comment_count = (
+                SELECT COUNT(*) FROM {prefix}comments WHERE comment_post_ID = ? AND comment_approved = '1'
+            ) WHERE ID = ?;"
+        ),
+        vec![Value::UInt(post_id), Value::UInt(post_id)],
+    )
+}
+
+/// The status of the comment's row read in the transaction.
+#[cfg_attr(
+    not(any(feature = "query_sync", feature = "query_async")),
+    allow(dead_code)
+)]
+fn current_status(comment_id: u64, status: Option<String>) -> Result<CommentApproved, WpError> {
+    status
+        .map(CommentApproved::from)
+        .ok_or_else(|| WpError::NotFound(format!("comment {comment_id}")))
+}
+
+/// Whether the comment status is one saving the previous status in the comment meta.
+fn is_trashed(status: &CommentApproved) -> bool {
+    matches!(status, CommentApproved::Spam | CommentApproved::Trash)
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{
+            r#async::{exec_transaction, get_conn},
+            traits::r#async::Executor,
+        },
+        wp_comment::{comment_approved::CommentApproved, r#async::WpCommentExt, WpComment},
+        WpError,
+    };
+
+    use super::current_status;
+
+    impl WpCommentExt for WpComment {
+        async fn approve(&mut self) -> Result<(), WpError> {
+            set_status(self, CommentApproved::Approved).await
+        }
+
+        async fn hold(&mut self) -> Result<(), WpError> {
+            set_status(self, CommentApproved::Hold).await
+        }
+
+        async fn spam(&mut self) -> Result<(), WpError> {
+            set_status(self, CommentApproved::Spam).await
+        }
+
+        async fn trash(&mut self) -> Result<(), WpError> {
+            set_status(self, CommentApproved::Trash).await
+        }
+
+        async fn delete(self) -> Result<(), WpError> {
+            exec_transaction(self.delete_queries()).await
+        }
+    }
+
+    async fn set_status(comment: &mut WpComment, status: CommentApproved) -> Result<(), WpError> {
+        let mut conn = get_conn().await?;
+        let mut tx = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await?;
+
+        let QueryAndValues(q, values) = comment.current_status_query();
+        let current = current_status(comment.comment_ID, tx.fetch_first(&q, values).await?)?;
+
+        for QueryAndValues(q, values) in comment
+            .status_queries(&current, &status)
+            .unwrap_or_default()
+        {
+            tx.execute(&q, values).await?;
+        }
+        tx.commit().await?;
+
+        comment.comment_approved = status;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment() -> WpComment {
+        let mut comment = WpComment::new(1);
+        comment.comment_ID = 9;
+        comment.comment_post_ID = 4;
+        comment.comment_parent = 2;
+
+        comment
+    }
+
+    #[test]
+    fn counts_approved_comments() {
+        let QueryAndValues(q, values) = comment_count_query(4);
+
+        assert!(q.starts_with("UPDATE wp_posts SET comment_count = ("));
+        assert!(q.contains("comment_approved = '1'"));
+        assert_eq!(values, vec![Value::UInt(4), Value::UInt(4)]);
+    }
+
+    #[test]
+    fn approving_updates_status_and_count() {
+        let queries = comment()
+            .status_queries(&CommentApproved::Hold, &CommentApproved::Approved)
+            .unwrap();
+
+        assert_eq!(queries.len(), 2);
+        assert_eq!(
+            queries[0].0,
+            "UPDATE wp_comments SET comment_approved = ? WHERE comment_ID = ?;"
+        );
+        assert_eq!(queries[0].1, vec![Value::from("1"), Value::UInt(9)]);
+        assert_eq!(queries[1].1[0], Value::UInt(4));
+    }
+
+    #[test]
+    fn trash_saves_previous_status() {
+        let queries = comment()
+            .status_queries(&CommentApproved::Approved, &CommentApproved::Trash)
+            .unwrap();
+
+        assert_eq!(queries.len(), 3);
+        assert!(queries[0].0.starts_with("INSERT INTO wp_commentmeta"));
+        assert_eq!(queries[0].1[2], Value::from("1"));
+        assert_eq!(queries[1].1[0], Value::from("trash"));
+    }
+
+    #[test]
+    fn restoring_from_spam_removes_saved_status() {
+        let queries = comment()
+            .status_queries(&CommentApproved::Spam, &CommentApproved::Approved)
+            .unwrap();
+
+        assert_eq!(queries.len(), 3);
+        assert!(queries[0].0.starts_with("DELETE FROM wp_commentmeta"));
+        assert_eq!(queries[0].1[1], Value::from(TRASH_META_STATUS));
+    }
+
+    #[test]
+    fn unchanged_status_is_not_written() {
+        assert!(comment()
+            .status_queries(&CommentApproved::Approved, &CommentApproved::Approved)
+            .is_none());
+    }
+
+    #[test]
+    fn status_is_read_from_the_locked_row() {
+        let comment = comment();
+        let QueryAndValues(q, values) = comment.current_status_query();
+
+        assert_eq!(
+            q,
+            "SELECT comment_approved FROM wp_comments WHERE comment_ID = ? FOR UPDATE;"
+        );
+        assert_eq!(values, vec![Value::UInt(9)]);
+        assert_eq!(
+            current_status(9, Some(String::from("spam"))).unwrap(),
+            CommentApproved::Spam
+        );
+        assert!(matches!(current_status(9, None), Err(WpError::NotFound(_))));
+    }
+
+    #[test]
+    fn delete_moves_replies_to_parent() {
+        let queries = comment().delete_queries();
+        let statements = [
+            "UPDATE wp_comments SET comment_parent",
+            "DELETE FROM wp_commentmeta",
+            "DELETE FROM wp_comments",
+            "UPDATE wp_posts SET comment_count",
+        ];
+
+        assert_eq!(queries.len(), statements.len());
+        for (QueryAndValues(q, _), statement) in queries.iter().zip(statements) {
+            assert!(q.starts_with(statement));
+        }
+        assert_eq!(queries[0].1, vec![Value::UInt(2), Value::UInt(9)]);
+    }
+}
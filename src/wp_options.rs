@@ -0,0 +1,730 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::*;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "query_sync")]
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::maybe_unserialize;
+#[cfg(feature = "query_sync")]
+use crate::{maybe_serialize, sql::get_conn};
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::{query_builder::QueryAndValues, sql::table_prefix::get_table_prefix, WpError};
+
+/// Prefix of the option storing a transient value.
+pub const TRANSIENT_PREFIX: &str = "_transient_";
+/// Prefix of the option storing the unix time a transient expires at.
+pub const TRANSIENT_TIMEOUT_PREFIX: &str = "_transient_timeout_";
+
+// Shared by all queries once loaded, like the alloptions cache in WordPress
+static AUTOLOADED: RwLock<Option<Arc<WpOptions>>> = RwLock::new(None);
+
+/// The autoloaded options of the site, loaded with a single query.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use wp_query_rs::wp_options::WpOptions;
+///
+/// let options = WpOptions::from(HashMap::from([
+///     (String::from("blogname"), String::from("My Blog")),
+///     (String::from("posts_per_page"), String::from("10")),
+/// ]));
+///
+/// assert_eq!(options.blogname(), Some("My Blog"));
+/// assert_eq!(options.posts_per_page(), Some(10));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WpOptions {
+    options: HashMap<String, String>,
+}
+
+impl From<HashMap<String, String>> for WpOptions {
+    fn from(options: HashMap<String, String>) -> Self {
+        Self { options }
+    }
+}
+
+impl WpOptions {
+    /// Loads all autoloaded options with one query and stores them in the shared cache,
+    /// which is then used by `get_option`.
+    #[cfg(feature = "query_sync")]
+    pub fn load() -> Result<Arc<Self>, WpError> {
+        let mut conn = get_conn()?;
+
+        let options: Vec<(String, String)> = conn.exec(autoload_query(), mysql::Params::Empty)?;
+
+        Ok(Self::store(options))
+    }
+
+    /// The shared cache of autoloaded options, if loaded.
+    pub fn cached() -> Option<Arc<Self>> {
+        AUTOLOADED
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Empties the shared cache so options are read from the database again.
+    pub fn flush() {
+        *AUTOLOADED.write().unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    /// The raw option value as stored in the database.
+    pub fn get_raw(&self, option_name: &str) -> Option<&str> {
+        self.options.get(option_name).map(String::as_str)
+    }
+
+    /// Decodes the option value, serialized or not.
+    pub fn get<T>(&self, option_name: &str) -> Result<Option<T>, serde_php::Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.get_raw(option_name).map(maybe_unserialize).transpose()
+    }
+
+    /// Site title.
+    pub fn blogname(&self) -> Option<&str> {
+        self.get_raw("blogname")
+    }
+
+    /// Site tagline.
+    pub fn blogdescription(&self) -> Option<&str> {
+        self.get_raw("blogdescription")
+    }
+
+    /// Site address (URL).
+    pub fn home(&self) -> Option<&str> {
+        self.get_raw("home")
+    }
+
+    /// WordPress address (URL).
+    pub fn siteurl(&self) -> Option<&str> {
+        self.get_raw("siteurl")
+    }
+
+    /// Permalink structure, e.g. `/%year%/%monthnum%/%postname%/`. Empty when using plain permalinks.
+    pub fn permalink_structure(&self) -> Option<&str> {
+        self.get_raw("permalink_structure")
+    }
+
+    /// Day the week starts on, 0 being Sunday.
+    pub fn start_of_week(&self) -> Option<u8> {
+        self.get("start_of_week").ok().flatten()
+    }
+
+    /// Number of posts shown on blog pages.
+    pub fn posts_per_page(&self) -> Option<u64> {
+        self.get("posts_per_page").ok().flatten()
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn store(options: Vec<(String, String)>) -> Arc<Self> {
+        let options = Arc::new(Self::from(options.into_iter().collect::<HashMap<_, _>>()));
+
+        *AUTOLOADED.write().unwrap_or_else(|err| err.into_inner()) = Some(options.clone());
+
+        options
+    }
+}
+
+/// Updates the shared cache after a write, if loaded. Values are only added for options that are autoloaded.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn update_cache(option_name: &str, option_value: Option<&str>, autoload: bool) {
+    let mut cache = AUTOLOADED.write().unwrap_or_else(|err| err.into_inner());
+
+    if let Some(cache) = cache.as_mut() {
+        match option_value {
+            Some(value) if autoload || cache.options.contains_key(option_name) => {
+                Arc::make_mut(cache)
+                    .options
+                    .insert(option_name.to_string(), value.to_string());
+            }
+            Some(_) => (),
+            None => {
+                Arc::make_mut(cache).options.remove(option_name);
+            }
+        }
+    }
+}
+
+/// Retrieves an option value, decoding it if serialized. Autoloaded options are read from the shared cache when loaded.
+///
+/// Returns None if the option does not exist.
+#[cfg(feature = "query_sync")]
+pub fn get_option<T>(option_name: &str) -> Result<Option<T>, WpError>
+where
+    T: DeserializeOwned,
+{
+    if let Some(value) = cached_option(option_name) {
+        return value;
+    }
+
+    let mut conn = get_conn()?;
+
+    let value: Option<String> = conn.exec_first(option_query(), (option_name,))?;
+
+    Ok(value.as_deref().map(maybe_unserialize).transpose()?)
+}
+
+/// Adds an option if it does not exist yet. Non scalar values are PHP serialized.
+///
+/// Returns false if the option already exists.
+#[cfg(feature = "query_sync")]
+pub fn add_option<T>(option_name: &str, option_value: T, autoload: bool) -> Result<bool, WpError>
+where
+    T: Serialize,
+{
+    let write = OptionWrite::Add {
+        option_name: option_name.to_string(),
+        option_value: maybe_serialize(&option_value)?,
+        autoload,
+    };
+
+    write_option(&mut get_conn()?, write)
+}
+
+/// Updates an option, adding it as autoloaded if it does not exist. Non scalar values are PHP serialized.
+///
+/// Returns false if the value was not changed.
+#[cfg(feature = "query_sync")]
+pub fn update_option<T>(option_name: &str, option_value: T) -> Result<bool, WpError>
+where
+    T: Serialize,
+{
+    let write = OptionWrite::Update {
+        option_name: option_name.to_string(),
+        option_value: maybe_serialize(&option_value)?,
+    };
+
+    write_option(&mut get_conn()?, write)
+}
+
+/// Deletes an option. Returns false if the option did not exist.
+#[cfg(feature = "query_sync")]
+pub fn delete_option(option_name: &str) -> Result<bool, WpError> {
+    write_option(
+        &mut get_conn()?,
+        OptionWrite::Delete {
+            option_name: option_name.to_string(),
+        },
+    )
+}
+
+/// Retrieves a transient value. Expired transients are deleted and None is returned.
+#[cfg(feature = "query_sync")]
+pub fn get_transient<T>(transient: &str) -> Result<Option<T>, WpError>
+where
+    T: DeserializeOwned,
+{
+    let timeout: Option<u64> = get_option(&transient_timeout_option(transient))?;
+
+    if is_expired(timeout, unix_now()) {
+        delete_transient(transient)?;
+
+        return Ok(None);
+    }
+
+    get_option(&transient_option(transient))
+}
+
+/// Sets a transient value expiring after `expiration` seconds, or never if 0.
+///
+/// Like WordPress, transients with an expiration are not autoloaded.
+#[cfg(feature = "query_sync")]
+pub fn set_transient<T>(transient: &str, value: T, expiration: u64) -> Result<(), WpError>
+where
+    T: Serialize,
+{
+    let value = maybe_serialize(&value)?;
+    let mut conn = get_conn()?;
+
+    for write in transient_options(transient, value, expiration) {
+        write_option(&mut conn, write)?;
+    }
+
+    Ok(())
+}
+
+/// Deletes a transient and its timeout. Returns false if the transient did not exist.
+#[cfg(feature = "query_sync")]
+pub fn delete_transient(transient: &str) -> Result<bool, WpError> {
+    delete_option(&transient_timeout_option(transient))?;
+
+    delete_option(&transient_option(transient))
+}
+
+/// Runs the write, then updates the shared cache from the number of affected rows.
+#[cfg(feature = "query_sync")]
+fn write_option(conn: &mut mysql::PooledConn, write: OptionWrite) -> Result<bool, WpError> {
+    let QueryAndValues(q, values) = write.query();
+    conn.exec_drop(q, values)?;
+
+    Ok(write.apply(conn.affected_rows()))
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::sync::Arc;
+
+    use mysql_async::prelude::*;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use crate::{
+        maybe_serialize, maybe_unserialize, query_builder::QueryAndValues, sql::r#async::get_conn,
+        WpError,
+    };
+
+    use super::{
+        autoload_query, cached_option, is_expired, option_query, transient_option,
+        transient_options, transient_timeout_option, unix_now, OptionWrite, WpOptions,
+    };
+
+    /// Loads all autoloaded options with one query and stores them in the shared cache,
+    /// which is then used by `get_option`. See [`super::WpOptions::load`].
+    pub async fn wp_load_alloptions() -> Result<Arc<WpOptions>, WpError> {
+        let mut conn = get_conn().await?;
+
+        let options: Vec<(String, String)> = conn
+            .exec(autoload_query(), mysql_async::Params::Empty)
+            .await?;
+
+        Ok(WpOptions::store(options))
+    }
+
+    /// Retrieves an option value, decoding it if serialized. Autoloaded options are read from the shared cache when loaded.
+    ///
+    /// Returns None if the option does not exist.
+    pub async fn get_option<T>(option_name: &str) -> Result<Option<T>, WpError>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(value) = cached_option(option_name) {
+            return value;
+        }
+
+        let mut conn = get_conn().await?;
+
+        let value: Option<String> = conn.exec_first(option_query(), (option_name,)).await?;
+
+        Ok(value.as_deref().map(maybe_unserialize).transpose()?)
+    }
+
+    /// Adds an option if it does not exist yet. Non scalar values are PHP serialized.
+    ///
+    /// Returns false if the option already exists.
+    pub async fn add_option<T>(
+        option_name: &str,
+        option_value: T,
+        autoload: bool,
+    ) -> Result<bool, WpError>
+    where
+        T: Serialize,
+    {
+        let write = OptionWrite::Add {
+            option_name: option_name.to_string(),
+            option_value: maybe_serialize(&option_value)?,
+            autoload,
+        };
+
+        write_option(&mut get_conn().await?, write).await
+    }
+
+    /// Updates an option, adding it as autoloaded if it does not exist. Non scalar values are PHP serialized.
+    ///
+    /// Returns false if the value was not changed.
+    pub async fn update_option<T>(option_name: &str, option_value: T) -> Result<bool, WpError>
+    where
+        T: Serialize,
+    {
+        let write = OptionWrite::Update {
+            option_name: option_name.to_string(),
+            option_value: maybe_serialize(&option_value)?,
+        };
+
+        write_option(&mut get_conn().await?, write).await
+    }
+
+    /// Deletes an option. Returns false if the option did not exist.
+    pub async fn delete_option(option_name: &str) -> Result<bool, WpError> {
+        let write = OptionWrite::Delete {
+            option_name: option_name.to_string(),
+        };
+
+        write_option(&mut get_conn().await?, write).await
+    }
+
+    /// Retrieves a transient value. Expired transients are deleted and None is returned.
+    pub async fn get_transient<T>(transient: &str) -> Result<Option<T>, WpError>
+    where
+        T: DeserializeOwned,
+    {
+        let timeout: Option<u64> = get_option(&transient_timeout_option(transient)).await?;
+
+        if is_expired(timeout, unix_now()) {
+            delete_transient(transient).await?;
+
+            return Ok(None);
+        }
+
+        get_option(&transient_option(transient)).await
+    }
+
+    /// Sets a transient value expiring after `expiration` seconds, or never if 0.
+    ///
+    /// Like WordPress, transients with an expiration are not autoloaded.
+    pub async fn set_transient<T>(transient: &str, value: T, expiration: u64) -> Result<(), WpError>
+    where
+        T: Serialize,
+    {
+        let value = maybe_serialize(&value)?;
+        let mut conn = get_conn().await?;
+
+        for write in transient_options(transient, value, expiration) {
+            write_option(&mut conn, write).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes a transient and its timeout. Returns false if the transient did not exist.
+    pub async fn delete_transient(transient: &str) -> Result<bool, WpError> {
+        delete_option(&transient_timeout_option(transient)).await?;
+
+        delete_option(&transient_option(transient)).await
+    }
+
+    /// Runs the write, then updates the shared cache from the number of affected rows.
+    async fn write_option(
+        conn: &mut mysql_async::Conn,
+        write: OptionWrite,
+    ) -> Result<bool, WpError> {
+        let QueryAndValues(q, values) = write.query();
+        conn.exec_drop(q, values).await?;
+
+        Ok(write.apply(conn.affected_rows()))
+    }
+}
+
+/// A write to the options table and how it changes the shared cache, shared by the blocking and the async API.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+#[derive(Debug, PartialEq)]
+enum OptionWrite {
+    /// Adds the option, leaving an existing option unchanged.
+    Add {
+        option_name: String,
+        option_value: String,
+        autoload: bool,
+    },
+    /// Updates the value, adding the option as autoloaded if it does not exist.
+    Update {
+        option_name: String,
+        option_value: String,
+    },
+    /// Adds or updates the option, cached only if autoloaded. Used for transients.
+    Set {
+        option_name: String,
+        option_value: String,
+        autoload: bool,
+    },
+    Delete {
+        option_name: String,
+    },
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl OptionWrite {
+    fn query(&self) -> QueryAndValues {
+        match self {
+            Self::Add {
+                option_name,
+                option_value,
+                autoload,
+            } => add_query(option_name, option_value, *autoload),
+            Self::Update {
+                option_name,
+                option_value,
+            } => upsert_query(option_name, option_value, true),
+            Self::Set {
+                option_name,
+                option_value,
+                autoload,
+            } => upsert_query(option_name, option_value, *autoload),
+            Self::Delete { option_name } => {
+                QueryAndValues(delete_query(), vec![option_name.as_str().into()])
+            }
+        }
+    }
+
+    /// Updates the shared cache once the write affected `affected_rows` rows, returning whether the option changed.
+    fn apply(&self, affected_rows: u64) -> bool {
+        match self {
+            Self::Add {
+                option_name,
+                option_value,
+                autoload,
+            } => {
+                if affected_rows > 0 {
+                    update_cache(option_name, Some(option_value), *autoload);
+                }
+            }
+            // 1 for a new row, 2 for an updated row
+            Self::Update {
+                option_name,
+                option_value,
+            } => update_cache(option_name, Some(option_value), affected_rows == 1),
+            Self::Set {
+                option_name,
+                option_value,
+                autoload,
+            } => update_cache(option_name, Some(option_value), *autoload),
+            Self::Delete { option_name } => update_cache(option_name, None, false),
+        }
+
+        affected_rows > 0
+    }
+}
+
+/// Reads an option from the shared cache of autoloaded options, None if not loaded or not autoloaded.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn cached_option<T>(option_name: &str) -> Option<Result<Option<T>, WpError>>
+where
+    T: DeserializeOwned,
+{
+    WpOptions::cached()
+        .and_then(|cache| cache.get(option_name).transpose())
+        .map(|value| value.map(Some).map_err(WpError::from))
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn autoload_query() -> String {
+    format!(
+        "SELECT option_name, option_value FROM {}options WHERE autoload IN ('yes', 'on', 'auto-on', 'auto');",
+        get_table_prefix()
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn option_query() -> String {
+    format!(
+        "SELECT option_value FROM {}options WHERE option_name = ? LIMIT 1;",
+        get_table_prefix()
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn delete_query() -> String {
+    format!(
+        "DELETE FROM {}options WHERE option_name = ?;",
+        get_table_prefix()
+    )
+}
+
+/// Inserts the option, leaving an existing option unchanged.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn add_query(option_name: &str, option_value: &str, autoload: bool) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "INSERT INTO {}options (option_name, option_value, autoload) VALUES (?, ?, ?) \
+            ON DUPLICATE KEY UPDATE option_name = option_name;",
+            get_table_prefix()
+        ),
+        vec![
+            option_name.into(),
+            option_value.into(),
+            autoload_value(autoload).into(),
+        ],
+    )
+}
+
+/// Inserts the option or updates the value of an existing option, keeping its autoload setting.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn upsert_query(option_name: &str, option_value: &str, autoload: bool) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "INSERT INTO {}options (option_name, option_value, autoload) VALUES (?, ?, ?) \
+            ON DUPLICATE KEY UPDATE option_value = VALUES(option_value);",
+            get_table_prefix()
+        ),
+        vec![
+            option_name.into(),
+            option_value.into(),
+            autoload_value(autoload).into(),
+        ],
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn autoload_value(autoload: bool) -> &'static str {
+    if autoload {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn transient_option(transient: &str) -> String {
+    format!("{TRANSIENT_PREFIX}{transient}")
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn transient_timeout_option(transient: &str) -> String {
+    format!("{TRANSIENT_TIMEOUT_PREFIX}{transient}")
+}
+
+/// The options to write for a transient, its timeout first.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn transient_options(transient: &str, value: String, expiration: u64) -> Vec<OptionWrite> {
+    let mut options = Vec::with_capacity(2);
+
+    if expiration > 0 {
+        options.push(OptionWrite::Set {
+            option_name: transient_timeout_option(transient),
+            option_value: (unix_now() + expiration).to_string(),
+            autoload: false,
+        });
+    }
+    options.push(OptionWrite::Set {
+        option_name: transient_option(transient),
+        option_value: value,
+        autoload: expiration == 0,
+    });
+
+    options
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn is_expired(timeout: Option<u64>, now: u64) -> bool {
+    timeout.is_some_and(|timeout| timeout < now)
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> WpOptions {
+        WpOptions::from(HashMap::from([
+            (String::from("blogname"), String::from("My Blog")),
+            (String::from("home"), String::from("https://example.com")),
+            (
+                String::from("permalink_structure"),
+                String::from("/%year%/%postname%/"),
+            ),
+            (String::from("start_of_week"), String::from("1")),
+            (String::from("posts_per_page"), String::from("10")),
+            (
+                String::from("sticky_posts"),
+                String::from("a:2:{i:0;i:12;i:1;i:34;}"),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn can_read_site_options() {
+        let options = options();
+
+        assert_eq!(options.blogname(), Some("My Blog"));
+        assert_eq!(options.home(), Some("https://example.com"));
+        assert_eq!(options.permalink_structure(), Some("/%year%/%postname%/"));
+        assert_eq!(options.start_of_week(), Some(1));
+        assert_eq!(options.posts_per_page(), Some(10));
+        assert_eq!(options.siteurl(), None);
+    }
+
+    #[test]
+    fn decodes_serialized_options() {
+        let options = options();
+
+        let sticky_posts: Option<Vec<u64>> = options.get("sticky_posts").unwrap();
+        assert_eq!(sticky_posts, Some(vec![12, 34]));
+
+        assert_eq!(options.get::<u64>("missing").unwrap(), None);
+        assert!(options.get::<u64>("blogname").is_err());
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn add_query_keeps_existing_options() {
+        let QueryAndValues(q, values) = add_query("my_option", "42", false);
+
+        assert!(q.contains("ON DUPLICATE KEY UPDATE option_name = option_name"));
+        assert_eq!(values, vec!["my_option".into(), "42".into(), "no".into()]);
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn upsert_query_updates_value() {
+        let QueryAndValues(q, values) = upsert_query("my_option", "42", true);
+
+        assert!(q.contains("ON DUPLICATE KEY UPDATE option_value = VALUES(option_value)"));
+        assert_eq!(values, vec!["my_option".into(), "42".into(), "yes".into()]);
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn expiring_transients_are_not_autoloaded() {
+        let options = transient_options("my_transient", String::from("value"), 60);
+
+        assert_eq!(options.len(), 2);
+        match &options[0] {
+            OptionWrite::Set {
+                option_name,
+                option_value,
+                autoload,
+            } => {
+                assert_eq!(option_name, "_transient_timeout_my_transient");
+                assert!(option_value.parse::<u64>().unwrap() > unix_now());
+                assert!(!autoload);
+            }
+            write => panic!("Unexpected write {write:?}"),
+        }
+        assert_eq!(
+            options[1],
+            OptionWrite::Set {
+                option_name: String::from("_transient_my_transient"),
+                option_value: String::from("value"),
+                autoload: false
+            }
+        );
+
+        let options = transient_options("my_transient", String::from("value"), 0);
+        assert_eq!(options.len(), 1);
+        assert!(matches!(
+            options[0],
+            OptionWrite::Set { autoload: true, .. }
+        ));
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn updates_write_the_value_as_autoloaded() {
+        let write = OptionWrite::Update {
+            option_name: String::from("my_option"),
+            option_value: String::from("42"),
+        };
+
+        let QueryAndValues(q, values) = write.query();
+
+        assert!(q.contains("ON DUPLICATE KEY UPDATE option_value"));
+        assert_eq!(values[2], "yes".into());
+        assert!(write.apply(2));
+        assert!(!write.apply(0));
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    #[test]
+    fn can_detect_expired_transients() {
+        assert!(is_expired(Some(99), 100));
+        assert!(!is_expired(Some(100), 100));
+        assert!(!is_expired(None, 100));
+    }
+}
@@ -2,6 +2,8 @@ use mysql_common::time::PrimitiveDateTime;
 use serde::ser::{Serialize, SerializeStruct};
 
 use crate::sql::date::{get_date_now, get_utc_date_now};
+#[cfg(feature = "query_sync")]
+use crate::WpError;
 
 pub use self::meta::WpMetaResults;
 use self::post_status::PostStatus;
@@ -10,11 +12,18 @@ mod builder;
 pub mod meta;
 pub mod post_status;
 mod sql;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub mod sticky;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod update;
 
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use self::update::{PostField, TRASH_META_STATUS, TRASH_META_TIME};
+
+#[cfg(feature = "query_sync")]
 use self::meta::WpMeta;
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
-use std::fmt::Display;
+#[cfg(feature = "query_sync")]
+use std::collections::HashMap;
 
 #[cfg(feature = "php")]
 mod zval;
@@ -92,8 +101,8 @@ impl WpPost {
         Self {
             ID: 0,
             post_author,
-            post_date: now.clone(),
-            post_date_gmt: now_utc.clone(),
+            post_date: now,
+            post_date_gmt: now_utc,
             post_content: String::new(),
             post_title: String::new(),
             post_excerpt: String::new(),
@@ -104,8 +113,8 @@ impl WpPost {
             post_name: String::new(),
             to_ping: String::new(),
             pinged: String::new(),
-            post_modified: now.clone(),
-            post_modified_gmt: now_utc.clone(),
+            post_modified: now,
+            post_modified_gmt: now_utc,
             post_content_filtered: String::new(),
             post_parent: 0,
             guid: String::new(),
@@ -132,14 +141,14 @@ impl Serialize for WpPost {
 
 /// Retrieves a post meta field for the given post ID.
 #[cfg(feature = "query_sync")]
-pub fn get_post_meta(post_id: u64, meta_key: &str, single: bool) -> WpMetaResults {
-    WpMeta::get_post_meta(post_id, meta_key, single).expect("Wp Meta get failed")
-}
-#[cfg(feature = "query_async")]
-pub async fn get_post_meta(post_id: u64, meta_key: &str, single: bool) -> WpMetaResults {
+pub fn get_post_meta(post_id: u64, meta_key: &str, single: bool) -> Result<WpMetaResults, WpError> {
     WpMeta::get_post_meta(post_id, meta_key, single)
-        .await
-        .expect("Wp Meta get failed")
+}
+
+/// Retrieves all meta fields of the given post, grouped by key.
+#[cfg(feature = "query_sync")]
+pub fn get_post_custom(post_id: u64) -> Result<HashMap<String, Vec<String>>, WpError> {
+    WpMeta::get_post_custom(post_id)
 }
 
 /// Adds a meta field to the given post.
@@ -147,17 +156,66 @@ pub async fn get_post_meta(post_id: u64, meta_key: &str, single: bool) -> WpMeta
 pub fn add_post_meta(
     post_id: u64,
     meta_key: &str,
-    meta_value: impl Display,
-) -> Result<u64, mysql::Error> {
+    meta_value: impl Serialize,
+) -> Result<u64, WpError> {
     WpMeta::add_post_meta(post_id, meta_key, meta_value)
 }
-#[cfg(feature = "query_async")]
-pub async fn add_post_meta(
+
+/// Updates a meta field of the given post, adding it if it does not exist.
+/// If `prev_value` is given, only meta with that value are updated.
+#[cfg(feature = "query_sync")]
+pub fn update_post_meta(
     post_id: u64,
     meta_key: &str,
-    meta_value: impl Display,
-) -> Result<u64, mysql_async::Error> {
-    WpMeta::add_post_meta(post_id, meta_key, meta_value).await
+    meta_value: impl Serialize,
+    prev_value: Option<&str>,
+) -> Result<bool, WpError> {
+    WpMeta::update_post_meta(post_id, meta_key, meta_value, prev_value)
+}
+
+/// Deletes a meta field from the given post. If `meta_value` is given, only meta with that value are deleted.
+#[cfg(feature = "query_sync")]
+pub fn delete_post_meta(
+    post_id: u64,
+    meta_key: &str,
+    meta_value: Option<&str>,
+) -> Result<bool, WpError> {
+    WpMeta::delete_post_meta(post_id, meta_key, meta_value)
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::future::Future;
+
+    use crate::WpError;
+
+    pub use super::meta::r#async::{
+        add_post_meta, add_post_meta_bulk, delete_post_meta, get_post_custom, get_post_meta,
+        update_post_meta,
+    };
+    pub use super::sticky::r#async::get_sticky_posts;
+
+    /// Async updates of posts, see the blocking methods of [`super::WpPost`].
+    pub trait WpPostExt {
+        /// Writes the given fields of the post to the row with the post's ID.
+        /// `post_modified` and `post_modified_gmt` are always set to the current time.
+        fn update(
+            &mut self,
+            fields: &[super::PostField],
+        ) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Moves the post to the trash like `wp_trash_post`, saving its current status in the `_wp_trash_meta_status` meta.
+        /// Does nothing if the post is already trashed. The current status is read in the transaction.
+        fn trash(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Restores a trashed post to the status saved in `_wp_trash_meta_status` like `wp_untrash_post`,
+        /// or to a draft if none was saved. Does nothing if the post is not trashed.
+        fn untrash(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Permanently deletes the post like `wp_delete_post`, along with its meta, term relationships and comments.
+        /// Children of the same post type are moved to the post's parent.
+        fn delete(self) -> impl Future<Output = Result<(), WpError>> + Send;
+    }
 }
 
 #[cfg(test)]
@@ -1,14 +1,27 @@
 #[cfg(feature = "query_sync")]
 use mysql::prelude::*;
-#[cfg(feature = "query_async")]
-use mysql_async::prelude::*;
-use mysql_common::params::Params;
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use std::{fmt::Display, vec};
+use mysql_common::prelude::ToValue;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "query_sync")]
+use serde::Serialize;
+use std::collections::HashMap;
 
+#[cfg(feature = "query_sync")]
+use crate::{
+    maybe_serialize,
+    sql::{
+        get_conn, last_insert_id,
+        traits::{Executor, Insertable},
+    },
+};
+use crate::{maybe_unserialize, ok_or_row_error, sql::find_col};
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use crate::sql::{get_conn, traits::Insertable};
-use crate::{ok_or_row_error, sql::find_col};
+use crate::{
+    query_builder::{implode_to_question_mark, QueryAndValues},
+    sql::table_prefix::{get_table_prefix, table_prefix_or_global},
+    WpError,
+};
 
 #[derive(Debug)]
 pub struct WpMeta {
@@ -25,21 +38,6 @@ pub enum WpMetaResults {
     Empty,
 }
 
-macro_rules! prepare_insert_stmt {
-    ($conn: ty, $result: ty) => {
-        fn prepare_insert_stmt(conn: &mut $conn) -> $result {
-            conn.prep(
-                "INSERT INTO wp_postmeta (
-                meta_id,
-                post_id,
-                meta_key,
-                meta_value
-            ) VALUES (?, ?, ?, ?);",
-            )
-        }
-    };
-}
-
 impl WpMeta {
     pub fn new<T>(post_id: u64, meta_key: &str, meta_value: T) -> Self
     where
@@ -53,129 +51,322 @@ impl WpMeta {
         }
     }
 
+    /// Decodes the meta value, unserializing it if it is PHP serialized like `maybe_unserialize`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use wp_query_rs::wp_post::meta::WpMeta;
+    ///
+    /// let meta = WpMeta::new(1, "_wp_attachment_metadata", "a:1:{s:5:\"width\";i:640;}");
+    /// let data: HashMap<String, u64> = meta.value_as().unwrap();
+    /// assert_eq!(data["width"], 640);
+    /// ```
+    pub fn value_as<T>(&self) -> Result<T, serde_php::Error>
+    where
+        T: DeserializeOwned,
+    {
+        maybe_unserialize(&self.meta_value)
+    }
+
     #[cfg(feature = "query_sync")]
     pub fn get_post_meta(
         post_id: u64,
         meta_key: &str,
         single: bool,
-    ) -> Result<WpMetaResults, mysql::Error> {
-        let mut conn = get_conn().expect("CouldNotGetConnection");
-
-        let stmt = conn
-            .prep(
-                "SELECT * FROM wp_postmeta
-                WHERE post_id = ? AND meta_key = ?;",
-            )
-            .expect("StmtError");
-        let params = Params::Positional(vec![post_id.to_value(), meta_key.to_value()]);
+    ) -> Result<WpMetaResults, WpError> {
+        let mut conn = get_conn()?;
 
-        if single {
-            conn.exec_first(stmt, params).map(|meta| match meta {
-                Some(meta) => WpMetaResults::Single(meta),
-                None => WpMetaResults::Empty,
-            })
-        } else {
-            conn.exec(stmt, params)
-                .map(|postmeta| WpMetaResults::Array(postmeta))
-        }
+        Self::get_post_meta_with_connection(&mut conn, post_id, meta_key, single)
     }
-    #[cfg(feature = "query_async")]
-    pub async fn get_post_meta(
+
+    /// Retrieves the meta of the post with the key using a mysql connection, or any other [`Executor`].
+    #[cfg(feature = "query_sync")]
+    pub fn get_post_meta_with_connection(
+        conn: &mut impl Executor,
         post_id: u64,
         meta_key: &str,
         single: bool,
-    ) -> Result<WpMetaResults, mysql_async::Error> {
-        let mut conn = get_conn().await?;
-
-        let stmt = conn
-            .prep(
-                "SELECT * FROM wp_postmeta
-                WHERE post_id = ? AND meta_key = ?;",
-            )
-            .await?;
-        let params = Params::Positional(vec![post_id.to_value(), meta_key.to_value()]);
+    ) -> Result<WpMetaResults, WpError> {
+        let QueryAndValues(q, values) = post_meta_query(post_id, meta_key);
 
         if single {
-            conn.exec_first(stmt, params).await.map(|meta| match meta {
+            Ok(match conn.fetch_first(&q, values)? {
                 Some(meta) => WpMetaResults::Single(meta),
                 None => WpMetaResults::Empty,
             })
         } else {
-            conn.exec(stmt, params)
-                .await
-                .map(|postmeta| WpMetaResults::Array(postmeta))
+            Ok(WpMetaResults::Array(conn.fetch(&q, values)?))
         }
     }
 
     #[cfg(feature = "query_sync")]
-    pub fn add_post_meta<T>(
-        post_id: u64,
-        meta_key: &str,
-        meta_value: T,
-    ) -> Result<u64, mysql::Error>
+    pub fn add_post_meta<T>(post_id: u64, meta_key: &str, meta_value: T) -> Result<u64, WpError>
     where
-        T: Display,
+        T: Serialize,
     {
+        let meta_value = maybe_serialize(&meta_value)?;
+
         Self::insert(Self::new(post_id, meta_key, meta_value))
     }
-    #[cfg(feature = "query_async")]
-    pub async fn add_post_meta<T>(
+
+    /// Updates all meta with the key, or only those with `prev_value`, adding the meta if the post has no meta with the key.
+    /// Returns false if nothing was changed.
+    #[cfg(feature = "query_sync")]
+    pub fn update_post_meta<T>(
         post_id: u64,
         meta_key: &str,
         meta_value: T,
-    ) -> Result<u64, mysql_async::Error>
+        prev_value: Option<&str>,
+    ) -> Result<bool, WpError>
     where
-        T: Display,
+        T: Serialize,
     {
-        Self::insert(Self::new(post_id, meta_key, meta_value)).await
+        let meta_value = maybe_serialize(&meta_value)?;
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        let QueryAndValues(q, values) = existing_meta_query(post_id, meta_key);
+        let existing: Vec<String> = tx.fetch(&q, values)?;
+
+        let changed = match write_meta_query(post_id, meta_key, meta_value, prev_value, &existing) {
+            Some(QueryAndValues(q, values)) => {
+                tx.execute(&q, values)?;
+
+                tx.affected_rows() > 0
+            }
+            None => false,
+        };
+        tx.commit()?;
+
+        Ok(changed)
     }
 
+    /// Deletes all meta with the key from the post, or only those matching `meta_value`.
+    /// Returns false if no meta was deleted.
     #[cfg(feature = "query_sync")]
-    prepare_insert_stmt!(impl Queryable, Result<mysql::Statement, mysql::Error>);
-
-    #[cfg(feature = "query_async")]
-    prepare_insert_stmt!(
-        mysql_async::Conn,
-        std::pin::Pin<
-            Box<
-                dyn std::future::Future<Output = Result<mysql_async::Statement, mysql_async::Error>>
-                    // Lives as long at least as the Conn reference!
-                    + '_,
-            >,
-        >
-    );
+    pub fn delete_post_meta(
+        post_id: u64,
+        meta_key: &str,
+        meta_value: Option<&str>,
+    ) -> Result<bool, WpError> {
+        let mut conn = get_conn()?;
+
+        let QueryAndValues(q, values) = delete_query(post_id, meta_key, meta_value);
+        conn.exec_drop(q, values)?;
+
+        Ok(conn.affected_rows() > 0)
+    }
+
+    /// Retrieves all meta of the post, with the values of each key in the order they were added.
+    #[cfg(feature = "query_sync")]
+    pub fn get_post_custom(post_id: u64) -> Result<HashMap<String, Vec<String>>, WpError> {
+        let mut conn = get_conn()?;
+
+        let meta: Vec<(String, String)> = conn.fetch(&custom_query(), vec![post_id.into()])?;
+
+        Ok(group_by_key(meta))
+    }
+
+    #[cfg(feature = "query_sync")]
+    fn prepare_insert_stmt(conn: &mut impl Queryable) -> Result<mysql::Statement, mysql::Error> {
+        conn.prep(insert_query())
+    }
 
     /// Allows multiple meta to be added in the same prepared statement, improving speed
     #[cfg(feature = "query_sync")]
     pub fn add_post_meta_bulk<T>(
         post_id: u64,
         meta_key_value_pairs: &[(&str, T)],
-    ) -> Result<(), mysql::Error>
+    ) -> Result<(), WpError>
     where
-        T: Display,
+        T: Serialize,
     {
         let values = meta_key_value_pairs
             .iter()
-            .map(|(meta_key, meta_value)| WpMeta::new(post_id, meta_key, meta_value));
+            .map(|(meta_key, meta_value)| {
+                maybe_serialize(meta_value).map(|value| WpMeta::new(post_id, meta_key, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         Self::batch(values)
     }
-    #[cfg(feature = "query_async")]
-    pub async fn add_post_meta_bulk<T>(
-        post_id: u64,
-        meta_key_value_pairs: &[(&str, T)],
-    ) -> Result<(), mysql_async::Error>
-    where
-        T: Display,
-    {
-        // Must collect to vector so it can be possibly sent in async runtime
-        let values: Vec<_> = meta_key_value_pairs
-            .iter()
-            .map(|(meta_key, meta_value)| WpMeta::new(post_id, meta_key, meta_value))
-            .collect();
+}
+
+/// Statement inserting a meta, with the values of `Into<Params> for WpMeta` as placeholders.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn insert_query() -> String {
+    format!(
+        "INSERT INTO {}postmeta (
+            meta_id,
+            post_id,
+            meta_key,
+            meta_value
+        ) VALUES (?, ?, ?, ?);",
+        get_table_prefix()
+    )
+}
+
+/// Selects the values of the post's meta with the key, locking the rows until the transaction ends.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn existing_meta_query(post_id: u64, meta_key: &str) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "SELECT meta_value FROM {}postmeta WHERE post_id = ? AND meta_key = ? FOR UPDATE;",
+            get_table_prefix()
+        ),
+        vec![post_id.to_value(), meta_key.to_value()],
+    )
+}
+
+/// The statement of `update_post_meta` given the existing values of the key, adding the meta if there are none.
+/// None if no `prev_value` is given and all values already match.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn write_meta_query(
+    post_id: u64,
+    meta_key: &str,
+    meta_value: String,
+    prev_value: Option<&str>,
+    existing: &[String],
+) -> Option<QueryAndValues> {
+    if existing.is_empty() {
+        return Some(QueryAndValues(
+            insert_query(),
+            WpMeta::new(post_id, meta_key, meta_value).insert_values(),
+        ));
+    }
+    if prev_value.is_none() && existing.iter().all(|value| value == &meta_value) {
+        return None;
+    }
+
+    Some(update_query(post_id, meta_key, &meta_value, prev_value))
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn update_query(
+    post_id: u64,
+    meta_key: &str,
+    meta_value: &str,
+    prev_value: Option<&str>,
+) -> QueryAndValues {
+    let mut q = format!(
+        "UPDATE {}postmeta SET meta_value = ? WHERE post_id = ? AND meta_key = ?",
+        get_table_prefix()
+    );
+    let mut values = vec![
+        meta_value.to_value(),
+        post_id.to_value(),
+        meta_key.to_value(),
+    ];
+
+    if let Some(prev_value) = prev_value {
+        q.push_str(" AND meta_value = ?");
+        values.push(prev_value.to_value());
+    }
+    q.push(';');
 
-        Self::batch(values).await
+    QueryAndValues(q, values)
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn delete_query(post_id: u64, meta_key: &str, meta_value: Option<&str>) -> QueryAndValues {
+    let mut q = format!(
+        "DELETE FROM {}postmeta WHERE post_id = ? AND meta_key = ?",
+        get_table_prefix()
+    );
+    let mut values = vec![post_id.to_value(), meta_key.to_value()];
+
+    if let Some(meta_value) = meta_value {
+        q.push_str(" AND meta_value = ?");
+        values.push(meta_value.to_value());
+    }
+    q.push(';');
+
+    QueryAndValues(q, values)
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn post_meta_query(post_id: u64, meta_key: &str) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "SELECT * FROM {}postmeta
+                WHERE post_id = ? AND meta_key = ?;",
+            get_table_prefix()
+        ),
+        vec![post_id.to_value(), meta_key.to_value()],
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn custom_query() -> String {
+    format!(
+        "SELECT meta_key, meta_value FROM {}postmeta WHERE post_id = ? ORDER BY meta_id ASC;",
+        get_table_prefix()
+    )
+}
+
+/// Meta of multiple posts, grouped by post ID and then by key.
+pub type MetaCache = HashMap<u64, HashMap<String, Vec<String>>>;
+
+/// Retrieves the meta of all given posts with a single query.
+#[cfg(feature = "query_sync")]
+pub fn load_meta_cache(
+    conn: &mut impl Executor,
+    post_ids: &[u64],
+    table_prefix: Option<&str>,
+) -> Result<MetaCache, WpError> {
+    if post_ids.is_empty() {
+        return Ok(MetaCache::new());
     }
+
+    let QueryAndValues(q, values) = meta_cache_query(post_ids, table_prefix)?;
+    let meta: Vec<(u64, String, String)> = conn.fetch(&q, values)?;
+
+    Ok(group_by_post(meta))
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn meta_cache_query(
+    post_ids: &[u64],
+    table_prefix: Option<&str>,
+) -> Result<QueryAndValues, WpError> {
+    Ok(QueryAndValues(
+        format!(
+            "SELECT post_id, meta_key, meta_value FROM {}postmeta WHERE post_id IN ({}) ORDER BY meta_id ASC;",
+            table_prefix_or_global(table_prefix)?,
+            implode_to_question_mark(post_ids)
+        ),
+        post_ids.iter().map(|id| id.to_value()).collect(),
+    ))
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn group_by_post(meta: Vec<(u64, String, String)>) -> MetaCache {
+    meta.into_iter().fold(
+        MetaCache::new(),
+        |mut acc, (post_id, meta_key, meta_value)| {
+            acc.entry(post_id)
+                .or_default()
+                .entry(meta_key)
+                .or_default()
+                .push(meta_value);
+
+            acc
+        },
+    )
+}
+
+/// Groups meta values by their key, keeping the order of the values.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn group_by_key(meta: Vec<(String, String)>) -> HashMap<String, Vec<String>> {
+    meta.into_iter()
+        .fold(HashMap::new(), |mut acc, (meta_key, meta_value)| {
+            acc.entry(meta_key)
+                .or_insert_with(Vec::new)
+                .push(meta_value);
+
+            acc
+        })
 }
 
 impl mysql_common::prelude::FromRow for WpMeta {
@@ -195,32 +386,38 @@ impl mysql_common::prelude::FromRow for WpMeta {
 }
 
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-impl Into<mysql_common::params::Params> for WpMeta {
-    fn into(self) -> mysql_common::params::Params {
-        mysql_common::params::Params::Positional(vec![
+impl WpMeta {
+    /// Values of the insert statement, in the order of its columns.
+    fn insert_values(self) -> Vec<mysql_common::Value> {
+        vec![
             self.meta_id.to_value(),
             self.post_id.to_value(),
             self.meta_key.to_value(),
             self.meta_value.to_value(),
-        ])
+        ]
     }
 }
 
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
+impl From<WpMeta> for mysql_common::params::Params {
+    fn from(value: WpMeta) -> Self {
+        mysql_common::params::Params::Positional(value.insert_values())
+    }
+}
+
+#[cfg(feature = "query_sync")]
 impl Insertable for WpMeta {
-    #[cfg(feature = "query_sync")]
-    fn insert(self) -> Result<u64, mysql::Error> {
+    fn insert(self) -> Result<u64, WpError> {
         let mut conn = get_conn()?;
 
         let stmt = Self::prepare_insert_stmt(&mut conn)?;
 
         conn.exec_drop(stmt, self)?;
 
-        Ok(conn.exec_first("SELECT LAST_INSERT_ID();", ())?.unwrap())
+        last_insert_id(conn.fetch_first("SELECT LAST_INSERT_ID();", vec![])?)
     }
 
-    #[cfg(feature = "query_sync")]
-    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), mysql::Error> {
+    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), WpError> {
         let mut conn = get_conn()?;
 
         let stmt = Self::prepare_insert_stmt(&mut conn)?;
@@ -229,47 +426,200 @@ impl Insertable for WpMeta {
 
         Ok(())
     }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{collections::HashMap, future::Future, pin::Pin};
+
+    use mysql_async::prelude::*;
+    use serde::Serialize;
+
+    use crate::{
+        maybe_serialize,
+        query_builder::QueryAndValues,
+        sql::{
+            last_insert_id,
+            r#async::get_conn,
+            traits::r#async::{Executor, Insertable},
+        },
+        WpError,
+    };
+
+    use super::{
+        custom_query, delete_query, existing_meta_query, group_by_key, group_by_post, insert_query,
+        meta_cache_query, post_meta_query, write_meta_query, MetaCache, WpMeta, WpMetaResults,
+    };
+
+    /// Retrieves the meta of the post with the key, only the first one if `single` is set.
+    pub async fn get_post_meta(
+        post_id: u64,
+        meta_key: &str,
+        single: bool,
+    ) -> Result<WpMetaResults, WpError> {
+        let mut conn = get_conn().await?;
+
+        get_post_meta_with_connection(&mut conn, post_id, meta_key, single).await
+    }
+
+    /// Retrieves the meta of the post with the key using a mysql connection, or any other [`Executor`].
+    pub async fn get_post_meta_with_connection(
+        conn: &mut impl Executor,
+        post_id: u64,
+        meta_key: &str,
+        single: bool,
+    ) -> Result<WpMetaResults, WpError> {
+        let QueryAndValues(q, values) = post_meta_query(post_id, meta_key);
+
+        if single {
+            Ok(match conn.fetch_first(&q, values).await? {
+                Some(meta) => WpMetaResults::Single(meta),
+                None => WpMetaResults::Empty,
+            })
+        } else {
+            Ok(WpMetaResults::Array(conn.fetch(&q, values).await?))
+        }
+    }
 
-    #[cfg(feature = "query_async")]
-    fn batch<T>(
-        values: T,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), mysql_async::Error>>>>
+    /// Adds a meta to the post, returning the ID of the new meta. Non scalar values are PHP serialized.
+    pub async fn add_post_meta<T>(
+        post_id: u64,
+        meta_key: &str,
+        meta_value: T,
+    ) -> Result<u64, WpError>
     where
-        T: IntoIterator<Item = Self> + Send + 'static,
-        T::IntoIter: Send,
-        Self: Sized,
+        T: Serialize,
+    {
+        let meta_value = maybe_serialize(&meta_value)?;
+
+        WpMeta::new(post_id, meta_key, meta_value).insert().await
+    }
+
+    /// Updates all meta with the key, or only those with `prev_value`, adding the meta if the post has no meta with the key.
+    /// Returns false if nothing was changed.
+    pub async fn update_post_meta<T>(
+        post_id: u64,
+        meta_key: &str,
+        meta_value: T,
+        prev_value: Option<&str>,
+    ) -> Result<bool, WpError>
+    where
+        T: Serialize,
     {
-        let fut = async {
-            let mut conn = get_conn().await?;
+        let meta_value = maybe_serialize(&meta_value)?;
+        let mut conn = get_conn().await?;
+        let mut tx = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await?;
 
-            let stmt = Self::prepare_insert_stmt(&mut conn).await?;
+        let QueryAndValues(q, values) = existing_meta_query(post_id, meta_key);
+        let existing: Vec<String> = tx.fetch(&q, values).await?;
 
-            conn.exec_batch(stmt, values).await?;
+        let changed = match write_meta_query(post_id, meta_key, meta_value, prev_value, &existing) {
+            Some(QueryAndValues(q, values)) => {
+                tx.execute(&q, values).await?;
 
-            Ok(())
+                tx.affected_rows() > 0
+            }
+            None => false,
         };
+        tx.commit().await?;
 
-        Box::pin(fut)
+        Ok(changed)
     }
 
-    #[cfg(feature = "query_async")]
-    fn insert(
-        self,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64, mysql_async::Error>>>> {
-        let fut = async {
-            let mut conn = get_conn().await?;
+    /// Deletes all meta with the key from the post, or only those matching `meta_value`.
+    /// Returns false if no meta was deleted.
+    pub async fn delete_post_meta(
+        post_id: u64,
+        meta_key: &str,
+        meta_value: Option<&str>,
+    ) -> Result<bool, WpError> {
+        let mut conn = get_conn().await?;
 
-            let stmt = Self::prepare_insert_stmt(&mut conn).await?;
+        let QueryAndValues(q, values) = delete_query(post_id, meta_key, meta_value);
+        conn.exec_drop(q, values).await?;
 
-            conn.exec_drop(stmt, self).await?;
+        Ok(conn.affected_rows() > 0)
+    }
 
-            Ok(conn
-                .exec_first("SELECT LAST_INSERT_ID();", ())
-                .await?
-                .unwrap())
-        };
+    /// Retrieves all meta of the post, with the values of each key in the order they were added.
+    pub async fn get_post_custom(post_id: u64) -> Result<HashMap<String, Vec<String>>, WpError> {
+        let mut conn = get_conn().await?;
+
+        let meta: Vec<(String, String)> = conn.fetch(&custom_query(), vec![post_id.into()]).await?;
 
-        Box::pin(fut)
+        Ok(group_by_key(meta))
+    }
+
+    /// Allows multiple meta to be added in the same prepared statement, improving speed
+    pub async fn add_post_meta_bulk<T>(
+        post_id: u64,
+        meta_key_value_pairs: &[(&str, T)],
+    ) -> Result<(), WpError>
+    where
+        T: Serialize,
+    {
+        // Must collect to vector so it can be possibly sent in async runtime
+        let values = meta_key_value_pairs
+            .iter()
+            .map(|(meta_key, meta_value)| {
+                maybe_serialize(meta_value).map(|value| WpMeta::new(post_id, meta_key, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        WpMeta::batch(values).await
+    }
+
+    /// Retrieves the meta of all given posts with a single query.
+    pub async fn load_meta_cache(
+        conn: &mut impl Executor,
+        post_ids: &[u64],
+        table_prefix: Option<&str>,
+    ) -> Result<MetaCache, WpError> {
+        if post_ids.is_empty() {
+            return Ok(MetaCache::new());
+        }
+
+        let QueryAndValues(q, values) = meta_cache_query(post_ids, table_prefix)?;
+        let meta: Vec<(u64, String, String)> = conn.fetch(&q, values).await?;
+
+        Ok(group_by_post(meta))
+    }
+
+    impl Insertable for WpMeta {
+        fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), WpError>>>>
+        where
+            T: IntoIterator<Item = Self> + Send + 'static,
+            T::IntoIter: Send,
+            Self: Sized,
+        {
+            let fut = async {
+                let mut conn = get_conn().await?;
+
+                let stmt = conn.prep(insert_query()).await?;
+
+                conn.exec_batch(stmt, values).await?;
+
+                Ok(())
+            };
+
+            Box::pin(fut)
+        }
+
+        fn insert(self) -> Pin<Box<dyn Future<Output = Result<u64, WpError>>>> {
+            let fut = async {
+                let mut conn = get_conn().await?;
+
+                let stmt = conn.prep(insert_query()).await?;
+
+                conn.exec_drop(stmt, self).await?;
+
+                last_insert_id(conn.fetch_first("SELECT LAST_INSERT_ID();", vec![]).await?)
+            };
+
+            Box::pin(fut)
+        }
     }
 }
 
@@ -294,4 +644,111 @@ mod tests {
         assert_eq!(meta.meta_id, 0);
         assert_eq!(meta.meta_value, String::from("1"));
     }
+
+    #[test]
+    fn can_read_serialized_value() {
+        let meta = WpMeta::new(1, "my_list", "a:2:{i:0;s:3:\"red\";i:1;s:4:\"blue\";}");
+        let colors: Vec<String> = meta.value_as().unwrap();
+        assert_eq!(colors, vec!["red", "blue"]);
+
+        let meta = WpMeta::new(1, "my_number", 42);
+        let n: u64 = meta.value_as().unwrap();
+        assert_eq!(n, 42);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn can_group_meta_by_key() {
+        let meta = vec![
+            (String::from("color"), String::from("red")),
+            (String::from("size"), String::from("large")),
+            (String::from("color"), String::from("blue")),
+        ];
+
+        let custom = group_by_key(meta);
+
+        assert_eq!(custom.len(), 2);
+        assert_eq!(custom["color"], vec!["red", "blue"]);
+        assert_eq!(custom["size"], vec!["large"]);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn can_group_meta_by_post() {
+        let meta = vec![
+            (1, String::from("color"), String::from("red")),
+            (2, String::from("color"), String::from("blue")),
+            (1, String::from("color"), String::from("green")),
+        ];
+
+        let cache = group_by_post(meta);
+
+        assert_eq!(cache[&1]["color"], vec!["red", "green"]);
+        assert_eq!(cache[&2]["color"], vec!["blue"]);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn loads_meta_of_all_posts_in_one_query() {
+        let QueryAndValues(q, values) = meta_cache_query(&[1, 2, 3], Some("my_")).unwrap();
+
+        assert_eq!(q, "SELECT post_id, meta_key, meta_value FROM my_postmeta WHERE post_id IN (?,?,?) ORDER BY meta_id ASC;");
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn update_can_check_previous_value() {
+        let QueryAndValues(q, values) = update_query(1, "color", "red", None);
+        assert_eq!(
+            q,
+            "UPDATE wp_postmeta SET meta_value = ? WHERE post_id = ? AND meta_key = ?;"
+        );
+        assert_eq!(values.len(), 3);
+
+        let QueryAndValues(q, values) = update_query(1, "color", "red", Some("blue"));
+        assert!(q.ends_with(" AND meta_key = ? AND meta_value = ?;"));
+        assert_eq!(values[3], "blue".to_value());
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn existing_meta_is_locked() {
+        let QueryAndValues(q, values) = existing_meta_query(1, "color");
+        assert_eq!(
+            q,
+            "SELECT meta_value FROM wp_postmeta WHERE post_id = ? AND meta_key = ? FOR UPDATE;"
+        );
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn write_depends_on_existing_meta() {
+        let QueryAndValues(q, values) =
+            write_meta_query(1, "color", "red".to_string(), None, &[]).unwrap();
+        assert_eq!(q, insert_query());
+        assert_eq!(values[3], "red".to_value());
+
+        let existing = vec!["red".to_string()];
+        assert!(write_meta_query(1, "color", "red".to_string(), None, &existing).is_none());
+
+        let QueryAndValues(q, _) =
+            write_meta_query(1, "color", "red".to_string(), Some("red"), &existing).unwrap();
+        assert!(q.starts_with("UPDATE wp_postmeta"));
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn delete_can_match_value() {
+        let QueryAndValues(q, values) = delete_query(1, "color", None);
+        assert_eq!(
+            q,
+            "DELETE FROM wp_postmeta WHERE post_id = ? AND meta_key = ?;"
+        );
+        assert_eq!(values.len(), 2);
+
+        let QueryAndValues(q, _) = delete_query(1, "color", Some("red"));
+        assert!(q.ends_with(" AND meta_value = ?;"));
+    }
 }
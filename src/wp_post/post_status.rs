@@ -65,15 +65,15 @@ impl serde::Serialize for PostStatus {
     }
 }
 
-impl Into<mysql_common::Value> for PostStatus {
-    fn into(self) -> mysql_common::Value {
-        mysql_common::Value::Bytes(self.to_string().into_bytes())
+impl From<PostStatus> for mysql_common::Value {
+    fn from(value: PostStatus) -> Self {
+        mysql_common::Value::Bytes(value.to_string().into_bytes())
     }
 }
 
-impl Into<PostStatus> for String {
-    fn into(self) -> PostStatus {
-        PostStatus::from_str(&self).unwrap()
+impl From<String> for PostStatus {
+    fn from(value: String) -> Self {
+        PostStatus::from_str(&value).unwrap()
     }
 }
 
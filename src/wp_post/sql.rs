@@ -1,18 +1,24 @@
 #[cfg(feature = "query_sync")]
 use mysql::prelude::*;
-#[cfg(feature = "query_async")]
-use mysql_async::prelude::*;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use mysql_common::prelude::ToValue;
 
 use crate::sql::find_col;
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use crate::sql::{get_conn, traits::Insertable};
+use crate::sql::table_prefix::get_table_prefix;
+#[cfg(feature = "query_sync")]
+use crate::{
+    sql::{get_conn, last_insert_id, traits::Insertable},
+    WpError,
+};
 
 use super::{get_date_now, get_utc_date_now, WpPost};
 
-macro_rules! get_stmt {
-    ($conn: ident) => {
-        $conn.prep(
-            "INSERT INTO `wp_posts` (
+/// Statement inserting a post, with the values of `Into<Params> for WpPost` as placeholders.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn insert_query() -> String {
+    format!(
+        "INSERT INTO `{}posts` (
             /* For new posts, ID will be 0 so MySQL will create an ID for us */
             `ID`,
             `post_author`,
@@ -38,60 +44,52 @@ macro_rules! get_stmt {
             `post_mime_type`,
             `comment_count`
         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);",
-        )
-    };
+        get_table_prefix()
+    )
 }
 
+#[cfg(feature = "query_sync")]
 impl WpPost {
-    #[cfg(feature = "query_sync")]
     fn get_stmt(conn: &mut impl Queryable) -> Result<mysql::Statement, mysql::Error> {
-        get_stmt!(conn)
-    }
-    #[cfg(feature = "query_async")]
-    async fn get_stmt(
-        conn: &mut mysql_async::Conn,
-    ) -> Result<mysql_async::Statement, mysql_async::Error> {
-        get_stmt!(conn).await
+        conn.prep(insert_query())
     }
 
-    #[cfg(feature = "query_sync")]
-    pub fn insert(self) -> Result<u64, mysql::Error> {
+    pub fn insert(self) -> Result<u64, WpError> {
         <Self as Insertable>::insert(self)
     }
 
-    #[cfg(feature = "query_sync")]
-    pub fn insert_bulk(v: Vec<Self>) -> Result<(), mysql::Error> {
+    pub fn insert_bulk(v: Vec<Self>) -> Result<(), WpError> {
         <Self as Insertable>::batch(v)
     }
 }
 
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-impl Into<mysql_common::params::Params> for WpPost {
-    fn into(self) -> mysql_common::params::Params {
+impl From<WpPost> for mysql_common::params::Params {
+    fn from(value: WpPost) -> Self {
         mysql_common::params::Params::Positional(vec![
-            self.ID.to_value(),
-            self.post_author.to_value(),
-            self.post_date.to_value(),
-            self.post_date_gmt.to_value(),
-            self.post_content.to_value(),
-            self.post_title.to_value(),
-            self.post_excerpt.to_value(),
-            self.post_status.to_value(),
-            self.comment_status.to_value(),
-            self.ping_status.to_value(),
-            self.post_password.to_value(),
-            self.post_name.to_value(),
-            self.to_ping.to_value(),
-            self.pinged.to_value(),
-            self.post_modified.to_value(),
-            self.post_modified_gmt.to_value(),
-            self.post_content_filtered.to_value(),
-            self.post_parent.to_value(),
-            self.guid.to_value(),
-            self.menu_order.to_value(),
-            self.post_type.to_value(),
-            self.post_mime_type.to_value(),
-            self.comment_count.to_value(),
+            value.ID.to_value(),
+            value.post_author.to_value(),
+            value.post_date.to_value(),
+            value.post_date_gmt.to_value(),
+            value.post_content.to_value(),
+            value.post_title.to_value(),
+            value.post_excerpt.to_value(),
+            value.post_status.to_value(),
+            value.comment_status.to_value(),
+            value.ping_status.to_value(),
+            value.post_password.to_value(),
+            value.post_name.to_value(),
+            value.to_ping.to_value(),
+            value.pinged.to_value(),
+            value.post_modified.to_value(),
+            value.post_modified_gmt.to_value(),
+            value.post_content_filtered.to_value(),
+            value.post_parent.to_value(),
+            value.guid.to_value(),
+            value.menu_order.to_value(),
+            value.post_type.to_value(),
+            value.post_mime_type.to_value(),
+            value.comment_count.to_value(),
         ])
     }
 }
@@ -137,10 +135,9 @@ impl mysql_common::prelude::FromRow for WpPost {
     }
 }
 
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+#[cfg(feature = "query_sync")]
 impl Insertable for WpPost {
-    #[cfg(feature = "query_sync")]
-    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), mysql::Error> {
+    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), WpError> {
         let mut conn = get_conn()?;
 
         let stmt = Self::get_stmt(&mut conn)?;
@@ -155,76 +152,82 @@ impl Insertable for WpPost {
         Ok(())
     }
 
-    #[cfg(feature = "query_async")]
-    fn batch<T>(
-        values: T,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), mysql_async::Error>>>>
-    where
-        T: IntoIterator<Item = Self> + Send + 'static,
-        T::IntoIter: Send,
-        Self: Sized,
-    {
-        let fut = async {
-            let mut conn = get_conn().await?;
+    fn insert(self) -> Result<u64, WpError> {
+        let mut conn = get_conn()?;
 
-            let stmt = Self::get_stmt(&mut conn).await?;
+        let stmt = Self::get_stmt(&mut conn)?;
 
-            conn.exec_batch(
-                stmt,
-                values
-                    .into_iter()
-                    .map(|post| -> mysql_common::params::Params { post.into() }),
-            )
-            .await?;
+        conn.exec_drop(stmt, self)?;
 
-            Ok(())
-        };
+        let post_id = last_insert_id(conn.exec_first("SELECT LAST_INSERT_ID();", ())?)?;
 
-        Box::pin(fut)
+        Ok(post_id)
     }
+}
 
-    #[cfg(feature = "query_sync")]
-    fn insert(self) -> Result<u64, mysql::Error> {
-        let mut conn = get_conn()?;
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{future::Future, pin::Pin};
 
-        let stmt = Self::get_stmt(&mut conn)?;
+    use mysql_async::prelude::*;
 
-        conn.exec_drop(stmt, self)?;
+    use crate::{
+        sql::{last_insert_id, r#async::get_conn, traits::r#async::Insertable},
+        WpError,
+    };
 
-        let post_id: u64 = conn.exec_first("SELECT LAST_INSERT_ID();", ())?.unwrap();
+    use super::{insert_query, WpPost};
 
-        Ok(post_id)
-    }
+    impl Insertable for WpPost {
+        fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), WpError>>>>
+        where
+            T: IntoIterator<Item = Self> + Send + 'static,
+            T::IntoIter: Send,
+            Self: Sized,
+        {
+            let fut = async {
+                let mut conn = get_conn().await?;
+
+                let stmt = conn.prep(insert_query()).await?;
 
-    #[cfg(feature = "query_async")]
-    fn insert(
-        self,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64, mysql_async::Error>>>> {
-        let fut = async {
-            let mut conn = get_conn().await?;
+                conn.exec_batch(
+                    stmt,
+                    values
+                        .into_iter()
+                        .map(|post| -> mysql_common::params::Params { post.into() }),
+                )
+                .await?;
 
-            let stmt = Self::get_stmt(&mut conn).await?;
+                Ok(())
+            };
+
+            Box::pin(fut)
+        }
 
-            conn.exec_drop(stmt, self).await?;
+        fn insert(self) -> Pin<Box<dyn Future<Output = Result<u64, WpError>>>> {
+            let fut = async {
+                let mut conn = get_conn().await?;
 
-            let post_id: u64 = conn
-                .exec_first("SELECT LAST_INSERT_ID();", ())
-                .await?
-                .unwrap();
+                let stmt = conn.prep(insert_query()).await?;
 
-            Ok(post_id)
-        };
+                conn.exec_drop(stmt, self).await?;
 
-        Box::pin(fut)
+                let post_id =
+                    last_insert_id(conn.exec_first("SELECT LAST_INSERT_ID();", ()).await?)?;
+
+                Ok(post_id)
+            };
+
+            Box::pin(fut)
+        }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, any(feature = "query_sync", feature = "query_async")))]
 mod tests {
     use super::*;
 
     #[test]
-    #[cfg(any(feature = "query_sync", feature = "query_async"))]
     fn can_convert_post_to_params() {
         let mut post = WpPost::new(1);
         post.post_title = String::from("My Post");
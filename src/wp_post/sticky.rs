@@ -0,0 +1,190 @@
+use crate::{
+    maybe_unserialize, params::Params, sql::table_prefix::table_prefix_or_global,
+    wp_post::post_status::PostStatus, PostType, SqlOrder, WpError, WpOrderBy,
+};
+#[cfg(feature = "query_sync")]
+use crate::{
+    query_builder::{QueryAndValues, QueryBuilder},
+    sql::traits::Executor,
+};
+
+use super::WpPost;
+
+/// Parses the PHP serialized `sticky_posts` option, e.g. `a:2:{i:0;i:12;i:1;i:34;}`.
+///
+/// Invalid values are treated as no sticky posts.
+pub fn parse_sticky_posts(option_value: &str) -> Vec<u64> {
+    maybe_unserialize(option_value).unwrap_or_default()
+}
+
+/// Retrieves the IDs of all sticky posts from the `sticky_posts` option.
+#[cfg(feature = "query_sync")]
+pub fn get_sticky_posts(
+    conn: &mut impl Executor,
+    table_prefix: Option<&str>,
+) -> Result<Vec<u64>, WpError> {
+    let option: Option<String> = conn.fetch_first(&sticky_posts_query(table_prefix)?, vec![])?;
+
+    Ok(option.map(|o| parse_sticky_posts(&o)).unwrap_or_default())
+}
+
+/// Moves sticky posts to the front of the results like WP_Query, then inserts the published sticky posts
+/// that were not part of the results after them.
+#[cfg(feature = "query_sync")]
+pub fn stick_posts(
+    conn: &mut impl Executor,
+    posts: &mut Vec<WpPost>,
+    table_prefix: Option<&str>,
+) -> Result<(), WpError> {
+    let sticky_posts = get_sticky_posts(conn, table_prefix)?;
+
+    let (offset, missing) = move_sticky_posts_to_front(posts, &sticky_posts);
+
+    if !missing.is_empty() {
+        let (QueryAndValues(q, values), _) =
+            QueryBuilder::new(missing_sticky_params(missing, table_prefix)).query()?;
+
+        let stickies: Vec<WpPost> = conn.fetch(&q, values)?;
+
+        posts.splice(offset..offset, stickies);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use crate::{
+        query_builder::{QueryAndValues, QueryBuilder},
+        sql::traits::r#async::Executor,
+        wp_post::WpPost,
+        WpError,
+    };
+
+    use super::{
+        missing_sticky_params, move_sticky_posts_to_front, parse_sticky_posts, sticky_posts_query,
+    };
+
+    /// Retrieves the IDs of all sticky posts from the `sticky_posts` option.
+    pub async fn get_sticky_posts(
+        conn: &mut impl Executor,
+        table_prefix: Option<&str>,
+    ) -> Result<Vec<u64>, WpError> {
+        let option: Option<String> = conn
+            .fetch_first(&sticky_posts_query(table_prefix)?, vec![])
+            .await?;
+
+        Ok(option.map(|o| parse_sticky_posts(&o)).unwrap_or_default())
+    }
+
+    /// Moves sticky posts to the front of the results like WP_Query, then inserts the published sticky posts
+    /// that were not part of the results after them.
+    pub async fn stick_posts(
+        conn: &mut impl Executor,
+        posts: &mut Vec<WpPost>,
+        table_prefix: Option<&str>,
+    ) -> Result<(), WpError> {
+        let sticky_posts = get_sticky_posts(conn, table_prefix).await?;
+
+        let (offset, missing) = move_sticky_posts_to_front(posts, &sticky_posts);
+
+        if !missing.is_empty() {
+            let (QueryAndValues(q, values), _) =
+                QueryBuilder::new(missing_sticky_params(missing, table_prefix)).query()?;
+
+            let stickies: Vec<WpPost> = conn.fetch(&q, values).await?;
+
+            posts.splice(offset..offset, stickies);
+        }
+
+        Ok(())
+    }
+}
+
+/// Moves the sticky posts found in the results to the front, keeping their order.
+/// Returns the number of posts moved and the sticky post IDs not found in the results.
+pub fn move_sticky_posts_to_front(
+    posts: &mut Vec<WpPost>,
+    sticky_posts: &[u64],
+) -> (usize, Vec<u64>) {
+    let (mut sticky, rest): (Vec<WpPost>, Vec<WpPost>) = posts
+        .drain(..)
+        .partition(|post| sticky_posts.contains(&post.ID));
+
+    let missing = sticky_posts
+        .iter()
+        .filter(|id| !sticky.iter().any(|post| post.ID == **id))
+        .copied()
+        .collect();
+    let offset = sticky.len();
+
+    sticky.extend(rest);
+    *posts = sticky;
+
+    (offset, missing)
+}
+
+fn sticky_posts_query(table_prefix: Option<&str>) -> Result<String, WpError> {
+    Ok(format!(
+        "SELECT option_value FROM {}options WHERE option_name = 'sticky_posts'",
+        table_prefix_or_global(table_prefix)?
+    ))
+}
+
+/// Params for the published sticky posts missing from the results, newest first like `get_posts`.
+fn missing_sticky_params(ids: Vec<u64>, table_prefix: Option<&str>) -> Params<'_> {
+    let mut params = Params::new();
+    params.posts_per_page = Some(ids.len() as u64);
+    params.post__in = Some(ids);
+    params.post_type = Some(vec![PostType::Post]);
+    params.post_status = Some(PostStatus::Publish);
+    params.orderby = Some(vec![(WpOrderBy::Date, Some(SqlOrder::Desc))]);
+    params.ignore_sticky_posts = Some(true);
+    params.no_found_rows = Some(true);
+    params.table_prefix = table_prefix;
+
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(id: u64) -> WpPost {
+        let mut post = WpPost::new(1);
+        post.ID = id;
+
+        post
+    }
+
+    #[test]
+    fn can_parse_sticky_posts_option() {
+        assert_eq!(parse_sticky_posts("a:2:{i:0;i:12;i:1;i:34;}"), vec![12, 34]);
+        assert_eq!(parse_sticky_posts("a:0:{}"), Vec::<u64>::new());
+        assert_eq!(parse_sticky_posts("not serialized"), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn moves_sticky_posts_to_front() {
+        let mut posts = vec![post(1), post(2), post(3), post(4)];
+
+        let (offset, missing) = move_sticky_posts_to_front(&mut posts, &[3, 9, 4]);
+
+        assert_eq!(offset, 2);
+        assert_eq!(missing, vec![9]);
+        assert_eq!(
+            posts.iter().map(|p| p.ID).collect::<Vec<u64>>(),
+            vec![3, 4, 1, 2]
+        );
+    }
+
+    #[test]
+    fn missing_sticky_posts_are_published_posts() {
+        let params = missing_sticky_params(vec![9, 10], Some("wp_"));
+
+        assert_eq!(params.post__in, Some(vec![9, 10]));
+        assert_eq!(params.posts_per_page, Some(2));
+        assert_eq!(params.post_status, Some(PostStatus::Publish));
+        assert!(!params.includes_sticky_posts());
+    }
+}
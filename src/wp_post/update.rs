@@ -0,0 +1,554 @@
+use mysql_common::{prelude::ToValue, Value};
+use std::str::FromStr;
+
+#[cfg(feature = "query_sync")]
+use crate::sql::{exec_transaction, get_conn, traits::Executor};
+use crate::{query_builder::QueryAndValues, sql::table_prefix::get_table_prefix, WpError};
+
+use super::{get_date_now, get_utc_date_now, post_status::PostStatus, WpPost};
+
+/// Meta key holding the status of a post before it was trashed.
+pub const TRASH_META_STATUS: &str = "_wp_trash_meta_status";
+/// Meta key holding the unix timestamp of when a post was trashed.
+pub const TRASH_META_TIME: &str = "_wp_trash_meta_time";
+
+/// A column of `wp_posts` written by [`WpPost::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostField {
+    Author,
+    Date,
+    DateGmt,
+    Content,
+    Title,
+    Excerpt,
+    Status,
+    CommentStatus,
+    PingStatus,
+    Password,
+    Name,
+    ToPing,
+    Pinged,
+    ContentFiltered,
+    Parent,
+    Guid,
+    MenuOrder,
+    Type,
+    MimeType,
+}
+
+impl PostField {
+    /// Every field which can be updated, to overwrite the whole row.
+    /// `comment_count` is left out, it is maintained when comments are inserted, moderated or deleted.
+    pub const ALL: [Self; 19] = [
+        Self::Author,
+        Self::Date,
+        Self::DateGmt,
+        Self::Content,
+        Self::Title,
+        Self::Excerpt,
+        Self::Status,
+        Self::CommentStatus,
+        Self::PingStatus,
+        Self::Password,
+        Self::Name,
+        Self::ToPing,
+        Self::Pinged,
+        Self::ContentFiltered,
+        Self::Parent,
+        Self::Guid,
+        Self::MenuOrder,
+        Self::Type,
+        Self::MimeType,
+    ];
+
+    fn column(self) -> &'static str {
+        match self {
+            Self::Author => "post_author",
+            Self::Date => "post_date",
+            Self::DateGmt => "post_date_gmt",
+            Self::Content => "post_content",
+            Self::Title => "post_title",
+            Self::Excerpt => "post_excerpt",
+            Self::Status => "post_status",
+            Self::CommentStatus => "comment_status",
+            Self::PingStatus => "ping_status",
+            Self::Password => "post_password",
+            Self::Name => "post_name",
+            Self::ToPing => "to_ping",
+            Self::Pinged => "pinged",
+            Self::ContentFiltered => "post_content_filtered",
+            Self::Parent => "post_parent",
+            Self::Guid => "guid",
+            Self::MenuOrder => "menu_order",
+            Self::Type => "post_type",
+            Self::MimeType => "post_mime_type",
+        }
+    }
+}
+
+impl WpPost {
+    /// Writes the given fields of the post to the row with the post's ID, leaving the other columns unchanged.
+    /// `post_modified` and `post_modified_gmt` are always set to the current time.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use wp_query_rs::PostField;
+    ///
+    /// post.post_title = String::from("Updated title");
+    /// post.update(&[PostField::Title])?;
+    /// ```
+    #[cfg(feature = "query_sync")]
+    pub fn update(&mut self, fields: &[PostField]) -> Result<(), WpError> {
+        self.touch();
+
+        let QueryAndValues(q, values) = self.update_query(fields);
+        get_conn()?.execute(&q, values)
+    }
+
+    /// Moves the post to the trash like `wp_trash_post`, saving its current status in the `_wp_trash_meta_status` meta.
+    /// Does nothing if the post is already trashed.
+    ///
+    /// The current status is read from the database in the transaction, not from the struct.
+    #[cfg(feature = "query_sync")]
+    pub fn trash(&mut self) -> Result<(), WpError> {
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        let QueryAndValues(q, values) = self.current_status_query();
+        let current = current_status(self.ID, tx.fetch_first(&q, values)?)?;
+
+        for QueryAndValues(q, values) in self.trash_queries(&current) {
+            tx.execute(&q, values)?;
+        }
+        tx.commit()?;
+
+        self.post_status = PostStatus::Trash;
+
+        Ok(())
+    }
+
+    /// Restores a trashed post to the status saved in `_wp_trash_meta_status` like `wp_untrash_post`,
+    /// or to a draft if none was saved. Does nothing if the post is not trashed.
+    ///
+    /// The current status is read from the database in the transaction, not from the struct.
+    #[cfg(feature = "query_sync")]
+    pub fn untrash(&mut self) -> Result<(), WpError> {
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+
+        let QueryAndValues(q, values) = self.current_status_query();
+        let mut status = current_status(self.ID, tx.fetch_first(&q, values)?)?;
+
+        if status == PostStatus::Trash {
+            let QueryAndValues(q, values) = self.trash_status_query();
+            let statements;
+            (status, statements) = self.untrash_queries(tx.fetch_first(&q, values)?);
+
+            for QueryAndValues(q, values) in statements {
+                tx.execute(&q, values)?;
+            }
+        }
+        tx.commit()?;
+
+        self.post_status = status;
+
+        Ok(())
+    }
+
+    /// Permanently deletes the post like `wp_delete_post`, along with its meta, term relationships and comments.
+    /// Children of the same post type are moved to the post's parent.
+    ///
+    /// All rows are deleted in a single transaction.
+    #[cfg(feature = "query_sync")]
+    pub fn delete(self) -> Result<(), WpError> {
+        exec_transaction(self.delete_queries())
+    }
+
+    fn touch(&mut self) {
+        self.post_modified = get_date_now();
+        self.post_modified_gmt = get_utc_date_now();
+    }
+
+    fn field_value(&self, field: PostField) -> Value {
+        match field {
+            PostField::Author => self.post_author.to_value(),
+            PostField::Date => self.post_date.to_value(),
+            PostField::DateGmt => self.post_date_gmt.to_value(),
+            PostField::Content => self.post_content.to_value(),
+            PostField::Title => self.post_title.to_value(),
+            PostField::Excerpt => self.post_excerpt.to_value(),
+            PostField::Status => self.post_status.to_value(),
+            PostField::CommentStatus => self.comment_status.to_value(),
+            PostField::PingStatus => self.ping_status.to_value(),
+            PostField::Password => self.post_password.to_value(),
+            PostField::Name => self.post_name.to_value(),
+            PostField::ToPing => self.to_ping.to_value(),
+            PostField::Pinged => self.pinged.to_value(),
+            PostField::ContentFiltered => self.post_content_filtered.to_value(),
+            PostField::Parent => self.post_parent.to_value(),
+            PostField::Guid => self.guid.to_value(),
+            PostField::MenuOrder => self.menu_order.to_value(),
+            PostField::Type => self.post_type.to_value(),
+            PostField::MimeType => self.post_mime_type.to_value(),
+        }
+    }
+
+    /// Sets the fields, each once, and the modified dates of the post's row.
+    fn update_query(&self, fields: &[PostField]) -> QueryAndValues {
+        let prefix = get_table_prefix();
+        let mut columns = vec![];
+        let mut values = vec![];
+
+        for (i, field) in fields.iter().enumerate() {
+            if !fields[..i].contains(field) {
+                columns.push(format!("`{}` = ?", field.column()));
+                values.push(self.field_value(*field));
+            }
+        }
+        columns.push(String::from("`post_modified` = ?, `post_modified_gmt` = ?"));
+        values.push(self.post_modified.to_value());
+        values.push(self.post_modified_gmt.to_value());
+        values.push(Value::UInt(self.ID));
+
+        QueryAndValues(
+            format!(
+                "UPDATE `{prefix}posts` SET {} WHERE `ID` = ?;",
+                columns.join(", ")
+            ),
+            values,
+        )
+    }
+
+    /// Selects the status of the post's row, locking it until the end of the transaction.
+    fn current_status_query(&self) -> QueryAndValues {
+        QueryAndValues(
+            format!(
+                "SELECT post_status FROM {}posts WHERE ID = ? FOR UPDATE;",
+                get_table_prefix()
+            ),
+            vec![Value::UInt(self.ID)],
+        )
+    }
+
+    /// The statements moving the post to the trash from its current status, none if it is already trashed.
+    fn trash_queries(&self, current: &PostStatus) -> Vec<QueryAndValues> {
+        if *current == PostStatus::Trash {
+            return vec![];
+        }
+
+        let prefix = get_table_prefix();
+        let id = Value::UInt(self.ID);
+        let now = chrono::Utc::now().timestamp();
+
+        vec![
+            QueryAndValues(
+                format!(
+                    "INSERT INTO {prefix}postmeta (post_id, meta_key, meta_value) VALUES (?, ?, ?), (?, ?, ?);"
+                ),
+                vec![
+                    id.clone(),
+                    TRASH_META_STATUS.to_value(),
+                    current.to_string().to_value(),
+                    id.clone(),
+                    TRASH_META_TIME.to_value(),
+                    now.to_string().to_value(),
+                ],
+            ),
+            status_query(prefix, self.ID, &PostStatus::Trash),
+        ]
+    }
+
+    /// Selects the status saved when the post was trashed.
+    fn trash_status_query(&self) -> QueryAndValues {
+        QueryAndValues(
+            format!(
+                "SELECT meta_value FROM {}postmeta WHERE post_id = ? AND meta_key = ? ORDER BY meta_id DESC LIMIT 1;",
+                get_table_prefix()
+            ),
+            vec![Value::UInt(self.ID), TRASH_META_STATUS.to_value()],
+        )
+    }
+
+    /// The status restored from the status saved when the post was trashed, with the statements restoring it.
+    fn untrash_queries(&self, previous: Option<String>) -> (PostStatus, Vec<QueryAndValues>) {
+        let prefix = get_table_prefix();
+        let status = untrashed_status(previous);
+
+        let statements = vec![
+            status_query(prefix, self.ID, &status),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}postmeta WHERE post_id = ? AND meta_key IN (?, ?);"),
+                vec![
+                    Value::UInt(self.ID),
+                    TRASH_META_STATUS.to_value(),
+                    TRASH_META_TIME.to_value(),
+                ],
+            ),
+        ];
+
+        (status, statements)
+    }
+
+    fn delete_queries(&self) -> Vec<QueryAndValues> {
+        let prefix = get_table_prefix();
+        let id = Value::UInt(self.ID);
+
+        vec![
+            QueryAndValues(
+                format!("UPDATE {prefix}posts SET post_parent = ? WHERE post_parent = ? AND post_type = ?;"),
+                vec![
+                    Value::UInt(self.post_parent),
+                    id.clone(),
+                    self.post_type.to_value(),
+                ],
+            ),
+            QueryAndValues(
+                format!(
+                    "UPDATE {prefix}term_taxonomy SET count = count - 1 WHERE count > 0 AND term_taxonomy_id IN (
+                    SELECT term_taxonomy_id FROM {prefix}term_relationships WHERE object_id = ?
+                );"
+                ),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}term_relationships WHERE object_id = ?;"),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!(
+                    "DELETE FROM {prefix}commentmeta WHERE comment_id IN (
+                    SELECT comment_ID FROM {prefix}comments WHERE comment_post_ID = ?
+                );"
+                ),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}comments WHERE comment_post_ID = ?;"),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}postmeta WHERE post_id = ?;"),
+                vec![id.clone()],
+            ),
+            QueryAndValues(
+                format!("DELETE FROM {prefix}posts WHERE ID = ?;"),
+                vec![id],
+            ),
+        ]
+    }
+}
+
+fn status_query(prefix: &str, post_id: u64, status: &PostStatus) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "UPDATE {prefix}posts SET post_status = ?, post_modified = ?, post_modified_gmt = ? WHERE ID = ?;"
+        ),
+        vec![
+            status.to_string().to_value(),
+            get_date_now().to_value(),
+            get_utc_date_now().to_value(),
+            Value::UInt(post_id),
+        ],
+    )
+}
+
+/// The status of the post's row read in the transaction.
+fn current_status(post_id: u64, status: Option<String>) -> Result<PostStatus, WpError> {
+    status
+        .and_then(|status| PostStatus::from_str(&status).ok())
+        .ok_or_else(|| WpError::NotFound(format!("post {post_id}")))
+}
+
+/// The status to restore from the `_wp_trash_meta_status` meta, defaulting to draft.
+fn untrashed_status(previous: Option<String>) -> PostStatus {
+    match previous.and_then(|s| PostStatus::from_str(&s).ok()) {
+        Some(PostStatus::Any | PostStatus::Trash) | None => PostStatus::Draft,
+        Some(status) => status,
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{
+            r#async::{exec_transaction, get_conn},
+            traits::r#async::Executor,
+        },
+        wp_post::{post_status::PostStatus, r#async::WpPostExt, WpPost},
+        WpError,
+    };
+
+    use super::{current_status, PostField};
+
+    impl WpPostExt for WpPost {
+        async fn update(&mut self, fields: &[PostField]) -> Result<(), WpError> {
+            self.touch();
+
+            let QueryAndValues(q, values) = self.update_query(fields);
+            get_conn().await?.execute(&q, values).await
+        }
+
+        async fn trash(&mut self) -> Result<(), WpError> {
+            let mut conn = get_conn().await?;
+            let mut tx = conn
+                .start_transaction(mysql_async::TxOpts::default())
+                .await?;
+
+            let QueryAndValues(q, values) = self.current_status_query();
+            let current = current_status(self.ID, tx.fetch_first(&q, values).await?)?;
+
+            for QueryAndValues(q, values) in self.trash_queries(&current) {
+                tx.execute(&q, values).await?;
+            }
+            tx.commit().await?;
+
+            self.post_status = PostStatus::Trash;
+
+            Ok(())
+        }
+
+        async fn untrash(&mut self) -> Result<(), WpError> {
+            let mut conn = get_conn().await?;
+            let mut tx = conn
+                .start_transaction(mysql_async::TxOpts::default())
+                .await?;
+
+            let QueryAndValues(q, values) = self.current_status_query();
+            let mut status = current_status(self.ID, tx.fetch_first(&q, values).await?)?;
+
+            if status == PostStatus::Trash {
+                let QueryAndValues(q, values) = self.trash_status_query();
+                let statements;
+                (status, statements) = self.untrash_queries(tx.fetch_first(&q, values).await?);
+
+                for QueryAndValues(q, values) in statements {
+                    tx.execute(&q, values).await?;
+                }
+            }
+            tx.commit().await?;
+
+            self.post_status = status;
+
+            Ok(())
+        }
+
+        async fn delete(self) -> Result<(), WpError> {
+            exec_transaction(self.delete_queries()).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_query_sets_given_fields_by_id() {
+        let mut post = WpPost::new(1);
+        post.ID = 42;
+        post.post_title = String::from("Updated");
+
+        let QueryAndValues(q, values) =
+            post.update_query(&[PostField::Title, PostField::Status, PostField::Title]);
+
+        assert_eq!(
+            q,
+            "UPDATE `wp_posts` SET `post_title` = ?, `post_status` = ?, `post_modified` = ?, `post_modified_gmt` = ? WHERE `ID` = ?;"
+        );
+        assert_eq!(values.len(), 5);
+        assert_eq!(values[0], Value::Bytes(b"Updated".to_vec()));
+        assert_eq!(values.last(), Some(&Value::UInt(42)));
+    }
+
+    #[test]
+    fn updating_all_fields_leaves_comment_count() {
+        let QueryAndValues(q, values) = WpPost::new(1).update_query(&PostField::ALL);
+
+        assert!(q.starts_with("UPDATE `wp_posts` SET `post_author` = ?, `post_date` = ?"));
+        assert!(!q.contains("comment_count"));
+        assert_eq!(values.len(), 22);
+    }
+
+    #[test]
+    fn trash_saves_current_status() {
+        let mut post = WpPost::new(1);
+        post.ID = 3;
+        // A stale struct does not decide the saved status
+        post.post_status = PostStatus::Draft;
+
+        let queries = post.trash_queries(&PostStatus::Publish);
+
+        assert_eq!(queries[0].1[1], Value::Bytes(TRASH_META_STATUS.into()));
+        assert_eq!(queries[0].1[2], Value::Bytes(b"publish".to_vec()));
+        assert_eq!(queries[1].1[0], Value::Bytes(b"trash".to_vec()));
+        assert_eq!(queries[1].1[3], Value::UInt(3));
+    }
+
+    #[test]
+    fn trashed_posts_are_not_trashed_again() {
+        let post = WpPost::new(1);
+
+        assert!(post.trash_queries(&PostStatus::Trash).is_empty());
+        assert!(post.current_status_query().0.ends_with("FOR UPDATE;"));
+    }
+
+    #[test]
+    fn current_status_must_exist() {
+        assert_eq!(
+            current_status(1, Some(String::from("trash"))).unwrap(),
+            PostStatus::Trash
+        );
+        assert!(matches!(current_status(1, None), Err(WpError::NotFound(_))));
+    }
+
+    #[test]
+    fn untrash_queries_restore_saved_status() {
+        let mut post = WpPost::new(1);
+        post.ID = 3;
+
+        let (status, queries) = post.untrash_queries(Some(String::from("private")));
+
+        assert_eq!(status, PostStatus::Private);
+        assert_eq!(queries[0].1[0], Value::Bytes(b"private".to_vec()));
+        assert!(queries[1].0.starts_with("DELETE FROM wp_postmeta"));
+    }
+
+    #[test]
+    fn untrash_restores_saved_status() {
+        assert_eq!(
+            untrashed_status(Some(String::from("publish"))),
+            PostStatus::Publish
+        );
+        assert_eq!(
+            untrashed_status(Some(String::from("unknown"))),
+            PostStatus::Draft
+        );
+        assert_eq!(untrashed_status(None), PostStatus::Draft);
+    }
+
+    #[test]
+    fn delete_cascades_to_related_rows() {
+        let mut post = WpPost::new(1);
+        post.ID = 7;
+
+        let queries = post.delete_queries();
+        let statements = [
+            "UPDATE wp_posts SET post_parent",
+            "UPDATE wp_term_taxonomy SET count",
+            "DELETE FROM wp_term_relationships",
+            "DELETE FROM wp_commentmeta",
+            "DELETE FROM wp_comments",
+            "DELETE FROM wp_postmeta",
+            "DELETE FROM wp_posts",
+        ];
+
+        assert_eq!(queries.len(), statements.len());
+        for (QueryAndValues(q, _), statement) in queries.iter().zip(statements) {
+            assert!(q.starts_with(statement));
+        }
+        assert!(queries
+            .iter()
+            .all(|QueryAndValues(_, values)| values.contains(&Value::UInt(7))));
+    }
+}
@@ -1,4 +1,5 @@
 mod parse_request;
+mod permalink;
 mod permalink_structure;
 mod rewrite_code;
 mod rewrite_filters;
@@ -7,35 +8,45 @@ mod rewrite_rule;
 use std::cell::RefCell;
 
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use crate::sql::get_conn;
+use crate::sql::table_prefix::get_table_prefix;
 #[cfg(feature = "query_sync")]
-use mysql::prelude::*;
-#[cfg(feature = "query_async")]
-use mysql_async::prelude::*;
+use crate::{
+    sql::{get_conn, traits::Executor},
+    WpError,
+};
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use std::{cell::Ref, ops::Deref};
+use std::cell::Ref;
 
-#[cfg(any(feature = "query_sync", feature = "query_async"))]
+#[cfg(feature = "query_sync")]
 pub use parse_request::parse_request;
+#[cfg(feature = "query_sync")]
+pub use permalink::{get_author_posts_url, get_permalink, get_term_link};
+pub use permalink_structure::PermalinkStructureParseError;
 pub use rewrite_code::RewriteCode;
 pub use rewrite_filters::RewriteFilters;
 
+use crate::WpOptions;
+
 use self::{
     permalink_structure::PermalinkStructure, rewrite_filters::RewriteFilterCache,
     rewrite_rule::RewriteRules,
 };
 
 pub struct WpRewrite {
+    /// The site address from the `home` option, without a trailing slash. Prepended to all links.
+    home: String,
     /// The permalink structure as in the database. This is what you set on the Permalink Options page, and includes ‘tags’ like %year%, %month% and %post_id%.
     permalink_structure: PermalinkStructure,
     /// Anything to be inserted before category archive URLs. Defaults to ‘category/’.
     category_base: String,
     /// Structure for category archive URLs. This is just the $category_base plus ‘%category%’.
-    category_structure: String,
+    _category_structure: String,
+    /// Anything to be inserted before tag archive URLs. Defaults to ‘tag/’.
+    tag_base: String,
     /// Anything to be inserted before author archive URLs. Defaults to ‘author/’.
     author_base: String,
     /// Structure for author archive URLs. This is just the $author_base plus ‘%author%’.
-    author_structure: String,
+    _author_structure: String,
     /// Anything to be inserted before pagination indices. Defaults to ‘page/’.
     pagination_base: String,
     /// Supported feeds names (rdf, rss, atom) Use add_feed to override or add.
@@ -47,22 +58,22 @@ pub struct WpRewrite {
     /// Anything to be inserted before searches. Defaults to ‘search/’.
     search_base: String,
     /// Structure for search URLs. This is just the $search_base plus ‘%search%’.
-    search_structure: String,
+    _search_structure: String,
     /// Anything to be inserted just before the $feed_structure to get the latest comments feed. Defaults to ‘comments’.
     comments_base: String,
     /// The structure for the latest comments feed. This is just $comments_base plus $feed_base plus ‘%feed%’.
-    comments_feed_structure: String,
+    _comments_feed_structure: String,
     /// Structure for dated archive URLs. Tries to be ‘%year%/%monthnum%/%day%’, ‘%day%/%monthnum%/%year%’ or ‘%monthnum%/%day%/%year%’,
     /// but if none of these are detected in your $permalink_structure, defaults to ‘%year%/%monthnum%/%day%’.
     /// Various functions use this structure to obtain less specific structures: for example, get_year_permastruct() simply
     /// removes the ‘%monthnum%’ and ‘%day%’ tags from $date_structure.
-    date_structure: String,
+    _date_structure: String,
     /// Structure for Pages. Just ‘%pagename%’.
-    page_structure: String,
+    _page_structure: String,
     /// Anything up to the start of the first tag in your $permalink_structure.
-    front: String,
+    _front: String,
     /// The root of your WordPress install. Prepended to all structures.
-    root: String,
+    _root: String,
     rules: RefCell<Option<RewriteRules>>,
     /// Know whether the rules were fetched from the DB or not, do not refetch if the results were bad/None
     rules_init: RefCell<bool>,
@@ -71,48 +82,28 @@ pub struct WpRewrite {
     hooks: RewriteFilterCache,
 }
 
-macro_rules! wp_rewrite_rules {
-    ($self: ident, $rules: expr) => {{
-        let rules = $self.rules.borrow();
-
-        if rules.is_some() || *$self.rules_init.borrow().deref() {
-            return Ok(rules);
-        }
-
-        // Get rid of imutable borrow so we can mutate it
-        drop(rules);
-
-        let res: Option<RewriteRules> = $rules;
-
-        // SAFETY never borrows mut if it is already in the cache
-        let mut rules_cache = $self.rules.borrow_mut();
-        *rules_cache = res;
-        drop(rules_cache);
-
-        Ok($self.rules.borrow())
-    }};
-}
-
 impl WpRewrite {
     pub fn new() -> Self {
         Self {
+            home: String::new(),
             permalink_structure: PermalinkStructure::new(),
             category_base: String::from("category/"),
-            category_structure: String::new(),
+            _category_structure: String::new(),
+            tag_base: String::from("tag/"),
             author_base: String::from("author/"),
-            author_structure: String::new(),
+            _author_structure: String::new(),
             pagination_base: String::from("page/"),
             _feeds: String::new(),
             feed_base: String::from("feed/"),
             _feed_structure: String::new(),
             search_base: String::from("search/"),
-            search_structure: String::new(),
+            _search_structure: String::new(),
             comments_base: String::from("comments"),
-            comments_feed_structure: String::new(),
-            date_structure: String::new(),
-            page_structure: String::new(),
-            front: String::new(),
-            root: String::new(),
+            _comments_feed_structure: String::new(),
+            _date_structure: String::new(),
+            _page_structure: String::new(),
+            _front: String::new(),
+            _root: String::new(),
             rules: RefCell::new(None),
             rules_init: RefCell::new(false),
             hooks: RewriteFilterCache::new(),
@@ -122,46 +113,173 @@ impl WpRewrite {
     /// Retrieves the rewrite rules from database.
     /// Results are cached if database result is valid
     #[cfg(feature = "query_sync")]
-    pub fn wp_rewrite_rules(&self) -> Result<Ref<'_, Option<RewriteRules>>, mysql::Error> {
-        wp_rewrite_rules!(self, {
-            let mut conn = get_conn()?;
+    pub fn wp_rewrite_rules(&self) -> Result<Ref<'_, Option<RewriteRules>>, WpError> {
+        if let Some(rules) = self.cached_rules() {
+            return Ok(rules);
+        }
 
-            let res: Option<RewriteRules> = conn.exec_first(
-                "SELECT option_value FROM wp_options WHERE option_name = 'rewrite_rules'",
-                mysql::Params::Empty,
-            )?;
+        let mut conn = get_conn()?;
 
-            res
-        })
+        self.wp_rewrite_rules_with_connection(&mut conn)
     }
-    #[cfg(feature = "query_async")]
-    pub async fn wp_rewrite_rules(
+
+    /// Retrieves the rewrite rules using a mysql connection, or any other [`Executor`].
+    /// Results are cached if database result is valid
+    #[cfg(feature = "query_sync")]
+    pub fn wp_rewrite_rules_with_connection(
         &self,
-    ) -> Result<Ref<'_, Option<RewriteRules>>, mysql_async::Error> {
-        wp_rewrite_rules!(self, {
-            let mut conn = get_conn().await?;
+        conn: &mut impl Executor,
+    ) -> Result<Ref<'_, Option<RewriteRules>>, WpError> {
+        if let Some(rules) = self.cached_rules() {
+            return Ok(rules);
+        }
+
+        let res: Option<RewriteRules> = conn.fetch_first(&rewrite_rules_query(), vec![])?;
+
+        Ok(self.cache_rules(res))
+    }
+
+    /// The cached rewrite rules, if they were already fetched from the database.
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn cached_rules(&self) -> Option<Ref<'_, Option<RewriteRules>>> {
+        let rules = self.rules.borrow();
+
+        if rules.is_some() || *self.rules_init.borrow() {
+            Some(rules)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn cache_rules(&self, rules: Option<RewriteRules>) -> Ref<'_, Option<RewriteRules>> {
+        // SAFETY never borrows mut if it is already in the cache
+        *self.rules.borrow_mut() = rules;
+
+        self.rules.borrow()
+    }
 
-            let res: Option<RewriteRules> = conn
-                .exec_first(
-                    "SELECT option_value FROM wp_options WHERE option_name = 'rewrite_rules'",
-                    mysql_async::Params::Empty,
-                )
-                .await?;
+    /// Creates the rewrite settings from the `home`, `permalink_structure`, `category_base` and `tag_base` options.
+    ///
+    /// # Errors
+    /// When the permalink structure contains an unknown rewrite code.
+    pub fn from_options(options: &WpOptions) -> Result<Self, PermalinkStructureParseError> {
+        let mut rewrite = Self::new();
 
-            res
-        })
+        rewrite.set_home(options.home().unwrap_or_default());
+        rewrite.set_permalink_structure(options.permalink_structure().unwrap_or_default())?;
+        if let Some(base) = options.get_raw("category_base").filter(|b| !b.is_empty()) {
+            rewrite.set_category_base(format!("{}/", base.trim_matches('/')));
+        }
+        if let Some(base) = options.get_raw("tag_base").filter(|b| !b.is_empty()) {
+            rewrite.set_tag_base(format!("{}/", base.trim_matches('/')));
+        }
+
+        Ok(rewrite)
+    }
+
+    /// Sets the site address links are generated for, e.g. `https://example.com`.
+    pub fn set_home(&mut self, home: &str) {
+        self.home = String::from(home.trim_end_matches('/'));
+    }
+
+    /// Sets the permalink structure, e.g. `/%year%/%monthnum%/%postname%/`. Empty for plain permalinks.
+    pub fn set_permalink_structure(
+        &mut self,
+        structure: &str,
+    ) -> Result<(), PermalinkStructureParseError> {
+        self.permalink_structure = structure.try_into()?;
+
+        Ok(())
     }
 
     /// Sets the category base for the category permalink.
     pub fn set_category_base(&mut self, base: String) {
         self.category_base = base;
     }
+
+    /// Sets the tag base for the tag permalink, e.g. `topics/`.
+    pub fn set_tag_base(&mut self, base: String) {
+        self.tag_base = base;
+    }
 }
 
+impl Default for WpRewrite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[allow(dead_code)]
 trait ToRegex {
     fn to_regex(self) -> Result<regex::Regex, regex::Error>;
 }
 
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn rewrite_rules_query() -> String {
+    format!(
+        "SELECT option_value FROM {}options WHERE option_name = 'rewrite_rules'",
+        get_table_prefix()
+    )
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{cell::Ref, future::Future};
+
+    use crate::{
+        sql::{r#async::get_conn, traits::r#async::Executor},
+        WpError,
+    };
+
+    use super::{rewrite_rule::RewriteRules, rewrite_rules_query, WpRewrite};
+
+    pub use super::parse_request::r#async::parse_request;
+    pub use super::permalink::r#async::{get_author_posts_url, get_permalink, get_term_link};
+
+    /// Async database methods of [`WpRewrite`].
+    pub trait WpRewriteExt {
+        /// Retrieves the rewrite rules from database.
+        /// Results are cached if database result is valid
+        fn wp_rewrite_rules(
+            &self,
+        ) -> impl Future<Output = Result<Ref<'_, Option<RewriteRules>>, WpError>>;
+
+        /// Retrieves the rewrite rules using a mysql connection, or any other [`Executor`].
+        /// Results are cached if database result is valid
+        fn wp_rewrite_rules_with_connection(
+            &self,
+            conn: &mut impl Executor,
+        ) -> impl Future<Output = Result<Ref<'_, Option<RewriteRules>>, WpError>>;
+    }
+
+    impl WpRewriteExt for WpRewrite {
+        async fn wp_rewrite_rules(&self) -> Result<Ref<'_, Option<RewriteRules>>, WpError> {
+            if let Some(rules) = self.cached_rules() {
+                return Ok(rules);
+            }
+
+            let mut conn = get_conn().await?;
+
+            WpRewriteExt::wp_rewrite_rules_with_connection(self, &mut conn).await
+        }
+
+        async fn wp_rewrite_rules_with_connection(
+            &self,
+            conn: &mut impl Executor,
+        ) -> Result<Ref<'_, Option<RewriteRules>>, WpError> {
+            if let Some(rules) = self.cached_rules() {
+                return Ok(rules);
+            }
+
+            let res: Option<RewriteRules> =
+                conn.fetch_first(&rewrite_rules_query(), vec![]).await?;
+
+            Ok(self.cache_rules(res))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
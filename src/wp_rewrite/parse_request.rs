@@ -1,57 +1,60 @@
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, ops::Deref, str::FromStr};
 
-use crate::{ParamBuilder, Params, PostQueryable, PostType};
+use crate::{ParamBuilder, Params, PostQueryable, PostType, WpError};
 
-use super::WpRewrite;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use super::{rewrite_rule::RewriteRules, WpRewrite};
 
-macro_rules! parse_request {
-    ($url: ident, $rewrite: ident, $rules: expr) => {{
-        let pathinfo = path_info(&$url).to_string();
-
-        if let Some(rules) = $rules.deref() {
-            let matched_rule = rules.find_match(&pathinfo, &$rewrite);
-            if let Some(q_params) = matched_rule.and_then(|r| r.replace(&pathinfo)) {
-                let mut parsed = $url.clone();
-                parsed.set_path("index.php");
-                parsed.set_query(Some(&q_params));
-
-                return Ok(parsed);
-            }
-        } else if $url.query().and_then(|q| q.find("p=")).is_some() {
-            // If is a default p=ID url, return it as is
-            return Ok($url);
+/// Rewrites the request url to its `index.php` query with the rewrite rules of the site.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn rewrite_request(
+    wp_rewrite: &WpRewrite,
+    url: url::Url,
+    rules: Option<&RewriteRules>,
+) -> Result<url::Url, WpError> {
+    let pathinfo = path_info(&url).to_string();
+
+    if let Some(rules) = rules {
+        let matched_rule = rules.find_match(&pathinfo, wp_rewrite);
+        if let Some(q_params) = matched_rule.and_then(|r| r.replace(&pathinfo)) {
+            let mut parsed = url.clone();
+            parsed.set_path("index.php");
+            parsed.set_query(Some(&q_params));
+
+            return Ok(parsed);
         }
+    } else if url.query().and_then(|q| q.find("p=")).is_some() {
+        // If is a default p=ID url, return it as is
+        return Ok(url);
+    }
 
-        Err(Box::new(WpParseError {}))
-    }};
+    Err(WpError::Rewrite(format!(
+        "could not parse request {pathinfo}"
+    )))
 }
 
 #[cfg(feature = "query_sync")]
-pub fn parse_request(
-    wp_rewrite: &WpRewrite,
-    url: url::Url,
-) -> Result<url::Url, Box<dyn std::error::Error>> {
-    parse_request!(url, wp_rewrite, { wp_rewrite.wp_rewrite_rules()? })
+pub fn parse_request(wp_rewrite: &WpRewrite, url: url::Url) -> Result<url::Url, WpError> {
+    let rules = wp_rewrite.wp_rewrite_rules()?;
+
+    rewrite_request(wp_rewrite, url, rules.deref().as_ref())
 }
+
 #[cfg(feature = "query_async")]
-pub async fn parse_request(
-    wp_rewrite: &WpRewrite,
-    url: url::Url,
-) -> Result<url::Url, Box<dyn std::error::Error>> {
-    parse_request!(url, wp_rewrite, { wp_rewrite.wp_rewrite_rules().await? })
-}
+pub mod r#async {
+    use std::ops::Deref;
+
+    use crate::WpError;
+
+    use super::{super::r#async::WpRewriteExt, rewrite_request, WpRewrite};
 
-#[derive(Debug)]
-pub struct WpParseError {}
+    pub async fn parse_request(wp_rewrite: &WpRewrite, url: url::Url) -> Result<url::Url, WpError> {
+        let rules = WpRewriteExt::wp_rewrite_rules(wp_rewrite).await?;
 
-impl std::fmt::Display for WpParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Could not parse request")
+        rewrite_request(wp_rewrite, url, rules.deref().as_ref())
     }
 }
 
-impl std::error::Error for WpParseError {}
-
 /// Contains any client-provided pathname information trailing the actual script filename but preceding the query string, if available.
 /// # Examples
 /// ```rust,ignore
@@ -88,7 +91,7 @@ fn _get_home_path_regex(home_path: Option<&str>) -> Option<regex::Regex> {
 }
 
 impl<'a> TryFrom<&'a url::Url> for Params<'a> {
-    type Error = Box<dyn std::error::Error>;
+    type Error = WpError;
 
     fn try_from(url_v: &'a url::Url) -> Result<Self, Self::Error> {
         let mut params = ParamBuilder::new();
@@ -101,13 +104,10 @@ impl<'a> TryFrom<&'a url::Url> for Params<'a> {
             };
 
             match key.deref() {
-                "p" => {
-                    let p: u64 = value.parse()?;
-                    params = params.p(p);
-                }
+                "p" => params = params.p(parse_query_var(&key, str)?),
                 "post_type" => params = params.post_type(PostType::from(str)),
-                "year" => params = params.year(value.parse()?),
-                "monthnum" => params = params.monthnum(value.parse()?),
+                "year" => params = params.try_year(parse_query_var(&key, str)?)?,
+                "monthnum" => params = params.try_monthnum(parse_query_var(&key, str)?)?,
                 "name" | "pagename" => params = params.name(str),
                 "category_name" => params = params.category_name(str),
                 "author_name" => params = params.author_name(str),
@@ -119,8 +119,15 @@ impl<'a> TryFrom<&'a url::Url> for Params<'a> {
     }
 }
 
+fn parse_query_var<T: FromStr>(key: &str, value: &str) -> Result<T, WpError> {
+    value
+        .parse()
+        .map_err(|_| WpError::InvalidParams(format!("invalid value for {key}: {value}")))
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "query_sync")]
     use std::cell::RefCell;
 
     use url::Url;
@@ -141,6 +148,22 @@ mod tests {
         assert_eq!(params.p, Some(123));
     }
 
+    #[test]
+    fn invalid_query_vars_are_errors() {
+        let url = Url::parse("http://localhost:8080/?year=99999").unwrap();
+        assert!(matches!(
+            Params::try_from(&url),
+            Err(WpError::InvalidParams(_))
+        ));
+
+        let url = Url::parse("http://localhost:8080/?monthnum=13").unwrap();
+        assert!(Params::try_from(&url).is_err());
+
+        let url = Url::parse("http://localhost:8080/?year=2023&monthnum=9").unwrap();
+        let params = Params::try_from(&url).unwrap();
+        assert_eq!(params.year, Some(2023));
+    }
+
     #[test]
     fn return_path_if_no_php() {
         let url = Url::parse("http://www.example.com/php/some/stuff?foo=bar").unwrap();
@@ -174,10 +197,11 @@ mod tests {
             .is_some());
         assert!(parsed
             .query_pairs()
-            .find(|(key, v)| key == "page")
+            .find(|(key, _v)| key == "page")
             .is_none());
     }
 
+    #[cfg(feature = "query_sync")]
     fn get_rewrite_dummy() -> WpRewrite {
         let db_res = std::fs::read_to_string("test_data/test_rewrite_rules.txt").unwrap();
         let rewrite_rules = db_res.try_into().unwrap();
@@ -0,0 +1,626 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::*;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use mysql_common::Value;
+#[cfg(feature = "query_sync")]
+use std::sync::Arc;
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::WpError;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use crate::{query_builder::QueryAndValues, sql::table_prefix::get_table_prefix, WpOptions};
+#[cfg(feature = "query_sync")]
+use crate::{sql::get_conn, WpUser};
+use crate::{wp_post::post_status::PostStatus, WpPost, WpTerm};
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use super::permalink_structure::PermalinkStructure;
+use super::{RewriteCode, WpRewrite};
+
+/// Slug used for `%category%` when a post has no category.
+pub const DEFAULT_CATEGORY_SLUG: &str = "uncategorized";
+
+impl WpRewrite {
+    /// Permalink of a post, filling the permalink structure with the post's date, slug and ID.
+    /// `category_path` are the slugs of the post's first category and its parents, e.g. `news/local`,
+    /// and `author_nicename` the nicename of the post's author.
+    ///
+    /// Falls back to `?p=ID` for plain permalinks and for drafts, pending and scheduled posts like `get_permalink`.
+    /// Pages are linked by their slug only, use `page_link` for the full path of child pages.
+    ///
+    /// # Example
+    /// ```
+    /// use wp_query_rs::{wp_rewrite::WpRewrite, PostStatus, WP_Post};
+    ///
+    /// let mut rewrite = WpRewrite::new();
+    /// rewrite.set_home("https://example.com");
+    /// rewrite.set_permalink_structure("/%category%/%postname%/").unwrap();
+    ///
+    /// let mut post = WP_Post::new(1);
+    /// post.post_name = String::from("hello-world");
+    /// post.post_status = PostStatus::Publish;
+    ///
+    /// assert_eq!(
+    ///     rewrite.post_link(&post, "news", "admin"),
+    ///     "https://example.com/news/hello-world/"
+    /// );
+    /// ```
+    pub fn post_link(&self, post: &WpPost, category_path: &str, author_nicename: &str) -> String {
+        let home = &self.home;
+        let plain = self.permalink_structure.is_plain() || is_draft_or_pending(post);
+
+        match post.post_type.as_str() {
+            "page" => self.page_link(post.ID, &post.post_name),
+            "attachment" => format!("{home}/?attachment_id={}", post.ID),
+            "post" if plain => format!("{home}/?p={}", post.ID),
+            "post" => {
+                let path = self.permalink_structure.fill(|code| match code {
+                    RewriteCode::Year => Some(post.post_date.year().to_string()),
+                    RewriteCode::Monthnum => Some(format!("{:02}", post.post_date.month() as u8)),
+                    RewriteCode::Day => Some(format!("{:02}", post.post_date.day())),
+                    RewriteCode::Hour => Some(format!("{:02}", post.post_date.hour())),
+                    RewriteCode::Minute => Some(format!("{:02}", post.post_date.minute())),
+                    RewriteCode::Second => Some(format!("{:02}", post.post_date.second())),
+                    RewriteCode::Postname | RewriteCode::Pagename => Some(post.post_name.clone()),
+                    RewriteCode::PostId => Some(post.ID.to_string()),
+                    RewriteCode::Category if category_path.is_empty() => {
+                        Some(String::from(DEFAULT_CATEGORY_SLUG))
+                    }
+                    RewriteCode::Category => Some(String::from(category_path)),
+                    RewriteCode::Author => Some(String::from(author_nicename)),
+                    _ => None,
+                });
+
+                format!("{home}{path}")
+            }
+            post_type if plain => format!("{home}/?post_type={post_type}&p={}", post.ID),
+            post_type => self.user_trailingslashit(format!(
+                "{home}{}{post_type}/{}",
+                self.permalink_structure.front(),
+                post.post_name
+            )),
+        }
+    }
+
+    /// Permalink of a page like `get_page_link`, where `page_uri` are the slugs of the page and its parents, e.g. `about/team`.
+    pub fn page_link(&self, page_id: u64, page_uri: &str) -> String {
+        if self.permalink_structure.is_plain() {
+            return format!("{}/?page_id={page_id}", self.home);
+        }
+
+        self.user_trailingslashit(format!("{}/{page_uri}", self.home))
+    }
+
+    /// Archive link of a term like `get_term_link`, where `term_path` are the slugs of the term and its parents for hierarchical taxonomies.
+    /// Categories and tags use the category and tag bases, other taxonomies their name.
+    pub fn term_link(&self, term: &WpTerm, term_path: &str) -> String {
+        let home = &self.home;
+
+        if self.permalink_structure.is_plain() {
+            return match term.taxonomy.as_str() {
+                "category" => format!("{home}/?cat={}", term.term_id),
+                "post_tag" => format!("{home}/?tag={}", term.slug),
+                taxonomy => format!("{home}/?taxonomy={taxonomy}&term={}", term.slug),
+            };
+        }
+
+        let base = match term.taxonomy.as_str() {
+            "category" => self.archive_base(&self.category_base, "category/"),
+            "post_tag" => self.archive_base(&self.tag_base, "tag/"),
+            taxonomy => format!("{}{taxonomy}/", self.permalink_structure.front()),
+        };
+
+        self.user_trailingslashit(format!("{home}{base}{term_path}"))
+    }
+
+    /// Archive link of the posts of an author like `get_author_posts_url`.
+    pub fn author_link(&self, user_id: u64, user_nicename: &str) -> String {
+        if self.permalink_structure.is_plain() {
+            return format!("{}/?author={user_id}", self.home);
+        }
+
+        self.user_trailingslashit(format!(
+            "{}{}{}{user_nicename}",
+            self.home,
+            self.permalink_structure.front(),
+            self.author_base
+        ))
+    }
+
+    /// The default bases are prefixed with the front of the permalink structure, custom bases are not.
+    fn archive_base(&self, base: &str, default: &str) -> String {
+        if base == default {
+            format!("{}{base}", self.permalink_structure.front())
+        } else {
+            format!("/{base}")
+        }
+    }
+
+    /// Adds or removes the trailing slash depending on the permalink structure.
+    fn user_trailingslashit(&self, link: String) -> String {
+        let link = link.trim_end_matches('/');
+
+        if self.permalink_structure.has_trailing_slash() {
+            format!("{link}/")
+        } else {
+            String::from(link)
+        }
+    }
+}
+
+/// Posts which are not published yet have no pretty permalink.
+fn is_draft_or_pending(post: &WpPost) -> bool {
+    post.post_name.is_empty()
+        || matches!(
+            post.post_status,
+            PostStatus::Draft | PostStatus::Pending | PostStatus::AutoDraft | PostStatus::Future
+        )
+}
+
+/// Retrieves the permalink of a post like `get_permalink`, using the `home`, `permalink_structure` and
+/// category base options. The category and author are only queried if the permalink structure contains them.
+///
+/// # Example
+/// ```rust,ignore
+/// use wp_query_rs::{wp_rewrite::get_permalink, WP_Query, ParamBuilder};
+///
+/// let query = WP_Query::new(ParamBuilder::new().p(1)).expect("SqlFailed");
+/// let link = get_permalink(&query.posts[0]).expect("SqlFailed");
+/// ```
+#[cfg(feature = "query_sync")]
+pub fn get_permalink(post: &WpPost) -> Result<String, WpError> {
+    let options = load_options()?;
+    let structure = permalink_structure(&options)?;
+    let mut conn = get_conn()?;
+
+    if post.post_type == "page" {
+        if is_front_page(&options, post.ID) {
+            return Ok(format!("{}/", home(&options)));
+        }
+
+        let mut uri: Vec<String> = vec![];
+        if post.post_parent > 0 && !structure.is_plain() {
+            let QueryAndValues(q, values) = page_ancestors_query(post.post_parent);
+            uri = conn.exec(q, values)?;
+        }
+        uri.push(post.post_name.clone());
+
+        return Ok(rewrite(&options)?.page_link(post.ID, &uri.join("/")));
+    }
+
+    let mut category: Vec<String> = vec![];
+    if structure.contains(&RewriteCode::Category) {
+        let QueryAndValues(q, values) = post_category_query(post.ID);
+        category = conn.exec(q, values)?;
+    }
+    let mut author: Option<String> = None;
+    if structure.contains(&RewriteCode::Author) {
+        let QueryAndValues(q, values) = author_nicename_query(post.post_author);
+        author = conn.exec_first(q, values)?;
+    }
+
+    Ok(rewrite(&options)?.post_link(post, &category.join("/"), &author.unwrap_or_default()))
+}
+
+/// Retrieves the archive link of a term like `get_term_link`, including the slugs of its parents.
+#[cfg(feature = "query_sync")]
+pub fn get_term_link(term: &WpTerm) -> Result<String, WpError> {
+    let options = load_options()?;
+    let structure = permalink_structure(&options)?;
+
+    let mut path: Vec<String> = vec![];
+    if term.parent > 0 && !structure.is_plain() {
+        let mut conn = get_conn()?;
+
+        let QueryAndValues(q, values) = term_ancestors_query(term.parent, &term.taxonomy);
+        path = conn.exec(q, values)?;
+    }
+    path.push(term.slug.clone());
+
+    Ok(rewrite(&options)?.term_link(term, &path.join("/")))
+}
+
+/// Retrieves the archive link of the posts of a user like `get_author_posts_url`.
+#[cfg(feature = "query_sync")]
+pub fn get_author_posts_url(user: &WpUser) -> Result<String, WpError> {
+    let options = load_options()?;
+
+    Ok(rewrite(&options)?.author_link(user.id, &user.user_nicename))
+}
+
+/// The shared autoloaded options, loading them on first use.
+#[cfg(feature = "query_sync")]
+fn load_options() -> Result<Arc<WpOptions>, WpError> {
+    match WpOptions::cached() {
+        Some(options) => Ok(options),
+        None => WpOptions::load(),
+    }
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn permalink_structure(options: &WpOptions) -> Result<PermalinkStructure, WpError> {
+    Ok(options
+        .permalink_structure()
+        .unwrap_or_default()
+        .try_into()?)
+}
+
+/// Rewrite settings from the options, created after all queries as they cannot be held across await points.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn rewrite(options: &WpOptions) -> Result<WpRewrite, WpError> {
+    Ok(WpRewrite::from_options(options)?)
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn home(options: &WpOptions) -> &str {
+    options.home().unwrap_or_default().trim_end_matches('/')
+}
+
+/// Whether the page is the static front page, which is linked to the home URL.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn is_front_page(options: &WpOptions, page_id: u64) -> bool {
+    options.get_raw("show_on_front") == Some("page")
+        && options.get_raw("page_on_front") == Some(page_id.to_string().as_str())
+}
+
+/// Selects the slugs of a page and its parents, the top level page first.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn page_ancestors_query(page_id: u64) -> QueryAndValues {
+    let prefix = get_table_prefix();
+
+    QueryAndValues(
+        format!(
+            "WITH RECURSIVE ancestors AS (
+                SELECT ID, post_parent, post_name, 0 AS depth FROM {prefix}posts WHERE ID = ?
+                UNION ALL
+                SELECT p.ID, p.post_parent, p.post_name, a.depth + 1 FROM ancestors AS a
+                JOIN {prefix}posts AS p ON p.ID = a.post_parent WHERE a.depth < 100
+            ) SELECT post_name FROM ancestors ORDER BY depth DESC;"
+        ),
+        vec![Value::UInt(page_id)],
+    )
+}
+
+/// Selects the slugs of the first category of a post, i.e. with the lowest ID, and its parents, the top level category first.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn post_category_query(post_id: u64) -> QueryAndValues {
+    let prefix = get_table_prefix();
+
+    term_path_query(
+        &format!(
+            "tt.term_id = (
+                SELECT tt.term_id FROM {prefix}term_relationships AS tr
+                JOIN {prefix}term_taxonomy AS tt ON tt.term_taxonomy_id = tr.term_taxonomy_id
+                WHERE tr.object_id = ? AND tt.taxonomy = ? ORDER BY tt.term_id ASC LIMIT 1
+            )"
+        ),
+        vec![Value::UInt(post_id), Value::from("category")],
+        "category",
+    )
+}
+
+/// Selects the slugs of a term and its parents, the top level term first.
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn term_ancestors_query(term_id: u64, taxonomy: &str) -> QueryAndValues {
+    term_path_query(
+        "tt.term_id = ? AND tt.taxonomy = ?",
+        vec![Value::UInt(term_id), Value::from(taxonomy)],
+        taxonomy,
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn term_path_query(anchor: &str, mut values: Vec<Value>, taxonomy: &str) -> QueryAndValues {
+    let prefix = get_table_prefix();
+    values.push(Value::from(taxonomy));
+
+    QueryAndValues(
+        format!(
+            "WITH RECURSIVE ancestors AS (
+                SELECT tt.term_id, tt.parent, t.slug, 0 AS depth FROM {prefix}term_taxonomy AS tt
+                JOIN {prefix}terms AS t ON t.term_id = tt.term_id WHERE {anchor}
+                UNION ALL
+                SELECT tt.term_id, tt.parent, t.slug, a.depth + 1 FROM ancestors AS a
+                JOIN {prefix}term_taxonomy AS tt ON tt.term_id = a.parent AND tt.taxonomy = ?
+                JOIN {prefix}terms AS t ON t.term_id = tt.term_id WHERE a.depth < 100
+            ) SELECT slug FROM ancestors ORDER BY depth DESC;"
+        ),
+        values,
+    )
+}
+
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+fn author_nicename_query(user_id: u64) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "SELECT user_nicename FROM {}users WHERE ID = ?;",
+            get_table_prefix()
+        ),
+        vec![Value::UInt(user_id)],
+    )
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::sync::Arc;
+
+    use mysql_async::prelude::*;
+
+    use crate::{
+        query_builder::QueryAndValues, sql::r#async::get_conn,
+        wp_options::r#async::wp_load_alloptions, WpError, WpOptions, WpPost, WpTerm, WpUser,
+    };
+
+    use super::{
+        author_nicename_query, home, is_front_page, page_ancestors_query, permalink_structure,
+        post_category_query, rewrite, term_ancestors_query, RewriteCode,
+    };
+
+    /// Retrieves the permalink of a post like `get_permalink`, using the `home`, `permalink_structure` and
+    /// category base options. The category and author are only queried if the permalink structure contains them.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use wp_query_rs::{r#async::{get_permalink, WpQuery}, ParamBuilder};
+    ///
+    /// let query = WpQuery::new(ParamBuilder::new().p(1)).await.expect("SqlFailed");
+    /// let link = get_permalink(&query.posts[0]).await.expect("SqlFailed");
+    /// ```
+    pub async fn get_permalink(post: &WpPost) -> Result<String, WpError> {
+        let options = load_options().await?;
+        let structure = permalink_structure(&options)?;
+        let mut conn = get_conn().await?;
+
+        if post.post_type == "page" {
+            if is_front_page(&options, post.ID) {
+                return Ok(format!("{}/", home(&options)));
+            }
+
+            let mut uri: Vec<String> = vec![];
+            if post.post_parent > 0 && !structure.is_plain() {
+                let QueryAndValues(q, values) = page_ancestors_query(post.post_parent);
+                uri = conn.exec(q, values).await?;
+            }
+            uri.push(post.post_name.clone());
+
+            return Ok(rewrite(&options)?.page_link(post.ID, &uri.join("/")));
+        }
+
+        let mut category: Vec<String> = vec![];
+        if structure.contains(&RewriteCode::Category) {
+            let QueryAndValues(q, values) = post_category_query(post.ID);
+            category = conn.exec(q, values).await?;
+        }
+        let mut author: Option<String> = None;
+        if structure.contains(&RewriteCode::Author) {
+            let QueryAndValues(q, values) = author_nicename_query(post.post_author);
+            author = conn.exec_first(q, values).await?;
+        }
+
+        Ok(rewrite(&options)?.post_link(post, &category.join("/"), &author.unwrap_or_default()))
+    }
+
+    /// Retrieves the archive link of a term like `get_term_link`, including the slugs of its parents.
+    pub async fn get_term_link(term: &WpTerm) -> Result<String, WpError> {
+        let options = load_options().await?;
+        let structure = permalink_structure(&options)?;
+
+        let mut path: Vec<String> = vec![];
+        if term.parent > 0 && !structure.is_plain() {
+            let mut conn = get_conn().await?;
+
+            let QueryAndValues(q, values) = term_ancestors_query(term.parent, &term.taxonomy);
+            path = conn.exec(q, values).await?;
+        }
+        path.push(term.slug.clone());
+
+        Ok(rewrite(&options)?.term_link(term, &path.join("/")))
+    }
+
+    /// Retrieves the archive link of the posts of a user like `get_author_posts_url`.
+    pub async fn get_author_posts_url(user: &WpUser) -> Result<String, WpError> {
+        let options = load_options().await?;
+
+        Ok(rewrite(&options)?.author_link(user.id, &user.user_nicename))
+    }
+
+    /// The shared autoloaded options, loading them on first use.
+    async fn load_options() -> Result<Arc<WpOptions>, WpError> {
+        match WpOptions::cached() {
+            Some(options) => Ok(options),
+            None => wp_load_alloptions().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_common::time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::*;
+    use crate::wp_options::WpOptions;
+
+    fn rewrite(structure: &str) -> WpRewrite {
+        let mut rewrite = WpRewrite::new();
+        rewrite.set_home("https://example.com/");
+        rewrite.set_permalink_structure(structure).unwrap();
+
+        rewrite
+    }
+
+    fn post() -> WpPost {
+        let mut post = WpPost::new(1);
+        post.ID = 42;
+        post.post_name = String::from("hello-world");
+        post.post_status = PostStatus::Publish;
+        post.post_date = PrimitiveDateTime::new(
+            Date::from_calendar_date(2024, Month::March, 5).unwrap(),
+            Time::from_hms(9, 7, 0).unwrap(),
+        );
+
+        post
+    }
+
+    fn term(taxonomy: &str, slug: &str) -> WpTerm {
+        WpTerm {
+            term_id: 3,
+            name: String::from(slug),
+            slug: String::from(slug),
+            term_group: 0,
+            term_taxonomy_id: 3,
+            taxonomy: String::from(taxonomy),
+            description: String::new(),
+            parent: 0,
+            count: 0,
+        }
+    }
+
+    #[test]
+    fn falls_back_to_plain_links() {
+        let rewrite = rewrite("");
+        let mut page = post();
+        page.post_type = String::from("page");
+
+        assert_eq!(
+            rewrite.post_link(&post(), "", ""),
+            "https://example.com/?p=42"
+        );
+        assert_eq!(
+            rewrite.post_link(&page, "", ""),
+            "https://example.com/?page_id=42"
+        );
+        assert_eq!(
+            rewrite.term_link(&term("category", "news"), "news"),
+            "https://example.com/?cat=3"
+        );
+        assert_eq!(
+            rewrite.author_link(1, "admin"),
+            "https://example.com/?author=1"
+        );
+    }
+
+    #[test]
+    fn fills_permalink_structure() {
+        let rewrite = rewrite("/%year%/%monthnum%/%day%/%hour%%minute%/%post_id%-%postname%/");
+
+        assert_eq!(
+            rewrite.post_link(&post(), "", ""),
+            "https://example.com/2024/03/05/0907/42-hello-world/"
+        );
+    }
+
+    #[test]
+    fn fills_category_and_author() {
+        let rewrite = rewrite("/%category%/%author%/%postname%.html");
+
+        assert_eq!(
+            rewrite.post_link(&post(), "news/local", "john"),
+            "https://example.com/news/local/john/hello-world.html"
+        );
+        assert_eq!(
+            rewrite.post_link(&post(), "", "john"),
+            "https://example.com/uncategorized/john/hello-world.html"
+        );
+    }
+
+    #[test]
+    fn drafts_use_plain_links() {
+        let rewrite = rewrite("/%postname%/");
+        let mut draft = post();
+        draft.post_status = PostStatus::Draft;
+
+        assert_eq!(
+            rewrite.post_link(&draft, "", ""),
+            "https://example.com/?p=42"
+        );
+    }
+
+    #[test]
+    fn links_pages_and_custom_post_types() {
+        let rewrite = rewrite("/blog/%postname%/");
+        let mut product = post();
+        product.post_type = String::from("product");
+
+        assert_eq!(
+            rewrite.page_link(42, "about/team"),
+            "https://example.com/about/team/"
+        );
+        assert_eq!(
+            rewrite.post_link(&product, "", ""),
+            "https://example.com/blog/product/hello-world/"
+        );
+    }
+
+    #[test]
+    fn term_links_respect_bases() {
+        let mut rewrite = rewrite("/blog/%postname%");
+
+        assert_eq!(
+            rewrite.term_link(&term("category", "local"), "news/local"),
+            "https://example.com/blog/category/news/local"
+        );
+        assert_eq!(
+            rewrite.term_link(&term("post_tag", "rust"), "rust"),
+            "https://example.com/blog/tag/rust"
+        );
+        assert_eq!(
+            rewrite.author_link(1, "admin"),
+            "https://example.com/blog/author/admin"
+        );
+
+        rewrite.set_category_base(String::from("topics/"));
+        rewrite.set_tag_base(String::from("keywords/"));
+        assert_eq!(
+            rewrite.term_link(&term("category", "news"), "news"),
+            "https://example.com/topics/news"
+        );
+        assert_eq!(
+            rewrite.term_link(&term("post_tag", "rust"), "rust"),
+            "https://example.com/keywords/rust"
+        );
+    }
+
+    #[test]
+    fn can_create_from_options() {
+        let options = WpOptions::from(std::collections::HashMap::from([
+            (String::from("home"), String::from("https://example.com")),
+            (
+                String::from("permalink_structure"),
+                String::from("/%postname%/"),
+            ),
+            (String::from("category_base"), String::from("/topics")),
+        ]));
+
+        let rewrite = WpRewrite::from_options(&options).unwrap();
+
+        assert_eq!(
+            rewrite.term_link(&term("category", "news"), "news"),
+            "https://example.com/topics/news/"
+        );
+        assert_eq!(
+            rewrite.term_link(&term("genre", "jazz"), "jazz"),
+            "https://example.com/genre/jazz/"
+        );
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn queries_first_category_path() {
+        let QueryAndValues(q, values) = post_category_query(42);
+
+        assert!(q.starts_with("WITH RECURSIVE ancestors AS ("));
+        assert!(q.contains("ORDER BY tt.term_id ASC LIMIT 1"));
+        assert!(q.ends_with("SELECT slug FROM ancestors ORDER BY depth DESC;"));
+        assert_eq!(q.matches('?').count(), values.len());
+        assert_eq!(values[0], Value::UInt(42));
+    }
+
+    #[test]
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    fn queries_page_ancestors() {
+        let QueryAndValues(q, values) = page_ancestors_query(7);
+
+        assert!(q.contains("FROM wp_posts WHERE ID = ?"));
+        assert_eq!(values, vec![Value::UInt(7)]);
+    }
+}
@@ -4,6 +4,8 @@ use super::RewriteCode;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct PermalinkStructure {
+    /// The structure as in the database, e.g. `/archives/%post_id%/`.
+    structure: String,
     /// When none, defaults to /?p=123
     rewrite_codes: Option<Vec<RewriteCode>>,
 }
@@ -11,21 +13,82 @@ pub struct PermalinkStructure {
 impl PermalinkStructure {
     pub fn new() -> Self {
         Self {
+            structure: String::new(),
             rewrite_codes: None,
         }
     }
+
+    /// Whether plain permalinks like `/?p=123` are used.
+    pub fn is_plain(&self) -> bool {
+        self.rewrite_codes.is_none()
+    }
+
+    pub fn rewrite_codes(&self) -> &[RewriteCode] {
+        self.rewrite_codes.as_deref().unwrap_or_default()
+    }
+
+    pub fn contains(&self, code: &RewriteCode) -> bool {
+        self.rewrite_codes().contains(code)
+    }
+
+    /// Anything up to the first rewrite code, e.g. `/blog/` for `/blog/%postname%/`.
+    pub fn front(&self) -> &str {
+        match self.structure.find('%') {
+            Some(i) => &self.structure[..i],
+            None => "/",
+        }
+    }
+
+    /// Whether links end with a slash, like `user_trailingslashit` in WordPress.
+    pub fn has_trailing_slash(&self) -> bool {
+        self.structure.ends_with('/')
+    }
+
+    /// Replaces the rewrite codes of the structure with the values given, keeping codes without a value.
+    pub fn fill<F>(&self, value: F) -> String
+    where
+        F: Fn(&RewriteCode) -> Option<String>,
+    {
+        let mut filled = String::new();
+
+        for (i, part) in self.structure.split('%').enumerate() {
+            // Rewrite codes are the odd parts between two %
+            if i % 2 == 0 {
+                filled.push_str(part);
+                continue;
+            }
+
+            let tag = format!("%{part}%");
+            match <&str as TryInto<RewriteCode>>::try_into(tag.as_str())
+                .ok()
+                .and_then(|code| value(&code))
+            {
+                Some(v) => filled.push_str(&v),
+                None => filled.push_str(&tag),
+            }
+        }
+
+        filled
+    }
 }
 
 impl TryInto<PermalinkStructure> for &str {
     type Error = PermalinkStructureParseError;
     fn try_into(self) -> Result<PermalinkStructure, Self::Error> {
-        let parts = self.split('/').filter(|p| p.len() > 0);
+        if self.trim().is_empty() {
+            return Ok(PermalinkStructure::new());
+        }
+
+        // Codes are between two %, static parts like `archives/` may surround them
+        let tags = self.split('%').skip(1).step_by(2);
 
-        let rewrite_codes: Result<Vec<RewriteCode>, super::rewrite_code::RewriteCodeError> =
-            parts.map(|p| p.try_into()).collect();
+        let rewrite_codes: Result<Vec<RewriteCode>, super::rewrite_code::RewriteCodeError> = tags
+            .map(|tag| format!("%{tag}%").as_str().try_into())
+            .collect();
 
         rewrite_codes
             .map(|codes| PermalinkStructure {
+                structure: String::from(self),
                 rewrite_codes: Some(codes),
             })
             .map_err(|e| PermalinkStructureParseError(e.to_string()))
@@ -70,6 +133,7 @@ mod tests {
         assert_eq!(
             sructure.unwrap(),
             PermalinkStructure {
+                structure: s.clone(),
                 rewrite_codes: Some(vec![
                     RewriteCode::Year,
                     RewriteCode::Monthnum,
@@ -78,4 +142,33 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn can_parse_static_parts() {
+        let structure: PermalinkStructure = "/archives/%post_id%.html".try_into().unwrap();
+
+        assert_eq!(structure.rewrite_codes(), &[RewriteCode::PostId]);
+        assert_eq!(structure.front(), "/archives/");
+        assert!(!structure.has_trailing_slash());
+    }
+
+    #[test]
+    fn empty_structure_is_plain() {
+        let structure: PermalinkStructure = "".try_into().unwrap();
+
+        assert!(structure.is_plain());
+        assert!(<&str as TryInto<PermalinkStructure>>::try_into("/%unknown%/").is_err());
+    }
+
+    #[test]
+    fn can_fill_codes() {
+        let structure: PermalinkStructure = "/%year%/%postname%/".try_into().unwrap();
+
+        let filled = structure.fill(|code| match code {
+            RewriteCode::Year => Some(String::from("2024")),
+            _ => None,
+        });
+
+        assert_eq!(filled, "/2024/%postname%/");
+    }
 }
@@ -59,6 +59,7 @@ impl TryInto<RewriteCode> for &str {
             "%postname%" => Ok(RewriteCode::Postname),
             "%category%" => Ok(RewriteCode::Category),
             "%author%" => Ok(RewriteCode::Author),
+            "%pagename%" => Ok(RewriteCode::Pagename),
             "%tag%" => Ok(RewriteCode::Tag),
             _ => Err(RewriteCodeError(self.to_string())),
         }
@@ -139,5 +140,9 @@ mod tests {
             <&str as TryInto<RewriteCode>>::try_into("%tag%").unwrap(),
             RewriteCode::Tag
         );
+        assert_eq!(
+            <&str as TryInto<RewriteCode>>::try_into("%pagename%").unwrap(),
+            RewriteCode::Pagename
+        );
     }
 }
@@ -88,9 +88,7 @@ impl RewriteRules {
                 let next_is_more_specific = r.param_count > acc.param_count;
 
                 // use more specific base match if available
-                if next_is_base_match && !acc_is_base_match
-                    || next_is_base_match && next_is_more_specific
-                {
+                if next_is_base_match && (!acc_is_base_match || next_is_more_specific) {
                     return r;
                 }
 
@@ -0,0 +1,207 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::Queryable;
+use serde::ser::SerializeStruct;
+
+#[cfg(feature = "query_sync")]
+use crate::{query_builder::QueryAndValues, sql::get_conn, WpError};
+
+#[cfg(feature = "query_sync")]
+use self::{
+    arg_builder::WpTermQueryArgs,
+    query_builder::{post_terms_query, TermQueryBuilder},
+};
+
+pub mod arg_builder;
+pub mod orderby;
+mod query_builder;
+mod sql;
+
+/// A term joined with its taxonomy, i.e. a category or tag, as returned by `get_terms`.
+#[derive(Debug, Clone)]
+pub struct WpTerm {
+    pub term_id: u64,
+    pub name: String,
+    pub slug: String,
+    pub term_group: i64,
+    pub term_taxonomy_id: u64,
+    pub taxonomy: String,
+    pub description: String,
+    pub parent: u64,
+    /// Number of objects, i.e. posts, assigned the term.
+    pub count: u64,
+}
+
+impl WpTerm {
+    /// Retrieves terms matching the args using the global connection pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use wp_query_rs::{WpTerm, WpTermArgBuilder};
+    ///
+    /// let categories = WpTerm::get_terms(WpTermArgBuilder::new().taxonomy("category"));
+    /// ```
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn get_terms<T>(args: T) -> Result<Vec<Self>, WpError>
+    where
+        T: Into<WpTermQueryArgs>,
+    {
+        let mut conn = get_conn()?;
+
+        Self::get_terms_with_connection(&mut conn, args)
+    }
+
+    /// Retrieves terms matching the args with a mysql connection.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn get_terms_with_connection<T>(
+        conn: &mut impl Queryable,
+        args: T,
+    ) -> Result<Vec<Self>, WpError>
+    where
+        T: Into<WpTermQueryArgs>,
+    {
+        let QueryAndValues(q, values) = TermQueryBuilder::new(args.into()).query()?;
+
+        Ok(conn.exec(q, values)?)
+    }
+
+    /// Retrieves the terms of a taxonomy assigned to a post, ordered by name.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn get_post_terms(post_id: u64, taxonomy: &str) -> Result<Vec<Self>, WpError> {
+        let mut conn = get_conn()?;
+
+        Self::get_post_terms_with_connection(&mut conn, post_id, taxonomy)
+    }
+
+    /// Retrieves the terms of a taxonomy assigned to a post with a mysql connection.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn get_post_terms_with_connection(
+        conn: &mut impl Queryable,
+        post_id: u64,
+        taxonomy: &str,
+    ) -> Result<Vec<Self>, WpError> {
+        let QueryAndValues(q, values) = post_terms_query(post_id, taxonomy);
+
+        Ok(conn.exec(q, values)?)
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use mysql_async::prelude::*;
+
+    use crate::{query_builder::QueryAndValues, sql::r#async::get_conn, WpError};
+
+    use super::{
+        arg_builder::WpTermQueryArgs,
+        query_builder::{post_terms_query, TermQueryBuilder},
+        WpTerm,
+    };
+
+    /// Retrieves the terms in a given taxonomy or list of taxonomies.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn get_terms<T>(args: T) -> Result<Vec<WpTerm>, WpError>
+    where
+        T: Into<WpTermQueryArgs>,
+    {
+        let mut conn = get_conn().await?;
+
+        get_terms_with_connection(&mut conn, args).await
+    }
+
+    /// Retrieves terms matching the args with a mysql connection.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn get_terms_with_connection<T>(
+        conn: &mut mysql_async::Conn,
+        args: T,
+    ) -> Result<Vec<WpTerm>, WpError>
+    where
+        T: Into<WpTermQueryArgs>,
+    {
+        let QueryAndValues(q, values) = TermQueryBuilder::new(args.into()).query()?;
+
+        Ok(conn.exec(q, values).await?)
+    }
+
+    /// Retrieves the terms for a post, i.e. its categories with a taxonomy of `category`.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn wp_get_post_terms(post_id: u64, taxonomy: &str) -> Result<Vec<WpTerm>, WpError> {
+        let mut conn = get_conn().await?;
+
+        get_post_terms_with_connection(&mut conn, post_id, taxonomy).await
+    }
+
+    /// Retrieves the terms of a taxonomy assigned to a post with a mysql connection.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    pub async fn get_post_terms_with_connection(
+        conn: &mut mysql_async::Conn,
+        post_id: u64,
+        taxonomy: &str,
+    ) -> Result<Vec<WpTerm>, WpError> {
+        let QueryAndValues(q, values) = post_terms_query(post_id, taxonomy);
+
+        Ok(conn.exec(q, values).await?)
+    }
+}
+
+impl serde::Serialize for WpTerm {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("WpTerm", 9)?;
+
+        state.serialize_field("term_id", &self.term_id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("slug", &self.slug)?;
+        state.serialize_field("term_group", &self.term_group)?;
+        state.serialize_field("term_taxonomy_id", &self.term_taxonomy_id)?;
+        state.serialize_field("taxonomy", &self.taxonomy)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("parent", &self.parent)?;
+        state.serialize_field("count", &self.count)?;
+
+        state.end()
+    }
+}
+
+/// Retrieves the terms in a given taxonomy or list of taxonomies.
+///
+/// # Errors
+/// When an error occurs in the SQL query.
+#[cfg(feature = "query_sync")]
+pub fn get_terms<T>(args: T) -> Result<Vec<WpTerm>, WpError>
+where
+    T: Into<WpTermQueryArgs>,
+{
+    WpTerm::get_terms(args)
+}
+
+/// Retrieves the terms for a post, i.e. its categories with a taxonomy of `category`.
+///
+/// # Errors
+/// When an error occurs in the SQL query.
+#[cfg(feature = "query_sync")]
+pub fn wp_get_post_terms(post_id: u64, taxonomy: &str) -> Result<Vec<WpTerm>, WpError> {
+    WpTerm::get_post_terms(post_id, taxonomy)
+}
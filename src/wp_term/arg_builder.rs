@@ -0,0 +1,192 @@
+use crate::{sql::table_prefix::validate_table_prefix, SqlOrder, WpError};
+
+use super::orderby::TermOrderby;
+
+/// Arguments for `get_terms`, mirroring those of the WordPress function.
+#[derive(Debug)]
+pub struct WpTermQueryArgs {
+    /// Taxonomy names to retrieve terms for, i.e. category or post_tag. All taxonomies if empty.
+    pub taxonomy: Vec<String>,
+    /// Whether to leave out terms not assigned to any posts. Defaults to true.
+    pub hide_empty: bool,
+    /// Only retrieves direct children of this term ID.
+    pub parent: Option<u64>,
+    pub include: Vec<u64>,
+    pub exclude: Vec<u64>,
+    pub orderby: TermOrderby,
+    pub order: SqlOrder,
+    /// Maximum number of terms to retrieve. All terms if None.
+    pub number: Option<u64>,
+    pub offset: Option<u64>,
+    /// Overrides the global table prefix for this query only.
+    pub table_prefix: Option<String>,
+}
+
+impl WpTermQueryArgs {
+    pub fn new() -> Self {
+        Self {
+            taxonomy: vec![],
+            hide_empty: true,
+            parent: None,
+            include: vec![],
+            exclude: vec![],
+            orderby: TermOrderby::Name,
+            order: SqlOrder::Asc,
+            number: None,
+            offset: None,
+            table_prefix: None,
+        }
+    }
+}
+
+impl Default for WpTermQueryArgs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds arguments for `get_terms` by chaining option callbacks
+///
+/// # Examples
+///
+/// ```
+/// use wp_query_rs::WpTermArgBuilder;
+///
+/// let args = WpTermArgBuilder::new()
+///     .taxonomy("category")
+///     .hide_empty(false)
+///     .number(10);
+/// ```
+pub struct WpTermArgBuilder(WpTermQueryArgs);
+
+impl WpTermArgBuilder {
+    pub fn new() -> Self {
+        Self(WpTermQueryArgs::new())
+    }
+
+    /// Adds a taxonomy to retrieve terms for.
+    pub fn taxonomy(mut self, taxonomy: &str) -> Self {
+        self.0.taxonomy.push(taxonomy.to_string());
+
+        self
+    }
+
+    pub fn hide_empty(mut self, hide_empty: bool) -> Self {
+        self.0.hide_empty = hide_empty;
+
+        self
+    }
+
+    /// Retrieves direct children of the term ID. Use 0 for top level terms.
+    pub fn parent(mut self, term_id: u64) -> Self {
+        self.0.parent = Some(term_id);
+
+        self
+    }
+
+    /// Adds a term ID to include.
+    pub fn include(mut self, term_id: u64) -> Self {
+        self.0.include.push(term_id);
+
+        self
+    }
+
+    /// Adds a term ID to exclude.
+    pub fn exclude(mut self, term_id: u64) -> Self {
+        self.0.exclude.push(term_id);
+
+        self
+    }
+
+    pub fn orderby(mut self, orderby: TermOrderby) -> Self {
+        self.0.orderby = orderby;
+
+        self
+    }
+
+    pub fn order(mut self, order: SqlOrder) -> Self {
+        self.0.order = order;
+
+        self
+    }
+
+    pub fn number(mut self, number: u64) -> Self {
+        self.0.number = Some(number);
+
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.0.offset = Some(offset);
+
+        self
+    }
+
+    /// Overrides the global table prefix for this query only.
+    pub fn table_prefix(self, prefix: &str) -> Self {
+        self.try_table_prefix(prefix).expect("InvalidTablePrefix")
+    }
+
+    /// Like [`WpTermArgBuilder::table_prefix`], returning an error instead of panicking if the prefix contains
+    /// characters other than letters, numbers and underscores.
+    pub fn try_table_prefix(mut self, prefix: &str) -> Result<Self, WpError> {
+        validate_table_prefix(prefix)?;
+
+        self.0.table_prefix = Some(prefix.to_string());
+
+        Ok(self)
+    }
+}
+
+impl Default for WpTermArgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<WpTermArgBuilder> for WpTermQueryArgs {
+    fn from(value: WpTermArgBuilder) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hides_empty_by_default() {
+        let args: WpTermQueryArgs = WpTermArgBuilder::new().into();
+
+        assert!(args.hide_empty);
+        assert_eq!(args.orderby, TermOrderby::Name);
+    }
+
+    #[test]
+    fn can_add_taxonomies() {
+        let args: WpTermQueryArgs = WpTermArgBuilder::new()
+            .taxonomy("category")
+            .taxonomy("post_tag")
+            .into();
+
+        assert_eq!(args.taxonomy, vec!["category", "post_tag"]);
+    }
+
+    #[test]
+    fn can_include_and_exclude() {
+        let args: WpTermQueryArgs = WpTermArgBuilder::new()
+            .include(1)
+            .include(2)
+            .exclude(3)
+            .into();
+
+        assert_eq!(args.include, vec![1, 2]);
+        assert_eq!(args.exclude, vec![3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidTablePrefix")]
+    fn invalid_table_prefix_panics() {
+        WpTermArgBuilder::new().table_prefix("wp-");
+    }
+}
@@ -0,0 +1,48 @@
+/// Columns terms can be ordered by in `get_terms`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermOrderby {
+    Name,
+    Slug,
+    TermGroup,
+    TermId,
+    Description,
+    Parent,
+    Count,
+    /// Keeps the order of the term IDs given in `include`.
+    Include,
+    None,
+}
+
+impl std::fmt::Display for TermOrderby {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Name => "name",
+                Self::Slug => "slug",
+                Self::TermGroup => "term_group",
+                Self::TermId => "term_id",
+                Self::Description => "description",
+                Self::Parent => "parent",
+                Self::Count => "count",
+                Self::Include => "include",
+                Self::None => "",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_to_string() {
+        assert_eq!(
+            TermOrderby::TermGroup.to_string(),
+            String::from("term_group")
+        );
+        assert_eq!(TermOrderby::None.to_string(), String::new());
+    }
+}
@@ -0,0 +1,239 @@
+use mysql_common::Value;
+
+use crate::{
+    query_builder::{implode_to_question_mark, QueryAndValues, StmtValues},
+    sql::table_prefix::{get_table_prefix, table_prefix_or_global},
+    WpError,
+};
+
+use super::{arg_builder::WpTermQueryArgs, orderby::TermOrderby};
+
+pub struct TermQueryBuilder {
+    args: WpTermQueryArgs,
+    query: String,
+    values: StmtValues,
+}
+
+impl TermQueryBuilder {
+    pub fn new(args: WpTermQueryArgs) -> Self {
+        Self {
+            args,
+            query: String::new(),
+            values: vec![],
+        }
+    }
+
+    /// Builds the terms query, selecting terms joined with their taxonomy.
+    ///
+    /// # Errors
+    /// When the table prefix of the args is invalid.
+    pub fn query(mut self) -> Result<QueryAndValues, WpError> {
+        let args = self.args;
+        let prefix = table_prefix_or_global(args.table_prefix.as_deref())?;
+
+        self.query.push_str(&select_terms(prefix));
+
+        // Avoid dangling WHERE issue
+        self.query.push_str(" WHERE 1 = 1");
+
+        if !args.taxonomy.is_empty() {
+            self.query.push_str(&format!(
+                " AND {prefix}term_taxonomy.taxonomy IN ({})",
+                implode_to_question_mark(&args.taxonomy)
+            ));
+            self.values.extend(
+                args.taxonomy
+                    .into_iter()
+                    .map(|t| Value::Bytes(t.into_bytes())),
+            );
+        }
+
+        if args.hide_empty {
+            self.query
+                .push_str(&format!(" AND {prefix}term_taxonomy.count > 0"));
+        }
+
+        if let Some(parent) = args.parent {
+            self.query
+                .push_str(&format!(" AND {prefix}term_taxonomy.parent = ?"));
+            self.values.push(Value::UInt(parent));
+        }
+
+        if !args.include.is_empty() {
+            self.query.push_str(&format!(
+                " AND {prefix}terms.term_id IN ({})",
+                implode_to_question_mark(&args.include)
+            ));
+            self.values
+                .extend(args.include.iter().map(|id| Value::UInt(*id)));
+        }
+
+        if !args.exclude.is_empty() {
+            self.query.push_str(&format!(
+                " AND {prefix}terms.term_id NOT IN ({})",
+                implode_to_question_mark(&args.exclude)
+            ));
+            self.values
+                .extend(args.exclude.into_iter().map(Value::UInt));
+        }
+
+        /* Add order conditions */
+        let order = args.order.to_string();
+        match args.orderby {
+            TermOrderby::None => {}
+            TermOrderby::Include if !args.include.is_empty() => {
+                self.query.push_str(&format!(
+                    " ORDER BY FIELD({prefix}terms.term_id, {})",
+                    implode_to_question_mark(&args.include)
+                ));
+                self.values
+                    .extend(args.include.into_iter().map(Value::UInt));
+            }
+            // Without IDs to order by, fall back to the WordPress default
+            TermOrderby::Include => {
+                self.query
+                    .push_str(&format!(" ORDER BY {prefix}terms.name {order}"));
+            }
+            TermOrderby::Description | TermOrderby::Parent | TermOrderby::Count => {
+                self.query.push_str(&format!(
+                    " ORDER BY {prefix}term_taxonomy.{} {order}",
+                    args.orderby
+                ));
+            }
+            orderby => {
+                self.query
+                    .push_str(&format!(" ORDER BY {prefix}terms.{orderby} {order}"));
+            }
+        }
+
+        /* Add pagination, no limit if number is not set */
+        if let Some(number) = args.number {
+            self.query.push_str(" LIMIT ? OFFSET ?");
+            self.values.push(Value::UInt(number));
+            self.values.push(Value::UInt(args.offset.unwrap_or(0)));
+        }
+
+        self.query.push(';');
+
+        Ok(QueryAndValues(self.query, self.values))
+    }
+}
+
+/// Builds the query for the terms of a taxonomy attached to a post, ordered by name.
+pub fn post_terms_query(post_id: u64, taxonomy: &str) -> QueryAndValues {
+    let prefix = get_table_prefix();
+
+    let q = format!(
+        "{} INNER JOIN {prefix}term_relationships ON {prefix}term_relationships.term_taxonomy_id = {prefix}term_taxonomy.term_taxonomy_id WHERE {prefix}term_relationships.object_id = ? AND {prefix}term_taxonomy.taxonomy = ? ORDER BY {prefix}terms.name ASC;",
+        select_terms(prefix)
+    );
+
+    QueryAndValues(
+        q,
+        vec![
+            Value::UInt(post_id),
+            Value::Bytes(taxonomy.as_bytes().to_vec()),
+        ],
+    )
+}
+
+fn select_terms(prefix: &str) -> String {
+    format!(
+        "SELECT {prefix}terms.term_id, {prefix}terms.name, {prefix}terms.slug, {prefix}terms.term_group, {prefix}term_taxonomy.term_taxonomy_id, {prefix}term_taxonomy.taxonomy, {prefix}term_taxonomy.description, {prefix}term_taxonomy.parent, {prefix}term_taxonomy.count FROM {prefix}terms INNER JOIN {prefix}term_taxonomy ON {prefix}term_taxonomy.term_id = {prefix}terms.term_id"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wp_term::arg_builder::WpTermArgBuilder, SqlOrder};
+
+    fn build(args: WpTermArgBuilder) -> QueryAndValues {
+        TermQueryBuilder::new(args.into()).query().unwrap()
+    }
+
+    #[test]
+    fn default_hides_empty_ordered_by_name() {
+        let QueryAndValues(q, values) = build(WpTermArgBuilder::new());
+
+        assert!(q.contains("FROM wp_terms INNER JOIN wp_term_taxonomy ON wp_term_taxonomy.term_id = wp_terms.term_id WHERE 1 = 1"));
+        assert!(q.contains("AND wp_term_taxonomy.count > 0"));
+        assert!(q.ends_with(" ORDER BY wp_terms.name ASC;"));
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn can_filter_by_taxonomy_and_parent() {
+        let QueryAndValues(q, values) = build(
+            WpTermArgBuilder::new()
+                .taxonomy("category")
+                .parent(0)
+                .hide_empty(false),
+        );
+
+        assert!(q.contains("AND wp_term_taxonomy.taxonomy IN (?)"));
+        assert!(q.contains("AND wp_term_taxonomy.parent = ?"));
+        assert!(!q.contains("count > 0"));
+        assert_eq!(
+            values,
+            vec![Value::Bytes(b"category".to_vec()), Value::UInt(0)]
+        );
+    }
+
+    #[test]
+    fn can_order_by_include() {
+        let QueryAndValues(q, values) = build(
+            WpTermArgBuilder::new()
+                .include(3)
+                .include(1)
+                .exclude(2)
+                .orderby(TermOrderby::Include),
+        );
+
+        assert!(q.contains("AND wp_terms.term_id IN (?,?) AND wp_terms.term_id NOT IN (?)"));
+        assert!(q.contains("ORDER BY FIELD(wp_terms.term_id, ?,?)"));
+        assert_eq!(
+            values,
+            vec![
+                Value::UInt(3),
+                Value::UInt(1),
+                Value::UInt(2),
+                Value::UInt(3),
+                Value::UInt(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_order_by_taxonomy_column() {
+        let QueryAndValues(q, _) = build(
+            WpTermArgBuilder::new()
+                .orderby(TermOrderby::Count)
+                .order(SqlOrder::Desc),
+        );
+
+        assert!(q.contains("ORDER BY wp_term_taxonomy.count DESC"));
+    }
+
+    #[test]
+    fn can_limit() {
+        let QueryAndValues(q, values) = build(WpTermArgBuilder::new().number(5).offset(10));
+
+        assert!(q.ends_with(" LIMIT ? OFFSET ?;"));
+        assert_eq!(values, vec![Value::UInt(5), Value::UInt(10)]);
+    }
+
+    #[test]
+    fn can_query_post_terms() {
+        let QueryAndValues(q, values) = post_terms_query(1, "post_tag");
+
+        assert!(q.contains("INNER JOIN wp_term_relationships ON wp_term_relationships.term_taxonomy_id = wp_term_taxonomy.term_taxonomy_id"));
+        assert!(q.contains(
+            "WHERE wp_term_relationships.object_id = ? AND wp_term_taxonomy.taxonomy = ?"
+        ));
+        assert_eq!(
+            values,
+            vec![Value::UInt(1), Value::Bytes(b"post_tag".to_vec())]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{ok_or_row_error, sql::find_col};
+
+use super::WpTerm;
+
+impl mysql_common::prelude::FromRow for WpTerm {
+    fn from_row_opt(mut row: mysql_common::Row) -> Result<Self, mysql_common::FromRowError>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            term_id: ok_or_row_error!(row, "term_id"),
+            name: ok_or_row_error!(row, "name"),
+            slug: ok_or_row_error!(row, "slug"),
+            term_group: ok_or_row_error!(row, "term_group"),
+            term_taxonomy_id: ok_or_row_error!(row, "term_taxonomy_id"),
+            taxonomy: ok_or_row_error!(row, "taxonomy"),
+            description: ok_or_row_error!(row, "description"),
+            parent: ok_or_row_error!(row, "parent"),
+            count: ok_or_row_error!(row, "count"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_common::{
+        constants::ColumnType, packets::Column, prelude::FromRow, row::new_row, Value,
+    };
+
+    use super::*;
+
+    #[test]
+    fn can_convert_from_row() {
+        let cols = [
+            ("term_id", Value::UInt(1)),
+            ("name", Value::Bytes(b"Uncategorized".to_vec())),
+            ("slug", Value::Bytes(b"uncategorized".to_vec())),
+            ("term_group", Value::Int(0)),
+            ("term_taxonomy_id", Value::UInt(1)),
+            ("taxonomy", Value::Bytes(b"category".to_vec())),
+            ("description", Value::Bytes(Vec::new())),
+            ("parent", Value::UInt(0)),
+            ("count", Value::Int(3)),
+        ];
+        let columns = cols
+            .iter()
+            .map(|(name, _)| {
+                Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(name.as_bytes())
+            })
+            .collect();
+        let row = new_row(cols.into_iter().map(|(_, v)| v).collect(), columns);
+
+        let term = WpTerm::from_row(row);
+
+        assert_eq!(term.term_id, 1);
+        assert_eq!(term.slug, "uncategorized");
+        assert_eq!(term.taxonomy, "category");
+        assert_eq!(term.count, 3);
+    }
+}
@@ -1,55 +1,260 @@
+pub mod arg_builder;
 #[cfg(any(feature = "query_sync", feature = "query_async"))]
-use crate::sql::get_conn;
+mod meta;
+mod password;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod query_builder;
+pub mod roles;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod update;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+mod user_query;
+
+#[cfg(feature = "query_sync")]
+use crate::{
+    query_builder::QueryAndValues,
+    sql::{get_conn, table_prefix::get_table_prefix, traits::Executor},
+    wp_options::get_option,
+    WpError,
+};
 #[cfg(feature = "query_sync")]
 use mysql::prelude::Queryable;
-#[cfg(feature = "query_async")]
-use mysql_async::prelude::*;
 
 use mysql_common::time::PrimitiveDateTime;
 use serde::ser::SerializeStruct;
 
-use crate::sql::{date::get_date_now, find_col};
+use crate::sql::{
+    date::{get_date_now, get_utc_date_now},
+    find_col,
+};
 
-#[derive(Debug)]
+#[cfg(feature = "query_sync")]
+pub use self::meta::{delete_user_meta, get_user_meta, update_user_meta};
+pub use self::password::{wp_check_password, wp_hash_password};
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+use self::update::PendingPassword;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use self::update::DELETE_WITH_USER_POST_TYPES;
+#[cfg(any(feature = "query_sync", feature = "query_async"))]
+pub use self::user_query::WpUserQuery;
+#[cfg(feature = "query_sync")]
+use self::{
+    query_builder::{set_password_query, user_by_query},
+    roles::{CapabilityList, UserCapabilities, WpRoles},
+};
+
+/// Field to retrieve a user by, like the `$field` of `get_user_by` in WordPress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserField<'a> {
+    Id(u64),
+    Login(&'a str),
+    Email(&'a str),
+    /// The user_nicename, used in author URLs.
+    Slug(&'a str),
+}
+
+#[derive(Debug, Clone)]
 pub struct WpUser {
     pub id: u64,
     user_login: String,
-    _user_pass: String,
+    user_pass: String,
     pub user_nicename: String,
-    _user_email: String,
+    user_email: String,
     pub user_url: String,
     pub user_registered: PrimitiveDateTime,
     _user_activation_key: String,
     _user_status: i64,
     pub display_name: String,
+    /// Plain text password hashed when the user is inserted or updated.
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    new_password: PendingPassword,
 }
 
 impl WpUser {
+    /// Creates a user to insert, with the nicename and display name derived from the login like `wp_insert_user`.
+    pub fn new(user_login: &str, user_email: &str) -> Self {
+        Self {
+            id: 0,
+            user_login: String::from(user_login),
+            user_pass: String::new(),
+            user_nicename: sanitize_nicename(user_login),
+            user_email: String::from(user_email),
+            user_url: String::new(),
+            user_registered: get_utc_date_now(),
+            _user_activation_key: String::new(),
+            _user_status: 0,
+            display_name: String::from(user_login),
+            #[cfg(any(feature = "query_sync", feature = "query_async"))]
+            new_password: PendingPassword::default(),
+        }
+    }
+
     /// Retrieves a user from the database by their user ID
     /// Returns a result containing an option as either the database query could fail, or there could be
     /// no user for the ID provided.
     #[cfg(feature = "query_sync")]
-    pub fn get_user_by_id(id: u64) -> Result<Option<Self>, mysql::Error> {
+    pub fn get_user_by_id(id: u64) -> Result<Option<Self>, WpError> {
+        Self::get_user_by(UserField::Id(id))
+    }
+
+    /// Retrieves a user by ID, login, email or slug.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use wp_query_rs::{UserField, WpUser};
+    ///
+    /// let user = WpUser::get_user_by(UserField::Login("admin"));
+    /// ```
+    #[cfg(feature = "query_sync")]
+    pub fn get_user_by(field: UserField) -> Result<Option<Self>, WpError> {
+        let mut conn = get_conn()?;
+
+        Self::get_user_by_with_connection(&mut conn, field)
+    }
+
+    /// Retrieves a user by ID, login, email or slug using a mysql connection, or any other [`Executor`].
+    #[cfg(feature = "query_sync")]
+    pub fn get_user_by_with_connection(
+        conn: &mut impl Executor,
+        field: UserField,
+    ) -> Result<Option<Self>, WpError> {
+        let QueryAndValues(q, values) = user_by_query(field, get_table_prefix());
+
+        conn.fetch_first(&q, values)
+    }
+
+    pub fn user_login(&self) -> &str {
+        &self.user_login
+    }
+
+    pub fn user_email(&self) -> &str {
+        &self.user_email
+    }
+
+    /// Changes the login, which must stay unique when the user is updated.
+    pub fn set_user_login(&mut self, user_login: &str) {
+        self.user_login = String::from(user_login);
+    }
+
+    /// Changes the email, which must stay unique when the user is updated.
+    pub fn set_user_email(&mut self, user_email: &str) {
+        self.user_email = String::from(user_email);
+    }
+
+    /// Sets a plain text password, which is hashed with `wp_hash_password` when the user is inserted or updated.
+    #[cfg(any(feature = "query_sync", feature = "query_async"))]
+    pub fn set_user_pass(&mut self, password: &str) {
+        self.new_password = PendingPassword::from(password);
+    }
+
+    /// Checks a plain text password against the password hash of the user, like `wp_check_password`.
+    pub fn check_password(&self, password: &str) -> bool {
+        wp_check_password(password, &self.user_pass)
+    }
+
+    /// Hashes and saves a new password for the user, resetting the activation key like `wp_set_password`.
+    #[cfg(feature = "query_sync")]
+    pub fn set_password(&mut self, password: &str) -> Result<(), WpError> {
+        let user_pass = wp_hash_password(password)?;
         let mut conn = get_conn()?;
 
-        let stmt = conn.prep("SELECT * FROM wp_users WHERE ID = ?")?;
+        let QueryAndValues(q, values) = set_password_query(self.id, &user_pass);
+        conn.exec_drop(q, values)?;
 
-        let value = mysql_common::Value::UInt(id);
+        self.user_pass = user_pass;
+        self._user_activation_key.clear();
 
-        conn.exec_first(stmt, mysql::Params::Positional(vec![value]))
-            .map(|row: Option<mysql_common::Row>| row.map(|r| WpUser::from(r)))
+        Ok(())
     }
-    #[cfg(feature = "query_async")]
-    pub async fn get_user_by_id(id: u64) -> Result<Option<Self>, mysql_async::Error> {
+
+    /// Retrieves the roles and capabilities of the user from the `wp_capabilities` meta and the roles of the site.
+    #[cfg(feature = "query_sync")]
+    pub fn capabilities(&self) -> Result<UserCapabilities, WpError> {
+        let prefix = get_table_prefix();
+
+        let caps: Option<CapabilityList> =
+            get_user_meta(self.id, &format!("{prefix}capabilities"))?;
+        let wp_roles: Option<WpRoles> = get_option(&WpRoles::option_name(prefix))?;
+
+        Ok(UserCapabilities::new(
+            caps.unwrap_or_default(),
+            &wp_roles.unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::future::Future;
+
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{r#async::get_conn, table_prefix::get_table_prefix, traits::r#async::Executor},
+        WpError,
+    };
+
+    use super::{query_builder::user_by_query, roles::UserCapabilities, UserField, WpUser};
+
+    pub use super::meta::r#async::{delete_user_meta, get_user_meta, update_user_meta};
+    pub use super::user_query::r#async::WpUserQuery;
+
+    /// Async database methods of users, see the blocking methods of [`super::WpUser`].
+    /// Users are inserted with the async `Insertable`.
+    pub trait WpUserExt {
+        /// Writes all fields of the user to the row with its ID, like `wp_update_user`.
+        ///
+        /// Fails if the login is empty or the login or email is used by another user.
+        /// A password set with `set_user_pass` is hashed and clears the activation key.
+        fn update(&mut self) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Permanently deletes the user and their meta like `wp_delete_user`.
+        ///
+        /// With `reassign`, all posts of the user are given to that user. Otherwise posts of the
+        /// `DELETE_WITH_USER_POST_TYPES` are deleted with their meta, term relationships and comments,
+        /// and posts of other types are kept. Links of the link manager are not changed.
+        ///
+        /// All rows are changed in a single transaction.
+        fn delete(self, reassign: Option<u64>) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Hashes and saves a new password for the user, resetting the activation key like `wp_set_password`.
+        fn set_password(
+            &mut self,
+            password: &str,
+        ) -> impl Future<Output = Result<(), WpError>> + Send;
+
+        /// Retrieves the roles and capabilities of the user from the `wp_capabilities` meta and the roles of the site.
+        fn capabilities(&self) -> impl Future<Output = Result<UserCapabilities, WpError>> + Send;
+    }
+
+    /// Retrieves a user from the database by their user ID
+    /// Returns a result containing an option as either the database query could fail, or there could be
+    /// no user for the ID provided.
+    pub async fn get_user_by_id(id: u64) -> Result<Option<WpUser>, WpError> {
+        get_user_by(UserField::Id(id)).await
+    }
+
+    /// Retrieves a user by ID, login, email or slug.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use wp_query_rs::{r#async::get_user_by, UserField};
+    ///
+    /// let user = get_user_by(UserField::Login("admin")).await;
+    /// ```
+    pub async fn get_user_by(field: UserField<'_>) -> Result<Option<WpUser>, WpError> {
         let mut conn = get_conn().await?;
 
-        let stmt = conn.prep("SELECT * FROM wp_users WHERE ID = ?").await?;
+        get_user_by_with_connection(&mut conn, field).await
+    }
 
-        let value = mysql_common::Value::UInt(id);
+    /// Retrieves a user by ID, login, email or slug using a mysql connection, or any other [`Executor`].
+    pub async fn get_user_by_with_connection(
+        conn: &mut impl Executor,
+        field: UserField<'_>,
+    ) -> Result<Option<WpUser>, WpError> {
+        let QueryAndValues(q, values) = user_by_query(field, get_table_prefix());
 
-        conn.exec_first(stmt, mysql_async::Params::Positional(vec![value]))
-            .await
-            .map(|row: Option<mysql_common::Row>| row.map(|r| WpUser::from(r)))
+        conn.fetch_first(&q, values).await
     }
 }
 
@@ -58,19 +263,30 @@ impl From<mysql_common::Row> for WpUser {
         WpUser {
             id: find_col(&mut value, "ID").unwrap_or(0),
             user_login: find_col(&mut value, "user_login").unwrap_or(String::new()),
-            _user_pass: find_col(&mut value, "user_pass").unwrap_or(String::new()),
+            user_pass: find_col(&mut value, "user_pass").unwrap_or(String::new()),
             user_nicename: find_col(&mut value, "user_nicename").unwrap_or(String::new()),
-            _user_email: find_col(&mut value, "user_email").unwrap_or(String::new()),
+            user_email: find_col(&mut value, "user_email").unwrap_or(String::new()),
             user_url: find_col(&mut value, "user_url").unwrap_or(String::new()),
             user_registered: find_col(&mut value, "user_registered").unwrap_or(get_date_now()),
             _user_activation_key: find_col(&mut value, "user_activation_key")
                 .unwrap_or(String::new()),
             _user_status: find_col(&mut value, "user_status").unwrap_or(0i64),
             display_name: find_col(&mut value, "display_name").unwrap_or(String::new()),
+            #[cfg(any(feature = "query_sync", feature = "query_async"))]
+            new_password: PendingPassword::default(),
         }
     }
 }
 
+impl mysql_common::prelude::FromRow for WpUser {
+    fn from_row_opt(row: mysql_common::Row) -> Result<Self, mysql_common::FromRowError>
+    where
+        Self: Sized,
+    {
+        Ok(Self::from(row))
+    }
+}
+
 impl serde::Serialize for WpUser {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -88,3 +304,45 @@ impl serde::Serialize for WpUser {
         state.end()
     }
 }
+
+/// Derives a nicename from a login like `sanitize_title`, e.g. `John Doe` becomes `john-doe`.
+fn sanitize_nicename(user_login: &str) -> String {
+    let mut nicename = String::new();
+
+    for c in user_login.trim().to_lowercase().chars() {
+        if c.is_whitespace() || c == '-' {
+            if !nicename.is_empty() && !nicename.ends_with('-') {
+                nicename.push('-');
+            }
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            nicename.push(c);
+        }
+    }
+
+    nicename.trim_end_matches('-').chars().take(50).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_nicename_from_login() {
+        assert_eq!(sanitize_nicename("John Doe"), "john-doe");
+        assert_eq!(
+            sanitize_nicename("john.doe@example.com"),
+            "johndoeexamplecom"
+        );
+        assert_eq!(sanitize_nicename(" -admin_2- "), "admin_2");
+    }
+
+    #[test]
+    fn new_user_defaults_to_login() {
+        let user = WpUser::new("John Doe", "john@example.com");
+
+        assert_eq!(user.id, 0);
+        assert_eq!(user.user_nicename, "john-doe");
+        assert_eq!(user.display_name, "John Doe");
+        assert_eq!(user.user_email(), "john@example.com");
+    }
+}
@@ -0,0 +1,107 @@
+pub mod orderby;
+pub mod search_column;
+
+use std::ops::DerefMut;
+
+use crate::{MetaQueryTree, SqlOrder};
+
+use self::{orderby::Orderby, search_column::SearchColumn};
+
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct WpUserQueryArgs {
+    /// Roles the user must all have.
+    pub role: Option<Vec<String>>,
+    /// Roles the user must have at least one of.
+    pub role__in: Option<Vec<String>>,
+    pub role__not_in: Option<Vec<String>>,
+    pub include: Option<Vec<u64>>,
+    pub exclude: Option<Vec<u64>>,
+    /// Search term. Use a leading or trailing `*` to match the start or end of columns only.
+    pub search: Option<String>,
+    /// Columns to search, guessed from the search term if not set.
+    pub search_columns: Option<Vec<SearchColumn>>,
+    /**
+     * A single custom field clause, or nested groups of clauses each with their logical relationship, ‘AND’ or ‘OR’.
+     */
+    pub meta_query: Option<MetaQueryTree>,
+    /// Only users with published posts of these post types.
+    pub has_published_posts: Option<Vec<String>>,
+    pub number: Option<u64>,
+    pub paged: Option<u64>,
+    pub offset: Option<u64>,
+    pub orderby: Orderby,
+    pub order: SqlOrder,
+    /// Counts all matches ignoring pagination in `total_users`.
+    pub count_total: bool,
+    /// Overrides the global table prefix for this query only. Writes always use the global prefix.
+    pub table_prefix: Option<String>,
+}
+
+pub struct WpUserArgBuilder {
+    args: WpUserQueryArgs,
+}
+
+impl WpUserArgBuilder {
+    pub fn new() -> Self {
+        Self {
+            args: WpUserQueryArgs {
+                role: None,
+                role__in: None,
+                role__not_in: None,
+                include: None,
+                exclude: None,
+                search: None,
+                search_columns: None,
+                meta_query: None,
+                has_published_posts: None,
+                number: None,
+                paged: None,
+                offset: None,
+                orderby: Orderby::Login,
+                order: SqlOrder::Asc,
+                count_total: true,
+                table_prefix: None,
+            },
+        }
+    }
+}
+
+impl Default for WpUserArgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<WpUserArgBuilder> for WpUserQueryArgs {
+    fn from(value: WpUserArgBuilder) -> Self {
+        value.args
+    }
+}
+
+impl std::ops::Deref for WpUserArgBuilder {
+    type Target = WpUserQueryArgs;
+
+    fn deref(&self) -> &Self::Target {
+        &self.args
+    }
+}
+
+impl DerefMut for WpUserArgBuilder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_by_login_by_default() {
+        let args: WpUserQueryArgs = WpUserArgBuilder::new().into();
+
+        assert_eq!(args.orderby, Orderby::Login);
+        assert!(args.count_total);
+    }
+}
@@ -0,0 +1,51 @@
+/// Column to order users by, like the `orderby` arg of WP_User_Query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Orderby {
+    Id,
+    DisplayName,
+    Email,
+    /// Keeps the order of the `include` arg.
+    Include,
+    Login,
+    Nicename,
+    /// Number of published posts of the user.
+    PostCount,
+    Registered,
+    Url,
+    MetaValue(String),
+    MetaValueNum(String),
+    None,
+}
+
+impl std::fmt::Display for Orderby {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Id => "ID",
+                Self::DisplayName => "display_name",
+                Self::Email => "user_email",
+                Self::Include => "include",
+                Self::Login => "user_login",
+                Self::Nicename => "user_nicename",
+                Self::PostCount => "post_count",
+                Self::Registered => "user_registered",
+                Self::Url => "user_url",
+                Self::MetaValue(s) => s.as_str(),
+                Self::MetaValueNum(s) => s.as_str(),
+                Self::None => "",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_to_string() {
+        assert_eq!(Orderby::Registered.to_string(), "user_registered");
+    }
+}
@@ -0,0 +1,70 @@
+/// Column searched by the `search` arg, like the `search_columns` arg of WP_User_Query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchColumn {
+    Id,
+    Login,
+    Email,
+    Url,
+    Nicename,
+    DisplayName,
+}
+
+impl SearchColumn {
+    /// Columns searched when none are given, guessed from the search term like WordPress.
+    pub fn defaults_for(search: &str) -> Vec<Self> {
+        if search.contains('@') {
+            vec![Self::Email]
+        } else if !search.is_empty() && search.bytes().all(|c| c.is_ascii_digit()) {
+            vec![Self::Login, Self::Id]
+        } else if search.starts_with("http://") || search.starts_with("https://") {
+            vec![Self::Url]
+        } else {
+            vec![
+                Self::Login,
+                Self::Url,
+                Self::Email,
+                Self::Nicename,
+                Self::DisplayName,
+            ]
+        }
+    }
+}
+
+impl std::fmt::Display for SearchColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Id => "ID",
+                Self::Login => "user_login",
+                Self::Email => "user_email",
+                Self::Url => "user_url",
+                Self::Nicename => "user_nicename",
+                Self::DisplayName => "display_name",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_columns_from_search() {
+        assert_eq!(
+            SearchColumn::defaults_for("admin@example.com"),
+            vec![SearchColumn::Email]
+        );
+        assert_eq!(
+            SearchColumn::defaults_for("42"),
+            vec![SearchColumn::Login, SearchColumn::Id]
+        );
+        assert_eq!(
+            SearchColumn::defaults_for("https://example.com"),
+            vec![SearchColumn::Url]
+        );
+        assert_eq!(SearchColumn::defaults_for("john").len(), 5);
+    }
+}
@@ -0,0 +1,190 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use md5::{Digest, Md5};
+use sha2::Sha384;
+
+/// Cost used by WordPress for bcrypt hashes.
+pub const BCRYPT_COST: u32 = 10;
+
+/// Characters used by phpass for its base64 like encoding and the iteration count.
+const ITOA64: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Hashes a password like `wp_hash_password` in WordPress 6.8, i.e. bcrypt of the password pre-hashed with HMAC-SHA384,
+/// prefixed with `$wp`.
+///
+/// # Example
+/// ```
+/// use wp_query_rs::{wp_check_password, wp_hash_password};
+///
+/// let hash = wp_hash_password("correct horse").unwrap();
+///
+/// assert!(hash.starts_with("$wp$2y$10$"));
+/// assert!(wp_check_password("correct horse", &hash));
+/// ```
+pub fn wp_hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
+    let hash = bcrypt::hash_with_result(pre_hash(password.trim()), BCRYPT_COST)?;
+
+    Ok(format!(
+        "$wp{}",
+        hash.format_for_version(bcrypt::Version::TwoY)
+    ))
+}
+
+/// Checks a plain text password against a hash like `wp_check_password` in WordPress.
+///
+/// Supports the `$wp$2y$` hashes of WordPress 6.8, plain bcrypt hashes, the phpass portable hashes
+/// of older versions, e.g. `$P$B...`, and legacy MD5 hashes.
+pub fn wp_check_password(password: &str, hash: &str) -> bool {
+    if let Some(bcrypt_hash) = hash.strip_prefix("$wp") {
+        return bcrypt_hash.starts_with("$2")
+            && bcrypt::verify(pre_hash(password), bcrypt_hash).unwrap_or(false);
+    }
+    if hash.starts_with("$2") {
+        return bcrypt::verify(password, hash).unwrap_or(false);
+    }
+    if hash.starts_with("$P$") || hash.starts_with("$H$") {
+        return phpass_crypt(password, hash).is_some_and(|h| constant_time_eq(&h, hash));
+    }
+    if hash.len() <= 32 {
+        return constant_time_eq(&hex(&Md5::digest(password)), hash);
+    }
+
+    false
+}
+
+/// Pre-hashes the password so bcrypt does not truncate passwords longer than 72 bytes.
+fn pre_hash(password: &str) -> String {
+    let mut mac =
+        Hmac::<Sha384>::new_from_slice(b"wp-sha384").expect("HMAC accepts keys of any length");
+    mac.update(password.as_bytes());
+
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// Hashes the password with the setting of a phpass portable hash, i.e. iteration count and salt.
+fn phpass_crypt(password: &str, setting: &str) -> Option<String> {
+    let setting = setting.get(..12)?;
+    let count_log2 = ITOA64.iter().position(|c| *c == setting.as_bytes()[3])?;
+    if !(7..=30).contains(&count_log2) {
+        return None;
+    }
+    let salt = &setting[4..12];
+
+    let mut hash = Md5::new()
+        .chain_update(salt)
+        .chain_update(password)
+        .finalize();
+    for _ in 0..(1u32 << count_log2) {
+        hash = Md5::new()
+            .chain_update(hash)
+            .chain_update(password)
+            .finalize();
+    }
+
+    Some(format!("{setting}{}", encode64(&hash)))
+}
+
+/// The base64 like encoding of phpass, which differs from standard base64 in the alphabet and bit order.
+fn encode64(input: &[u8]) -> String {
+    let mut output = String::new();
+
+    for chunk in input.chunks(3) {
+        let value = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |value, (i, byte)| value | (*byte as u32) << (8 * i));
+
+        for i in 0..=chunk.len() {
+            output.push(ITOA64[((value >> (6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+
+    output
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_check_phpass_hashes() {
+        // Test vector from phpass
+        assert!(wp_check_password(
+            "test12345",
+            "$P$9IQRaTwmfeRo7ud9Fh4E2PdI0S3r.L0"
+        ));
+        assert!(!wp_check_password(
+            "test12346",
+            "$P$9IQRaTwmfeRo7ud9Fh4E2PdI0S3r.L0"
+        ));
+    }
+
+    #[test]
+    fn can_check_bcrypt_hashes() {
+        // Example from the PHP password_verify documentation
+        assert!(wp_check_password(
+            "rasmuslerdorf",
+            "$2y$10$.vGA1O9wmRjrwAVXD98HNOgsNpDczlqm3Jq7KnEd1rVAGv3Fykk1a"
+        ));
+        assert!(!wp_check_password(
+            "rasmus",
+            "$2y$10$.vGA1O9wmRjrwAVXD98HNOgsNpDczlqm3Jq7KnEd1rVAGv3Fykk1a"
+        ));
+    }
+
+    #[test]
+    fn can_check_wp_bcrypt_hashes() {
+        let hash = "$wp$2y$10$KXb0L8rFGTYrw8owDAdX8OJyXks53SdLiXDg48XaY1JoYldFGjF8C";
+
+        assert_eq!(
+            pre_hash("password"),
+            "ZEIBxmCIjkyF8MNbMmsrJOPmp2/ioyAvd90BaIOY1lr6z3gfcWZusfjOv0kWasvS"
+        );
+        assert!(wp_check_password("password", hash));
+        assert!(!wp_check_password("Password", hash));
+    }
+
+    #[test]
+    fn can_check_md5_hashes() {
+        assert!(wp_check_password(
+            "password",
+            "5f4dcc3b5aa765d61d8327deb882cf99"
+        ));
+        assert!(!wp_check_password("password", ""));
+    }
+
+    #[test]
+    fn rejects_unknown_hashes() {
+        assert!(!wp_check_password(
+            "password",
+            "$wp$P$9IQRaTwmfeRo7ud9Fh4E2PdI0S3r.L0"
+        ));
+        assert!(!wp_check_password("password", "$P$"));
+        assert!(!wp_check_password(
+            "password",
+            "$argon2id$v=19$m=65536,t=4,p=1$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG"
+        ));
+    }
+
+    #[test]
+    fn hashes_can_be_checked() {
+        let hash = wp_hash_password(" secret ").unwrap();
+
+        assert!(hash.starts_with("$wp$2y$10$"));
+        // Passwords are trimmed before hashing like WordPress
+        assert!(wp_check_password("secret", &hash));
+        assert_ne!(wp_hash_password("secret").unwrap(), hash);
+    }
+}
@@ -0,0 +1,455 @@
+use mysql_common::Value;
+use sql_paginatorr::LimitOffsetPair;
+
+use crate::{
+    query_builder::{
+        implode_to_question_mark, push_meta_query, MetaTable, QueryAndValues, StmtValues,
+    },
+    sql::table_prefix::{get_table_prefix, table_prefix_or_global},
+    WpError,
+};
+
+use super::{
+    arg_builder::{orderby::Orderby, search_column::SearchColumn, WpUserQueryArgs},
+    UserField,
+};
+
+pub struct UserQueryBuilder {
+    args: WpUserQueryArgs,
+}
+
+impl UserQueryBuilder {
+    pub fn new(args: WpUserQueryArgs) -> Self {
+        Self { args }
+    }
+
+    /// Builds the users query, and the query counting all matches if `count_total` is set.
+    ///
+    /// # Errors
+    /// When the table prefix of the args is invalid.
+    pub fn query(self) -> Result<(QueryAndValues, Option<QueryAndValues>), WpError> {
+        let args = self.args;
+        let prefix = table_prefix_or_global(args.table_prefix.as_deref())?.to_string();
+        let users = format!("{prefix}users");
+
+        let mut conditions = String::from(" WHERE 1 = 1");
+        let mut values: StmtValues = vec![];
+
+        if let Some(ids) = &args.include {
+            push_in(&mut conditions, &mut values, &users, "IN", ids);
+        }
+        if let Some(ids) = &args.exclude {
+            push_in(&mut conditions, &mut values, &users, "NOT IN", ids);
+        }
+
+        /* Roles are matched in the serialized capabilities meta */
+        if let Some(roles) = args.role {
+            for role in roles {
+                conditions.push_str(" AND ");
+                push_role(&mut conditions, &mut values, &prefix, role);
+            }
+        }
+        if let Some(roles) = args.role__in.filter(|roles| !roles.is_empty()) {
+            conditions.push_str(" AND (");
+            for (i, role) in roles.into_iter().enumerate() {
+                if i > 0 {
+                    conditions.push_str(" OR ");
+                }
+                push_role(&mut conditions, &mut values, &prefix, role);
+            }
+            conditions.push(')');
+        }
+        if let Some(roles) = args.role__not_in {
+            for role in roles {
+                conditions.push_str(" AND NOT ");
+                push_role(&mut conditions, &mut values, &prefix, role);
+            }
+        }
+
+        /* Search */
+        if let Some(search) = args.search.filter(|s| !s.is_empty()) {
+            let (term, pattern) = search_pattern(&search);
+            let columns = args
+                .search_columns
+                .unwrap_or_else(|| SearchColumn::defaults_for(term));
+
+            let q = columns
+                .iter()
+                .map(|col| match col {
+                    SearchColumn::Id => {
+                        values.push(Value::Bytes(term.as_bytes().to_vec()));
+                        format!("{users}.ID = ?")
+                    }
+                    col => {
+                        values.push(Value::Bytes(pattern.as_bytes().to_vec()));
+                        format!("{users}.{col} LIKE ?")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ");
+
+            conditions.push_str(&format!(" AND ({q})"));
+        }
+
+        if let Some(post_types) = args.has_published_posts.filter(|types| !types.is_empty()) {
+            conditions.push_str(&format!(
+                " AND {users}.ID IN (SELECT DISTINCT {prefix}posts.post_author FROM {prefix}posts WHERE {prefix}posts.post_status = 'publish' AND {prefix}posts.post_type IN ({}))",
+                implode_to_question_mark(&post_types)
+            ));
+            values.extend(post_types.into_iter().map(|t| Value::Bytes(t.into_bytes())));
+        }
+
+        if let Some(meta_query) = args.meta_query {
+            push_meta_query(
+                &mut conditions,
+                &mut values,
+                MetaTable {
+                    table: &format!("{prefix}usermeta"),
+                    object_id_col: "user_id",
+                    parent_id_col: &format!("{users}.ID"),
+                },
+                meta_query,
+            );
+        }
+
+        /* Count all matches before ordering and pagination */
+        let count = if args.count_total {
+            Some(QueryAndValues(
+                format!("SELECT COUNT(*) FROM {users}{conditions};"),
+                values.clone(),
+            ))
+        } else {
+            None
+        };
+
+        let mut q = format!("SELECT {users}.* FROM {users}{conditions}");
+
+        /* Add order conditions */
+        let order = args.order.to_string();
+        match args.orderby {
+            Orderby::None => (),
+            Orderby::Include if args.include.as_ref().is_some_and(|ids| !ids.is_empty()) => {
+                let ids = args.include.unwrap_or_default();
+
+                q.push_str(&format!(
+                    " ORDER BY FIELD({users}.ID, {})",
+                    implode_to_question_mark(&ids)
+                ));
+                values.extend(ids.into_iter().map(Value::UInt));
+            }
+            Orderby::Include => q.push_str(&format!(" ORDER BY {users}.user_login {order}")),
+            Orderby::PostCount => q.push_str(&format!(
+                " ORDER BY (SELECT COUNT(*) FROM {prefix}posts WHERE {prefix}posts.post_author = {users}.ID AND {prefix}posts.post_status = 'publish' AND {prefix}posts.post_type = 'post') {order}"
+            )),
+            Orderby::MetaValue(key) => push_meta_order(&mut q, &mut values, &prefix, key, "", &order),
+            Orderby::MetaValueNum(key) => {
+                push_meta_order(&mut q, &mut values, &prefix, key, "+0", &order)
+            }
+            col => q.push_str(&format!(" ORDER BY {users}.{col} {order}")),
+        }
+
+        /* Add pagination, no limit if number is not set */
+        if let Some(number) = args.number {
+            let LimitOffsetPair { offset, limit } = sql_paginatorr::for_page(
+                args.paged.unwrap_or(1).saturating_sub(1) as usize,
+                number as usize,
+            );
+            // Offset takes priority over paged
+            let offset = args.offset.unwrap_or(offset as u64);
+
+            q.push_str(" LIMIT ? OFFSET ?");
+            values.push(Value::UInt(limit as u64));
+            values.push(Value::UInt(offset));
+        }
+
+        q.push(';');
+
+        Ok((QueryAndValues(q, values), count))
+    }
+}
+
+/// Orders by the first value of a user meta key, optionally cast with a suffix like `+0`.
+fn push_meta_order(
+    s: &mut String,
+    v: &mut StmtValues,
+    prefix: &str,
+    key: String,
+    cast: &str,
+    order: &str,
+) {
+    s.push_str(&format!(
+        " ORDER BY (SELECT {prefix}usermeta.meta_value{cast} FROM {prefix}usermeta WHERE {prefix}usermeta.user_id = {prefix}users.ID AND {prefix}usermeta.meta_key = ? LIMIT 1) {order}"
+    ));
+    v.push(Value::Bytes(key.into_bytes()));
+}
+
+/// Selects a single user by the field given.
+pub fn user_by_query(field: UserField, table_prefix: &str) -> QueryAndValues {
+    let (col, value) = match field {
+        UserField::Id(id) => ("ID", Value::UInt(id)),
+        UserField::Login(login) => ("user_login", Value::from(login)),
+        UserField::Email(email) => ("user_email", Value::from(email)),
+        UserField::Slug(slug) => ("user_nicename", Value::from(slug)),
+    };
+
+    QueryAndValues(
+        format!("SELECT * FROM {table_prefix}users WHERE {col} = ? LIMIT 1;"),
+        vec![value],
+    )
+}
+
+/// Saves a password hash, clearing the activation key used to reset the password.
+pub fn set_password_query(user_id: u64, user_pass: &str) -> QueryAndValues {
+    QueryAndValues(
+        format!(
+            "UPDATE {}users SET user_pass = ?, user_activation_key = '' WHERE ID = ?;",
+            get_table_prefix()
+        ),
+        vec![Value::from(user_pass), Value::UInt(user_id)],
+    )
+}
+
+fn push_in(s: &mut String, v: &mut StmtValues, users: &str, op: &str, ids: &[u64]) {
+    // No user is in an empty list, and every user is not in it
+    if ids.is_empty() {
+        if op == "IN" {
+            s.push_str(" AND 1 = 0");
+        }
+
+        return;
+    }
+
+    s.push_str(&format!(
+        " AND {users}.ID {op} ({})",
+        implode_to_question_mark(ids)
+    ));
+    v.extend(ids.iter().map(|id| Value::UInt(*id)));
+}
+
+/// Adds a condition matching users with the role in their serialized capabilities, e.g. `a:1:{s:6:"editor";b:1;}`.
+fn push_role(s: &mut String, v: &mut StmtValues, prefix: &str, role: String) {
+    s.push_str(&format!(
+        "EXISTS (SELECT 1 FROM {prefix}usermeta WHERE {prefix}usermeta.user_id = {prefix}users.ID AND {prefix}usermeta.meta_key = ? AND {prefix}usermeta.meta_value LIKE ?)"
+    ));
+    v.push(Value::Bytes(format!("{prefix}capabilities").into_bytes()));
+    v.push(Value::Bytes(
+        format!("%\"{}\"%", escape_like(&role)).into_bytes(),
+    ));
+}
+
+/// Returns the search term without wildcards and its LIKE pattern. A leading or trailing `*` only
+/// matches the end or start of columns, otherwise the term can be anywhere in the column.
+fn search_pattern(search: &str) -> (&str, String) {
+    let leading = search.starts_with('*');
+    let trailing = search.ends_with('*') && search.len() > 1;
+    let term = search.trim_matches('*');
+    let escaped = escape_like(term);
+
+    let pattern = match (leading, trailing) {
+        (true, false) => format!("%{escaped}"),
+        (false, true) => format!("{escaped}%"),
+        _ => format!("%{escaped}%"),
+    };
+
+    (term, pattern)
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MetaQuery, MetaQueryTree, SqlOrder, SqlSearchOperators, WpUserArgBuilder};
+
+    use super::*;
+
+    fn build(args: WpUserArgBuilder) -> (QueryAndValues, Option<QueryAndValues>) {
+        UserQueryBuilder::new(args.into()).query().unwrap()
+    }
+
+    #[test]
+    fn invalid_table_prefix_is_an_error() {
+        let mut args: WpUserQueryArgs = WpUserArgBuilder::new().into();
+        args.table_prefix = Some(String::from("wp_users; --"));
+
+        assert!(matches!(
+            UserQueryBuilder::new(args).query(),
+            Err(WpError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn default_orders_by_login() {
+        let (QueryAndValues(q, values), count) = build(WpUserArgBuilder::new());
+
+        assert_eq!(
+            q,
+            "SELECT wp_users.* FROM wp_users WHERE 1 = 1 ORDER BY wp_users.user_login ASC;"
+        );
+        assert!(values.is_empty());
+
+        let QueryAndValues(count_q, _) = count.unwrap();
+        assert_eq!(count_q, "SELECT COUNT(*) FROM wp_users WHERE 1 = 1;");
+    }
+
+    #[test]
+    fn can_query_roles() {
+        let mut args = WpUserArgBuilder::new();
+        args.role__in = Some(vec![String::from("author"), String::from("editor")]);
+        args.role__not_in = Some(vec![String::from("administrator")]);
+
+        let (QueryAndValues(q, values), _) = build(args);
+
+        assert!(q.contains(" AND (EXISTS (SELECT 1 FROM wp_usermeta WHERE wp_usermeta.user_id = wp_users.ID AND wp_usermeta.meta_key = ? AND wp_usermeta.meta_value LIKE ?) OR EXISTS"));
+        assert!(q.contains(" AND NOT EXISTS (SELECT 1 FROM wp_usermeta"));
+        assert_eq!(values.len(), 6);
+        assert_eq!(values[0], Value::from("wp_capabilities"));
+        assert_eq!(values[1], Value::from("%\"author\"%"));
+        assert_eq!(values[5], Value::from("%\"administrator\"%"));
+    }
+
+    #[test]
+    fn can_include_and_exclude() {
+        let mut args = WpUserArgBuilder::new();
+        args.include = Some(vec![3, 1]);
+        args.exclude = Some(vec![2]);
+        args.orderby = Orderby::Include;
+
+        let (QueryAndValues(q, values), _) = build(args);
+
+        assert!(q.contains(" AND wp_users.ID IN (?,?) AND wp_users.ID NOT IN (?)"));
+        assert!(q.ends_with(" ORDER BY FIELD(wp_users.ID, ?,?);"));
+        assert_eq!(
+            values,
+            vec![
+                Value::UInt(3),
+                Value::UInt(1),
+                Value::UInt(2),
+                Value::UInt(3),
+                Value::UInt(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_search_columns() {
+        let mut args = WpUserArgBuilder::new();
+        args.search = Some(String::from("jo*"));
+        args.search_columns = Some(vec![SearchColumn::Login, SearchColumn::DisplayName]);
+
+        let (QueryAndValues(q, values), _) = build(args);
+
+        assert!(q.contains(" AND (wp_users.user_login LIKE ? OR wp_users.display_name LIKE ?)"));
+        assert_eq!(values, vec![Value::from("jo%"), Value::from("jo%")]);
+    }
+
+    #[test]
+    fn search_guesses_columns() {
+        let mut args = WpUserArgBuilder::new();
+        args.search = Some(String::from("12"));
+
+        let (QueryAndValues(q, values), _) = build(args);
+
+        assert!(q.contains(" AND (wp_users.user_login LIKE ? OR wp_users.ID = ?)"));
+        assert_eq!(values, vec![Value::from("%12%"), Value::from("12")]);
+    }
+
+    #[test]
+    fn search_patterns_escape_wildcards() {
+        assert_eq!(search_pattern("*son"), ("son", String::from("%son")));
+        assert_eq!(search_pattern("100%"), ("100%", String::from("%100\\%%")));
+        assert_eq!(search_pattern("john_doe*").1, "john\\_doe%");
+    }
+
+    #[test]
+    fn can_query_meta() {
+        let mut args = WpUserArgBuilder::new();
+        args.meta_query = Some(MetaQueryTree::Clause(MetaQuery::new(
+            "country",
+            "JP",
+            SqlSearchOperators::Equals,
+        )));
+
+        let (QueryAndValues(q, _), count) = build(args);
+
+        assert!(q.contains("SELECT 1 FROM wp_usermeta WHERE wp_usermeta.user_id = wp_users.ID AND wp_usermeta.meta_key = ?"));
+        assert!(count.unwrap().0.contains("wp_usermeta.meta_key = ?"));
+    }
+
+    #[test]
+    fn can_order_by_meta_and_paginate() {
+        let mut args = WpUserArgBuilder::new();
+        args.orderby = Orderby::MetaValueNum(String::from("score"));
+        args.order = SqlOrder::Desc;
+        args.number = Some(10);
+        args.paged = Some(2);
+        args.count_total = false;
+
+        let (QueryAndValues(q, values), count) = build(args);
+
+        assert!(q.ends_with(" ORDER BY (SELECT wp_usermeta.meta_value+0 FROM wp_usermeta WHERE wp_usermeta.user_id = wp_users.ID AND wp_usermeta.meta_key = ? LIMIT 1) DESC LIMIT ? OFFSET ?;"));
+        assert_eq!(
+            values,
+            vec![Value::from("score"), Value::UInt(10), Value::UInt(10)]
+        );
+        assert!(count.is_none());
+    }
+
+    #[test]
+    fn can_filter_authors_with_posts() {
+        let mut args = WpUserArgBuilder::new();
+        args.has_published_posts = Some(vec![String::from("post")]);
+        args.orderby = Orderby::PostCount;
+
+        let (QueryAndValues(q, values), _) = build(args);
+
+        assert!(q.contains(" AND wp_users.ID IN (SELECT DISTINCT wp_posts.post_author FROM wp_posts WHERE wp_posts.post_status = 'publish' AND wp_posts.post_type IN (?))"));
+        assert!(q.contains(
+            " ORDER BY (SELECT COUNT(*) FROM wp_posts WHERE wp_posts.post_author = wp_users.ID"
+        ));
+        assert_eq!(values, vec![Value::from("post")]);
+    }
+
+    #[test]
+    fn empty_lists_are_valid_sql() {
+        let mut args = WpUserArgBuilder::new();
+        args.exclude = Some(vec![]);
+        args.has_published_posts = Some(vec![]);
+
+        let (QueryAndValues(q, _), _) = build(args);
+
+        assert!(!q.contains("()"));
+        assert!(!q.contains("NOT IN"));
+        assert!(!q.contains("post_author"));
+
+        let mut args = WpUserArgBuilder::new();
+        args.include = Some(vec![]);
+
+        let (QueryAndValues(q, _), _) = build(args);
+
+        assert!(q.contains(" WHERE 1 = 1 AND 1 = 0"));
+        assert!(!q.contains("()"));
+    }
+
+    #[test]
+    fn can_get_user_by_field() {
+        let QueryAndValues(q, values) = user_by_query(UserField::Slug("john-doe"), "wp_");
+
+        assert_eq!(q, "SELECT * FROM wp_users WHERE user_nicename = ? LIMIT 1;");
+        assert_eq!(values, vec![Value::from("john-doe")]);
+    }
+
+    #[test]
+    fn setting_password_clears_activation_key() {
+        let QueryAndValues(q, values) = set_password_query(3, "$wp$2y$10$hash");
+
+        assert_eq!(
+            q,
+            "UPDATE wp_users SET user_pass = ?, user_activation_key = '' WHERE ID = ?;"
+        );
+        assert_eq!(values, vec![Value::from("$wp$2y$10$hash"), Value::UInt(3)]);
+    }
+}
@@ -0,0 +1,279 @@
+use std::{collections::HashMap, fmt};
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+/// A role from the `wp_user_roles` option, e.g. `administrator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WpRole {
+    pub name: String,
+    pub capabilities: HashMap<String, bool>,
+}
+
+/// All roles of the site, decoded from the PHP serialized `wp_user_roles` option.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WpRoles {
+    roles: HashMap<String, WpRole>,
+}
+
+impl WpRoles {
+    /// Name of the option storing the roles, which is prefixed with the table prefix.
+    pub fn option_name(table_prefix: &str) -> String {
+        format!("{table_prefix}user_roles")
+    }
+
+    pub fn get_role(&self, role: &str) -> Option<&WpRole> {
+        self.roles.get(role)
+    }
+
+    pub fn is_role(&self, role: &str) -> bool {
+        self.roles.contains_key(role)
+    }
+
+    /// Names of all roles.
+    pub fn role_names(&self) -> Vec<&str> {
+        self.roles.keys().map(String::as_str).collect()
+    }
+}
+
+impl From<HashMap<String, WpRole>> for WpRoles {
+    fn from(roles: HashMap<String, WpRole>) -> Self {
+        Self { roles }
+    }
+}
+
+/// Roles and capabilities of a user, resolved like `WP_User::get_role_caps`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserCapabilities {
+    /// Roles of the user, in the order they were granted.
+    pub roles: Vec<String>,
+    /// Roles and capabilities granted to the user directly, from the `wp_capabilities` meta.
+    pub caps: HashMap<String, bool>,
+    /// Capabilities of all roles of the user, overridden by the user's own caps.
+    pub allcaps: HashMap<String, bool>,
+}
+
+impl UserCapabilities {
+    pub fn new(caps: CapabilityList, wp_roles: &WpRoles) -> Self {
+        let CapabilityList(caps) = caps;
+
+        let roles: Vec<String> = caps
+            .iter()
+            .map(|(cap, _)| cap)
+            .filter(|cap| wp_roles.is_role(cap))
+            .cloned()
+            .collect();
+
+        let mut allcaps: HashMap<String, bool> = HashMap::new();
+        for role in roles.iter().filter_map(|role| wp_roles.get_role(role)) {
+            allcaps.extend(role.capabilities.clone());
+        }
+        allcaps.extend(caps.iter().cloned());
+
+        Self {
+            roles,
+            caps: caps.into_iter().collect(),
+            allcaps,
+        }
+    }
+
+    /// Whether the user has the capability, e.g. `edit_posts`.
+    pub fn has_cap(&self, cap: &str) -> bool {
+        self.allcaps.get(cap).copied().unwrap_or(false)
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Capabilities keeping the order of the serialized array, e.g. `a:1:{s:13:"administrator";b:1;}`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapabilityList(pub Vec<(String, bool)>);
+
+impl<'de> Deserialize<'de> for CapabilityList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CapabilityListVisitor;
+
+        impl<'de> Visitor<'de> for CapabilityListVisitor {
+            type Value = CapabilityList;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an array of capabilities")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut caps = vec![];
+
+                while let Some((cap, Grant(granted))) = map.next_entry::<String, Grant>()? {
+                    caps.push((cap, granted));
+                }
+
+                Ok(CapabilityList(caps))
+            }
+        }
+
+        deserializer.deserialize_map(CapabilityListVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for WpRole {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct WpRoleVisitor;
+
+        impl<'de> Visitor<'de> for WpRoleVisitor {
+            type Value = WpRole;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a role with a name and capabilities")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut name = None;
+                let mut capabilities = CapabilityList::default();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "name" => name = Some(map.next_value()?),
+                        "capabilities" => capabilities = map.next_value()?,
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(WpRole {
+                    name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+                    capabilities: capabilities.0.into_iter().collect(),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(WpRoleVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for WpRoles {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HashMap::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Whether a capability is granted. PHP may store it as a boolean, an integer or a string.
+struct Grant(bool);
+
+impl<'de> Deserialize<'de> for Grant {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct GrantVisitor;
+
+        impl<'de> Visitor<'de> for GrantVisitor {
+            type Value = Grant;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a boolean, integer or string")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(Grant(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(Grant(v != 0))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(Grant(v != 0))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Grant(!matches!(v, "" | "0")))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(Grant(false))
+            }
+
+            // PHP strings are read as a sequence of bytes
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = vec![];
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+
+                Ok(Grant(!matches!(bytes.as_slice(), b"" | b"0")))
+            }
+        }
+
+        deserializer.deserialize_any(GrantVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maybe_unserialize;
+
+    use super::*;
+
+    const USER_ROLES: &str = "a:2:{s:13:\"administrator\";a:2:{s:4:\"name\";s:13:\"Administrator\";s:12:\"capabilities\";a:2:{s:14:\"manage_options\";b:1;s:10:\"edit_posts\";b:1;}}s:6:\"author\";a:2:{s:4:\"name\";s:6:\"Author\";s:12:\"capabilities\";a:2:{s:10:\"edit_posts\";b:1;s:7:\"level_2\";i:1;}}}";
+
+    #[test]
+    fn can_decode_user_roles_option() {
+        let roles: WpRoles = maybe_unserialize(USER_ROLES).unwrap();
+
+        let author = roles.get_role("author").unwrap();
+        assert_eq!(author.name, "Author");
+        assert_eq!(author.capabilities.get("level_2"), Some(&true));
+        assert!(roles.is_role("administrator"));
+        assert!(!roles.is_role("editor"));
+    }
+
+    #[test]
+    fn resolves_user_capabilities() {
+        let roles: WpRoles = maybe_unserialize(USER_ROLES).unwrap();
+        let caps: CapabilityList =
+            maybe_unserialize("a:2:{s:6:\"author\";b:1;s:10:\"edit_posts\";b:0;}").unwrap();
+
+        let caps = UserCapabilities::new(caps, &roles);
+
+        assert_eq!(caps.roles, vec![String::from("author")]);
+        assert!(caps.has_role("author"));
+        assert!(caps.has_cap("level_2"));
+        assert!(caps.has_cap("author"));
+        // Denied for the user although granted by the role
+        assert!(!caps.has_cap("edit_posts"));
+        assert!(!caps.has_cap("manage_options"));
+    }
+
+    #[test]
+    fn keeps_role_order() {
+        let roles: WpRoles = maybe_unserialize(USER_ROLES).unwrap();
+        let caps: CapabilityList =
+            maybe_unserialize("a:2:{s:13:\"administrator\";b:1;s:6:\"author\";s:1:\"1\";}")
+                .unwrap();
+
+        let caps = UserCapabilities::new(caps, &roles);
+
+        assert_eq!(caps.roles, vec!["administrator", "author"]);
+        assert!(caps.has_cap("manage_options"));
+    }
+}
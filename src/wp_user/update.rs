@@ -0,0 +1,701 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::*;
+use mysql_common::{prelude::ToValue, Value};
+
+#[cfg(feature = "query_sync")]
+use crate::sql::{get_conn, last_insert_id, traits::Insertable};
+use crate::{
+    query_builder::{implode_to_question_mark, QueryAndValues},
+    sql::table_prefix::get_table_prefix,
+    WpError,
+};
+
+use super::{sanitize_nicename, wp_hash_password, WpUser};
+
+/// Post types deleted along with their author when the posts are not reassigned,
+/// i.e. the built in post types registered with `delete_with_user`.
+pub const DELETE_WITH_USER_POST_TYPES: [&str; 4] = ["post", "page", "attachment", "revision"];
+
+/// Columns written on update, in the same order as the insert params without the ID.
+const UPDATE_COLUMNS: [&str; 9] = [
+    "user_login",
+    "user_pass",
+    "user_nicename",
+    "user_email",
+    "user_url",
+    "user_registered",
+    "user_activation_key",
+    "user_status",
+    "display_name",
+];
+
+/// A plain text password waiting to be hashed, hidden from debug output.
+#[derive(Clone, Default)]
+pub struct PendingPassword(Option<String>);
+
+impl From<&str> for PendingPassword {
+    fn from(password: &str) -> Self {
+        Self(Some(String::from(password)))
+    }
+}
+
+impl std::fmt::Debug for PendingPassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("PendingPassword(..)"),
+            None => f.write_str("PendingPassword(None)"),
+        }
+    }
+}
+
+/// Statement inserting a user, with the values of `From<WpUser> for Params` as placeholders.
+fn insert_query() -> String {
+    format!(
+        "INSERT INTO `{}users` (
+            /* For new users, ID will be 0 so MySQL will create an ID for us */
+            `ID`,
+            `user_login`,
+            `user_pass`,
+            `user_nicename`,
+            `user_email`,
+            `user_url`,
+            `user_registered`,
+            `user_activation_key`,
+            `user_status`,
+            `display_name`
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);",
+        get_table_prefix()
+    )
+}
+
+#[cfg(feature = "query_sync")]
+impl WpUser {
+    fn get_stmt(conn: &mut impl Queryable) -> Result<mysql::Statement, mysql::Error> {
+        conn.prep(insert_query())
+    }
+
+    /// Inserts the user like `wp_insert_user`, returning the new ID.
+    ///
+    /// Fails if the login is empty or the login or email is used by another user.
+    /// The password set with `set_user_pass` is hashed, otherwise `user_pass` is written as is.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use wp_query_rs::WpUser;
+    ///
+    /// let mut user = WpUser::new("john", "john@example.com");
+    /// user.set_user_pass("secret");
+    ///
+    /// let user_id = user.insert().expect("InsertFailed");
+    /// ```
+    pub fn insert(self) -> Result<u64, WpError> {
+        <Self as Insertable>::insert(self)
+    }
+
+    /// Writes all fields of the user to the row with its ID, like `wp_update_user`.
+    ///
+    /// Fails if the login is empty or the login or email is used by another user.
+    /// A password set with `set_user_pass` is hashed and clears the activation key.
+    pub fn update(&mut self) -> Result<(), WpError> {
+        let mut conn = get_conn()?;
+
+        let QueryAndValues(q, values) = existing_users_query(std::slice::from_ref(self));
+        let existing: Vec<(u64, String, String)> = conn.exec(q, values)?;
+        check_unique(std::slice::from_ref(self), &existing)?;
+
+        self.hash_new_password()?;
+        let QueryAndValues(q, values) = self.update_query();
+
+        Ok(conn.exec_drop(q, values)?)
+    }
+
+    /// Permanently deletes the user and their meta like `wp_delete_user`.
+    ///
+    /// With `reassign`, all posts of the user are given to that user. Otherwise posts of the
+    /// `DELETE_WITH_USER_POST_TYPES` are deleted with their meta, term relationships and comments,
+    /// and posts of other types are kept. Links of the link manager are not changed.
+    ///
+    /// All rows are changed in a single transaction.
+    pub fn delete(self, reassign: Option<u64>) -> Result<(), WpError> {
+        let statements = self.delete_queries(reassign)?;
+
+        let mut conn = get_conn()?;
+        let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
+        for QueryAndValues(q, values) in statements {
+            tx.exec_drop(q, values)?;
+        }
+
+        Ok(tx.commit()?)
+    }
+}
+
+impl WpUser {
+    /// Hashes the password set with `set_user_pass`, clearing the activation key like `wp_set_password`.
+    /// Also fills in the nicename and display name if they are empty.
+    fn hash_new_password(&mut self) -> Result<(), WpError> {
+        if let Some(password) = self.new_password.0.take() {
+            self.user_pass = wp_hash_password(&password)?;
+            self._user_activation_key.clear();
+        }
+        if self.user_nicename.is_empty() {
+            self.user_nicename = sanitize_nicename(&self.user_login);
+        }
+        if self.display_name.is_empty() {
+            self.display_name = self.user_login.clone();
+        }
+
+        Ok(())
+    }
+
+    fn update_query(&self) -> QueryAndValues {
+        let prefix = get_table_prefix();
+        let columns = UPDATE_COLUMNS
+            .iter()
+            .map(|col| format!("`{col}` = ?"))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let params: mysql_common::params::Params = self.clone().into();
+        let mut values = match params {
+            mysql_common::params::Params::Positional(values) => values,
+            _ => vec![],
+        };
+        // The ID is the first insert param but the last for an update
+        let id = values.remove(0);
+        values.push(id);
+
+        QueryAndValues(
+            format!("UPDATE `{prefix}users` SET {columns} WHERE `ID` = ?;"),
+            values,
+        )
+    }
+
+    fn delete_queries(&self, reassign: Option<u64>) -> Result<Vec<QueryAndValues>, WpError> {
+        let prefix = get_table_prefix();
+        let id = Value::UInt(self.id);
+
+        let mut queries = match reassign {
+            Some(reassign) if reassign == self.id => {
+                return Err(WpError::InvalidParams(String::from(
+                    "Cannot reassign the posts of a user to the user being deleted.",
+                )));
+            }
+            Some(reassign) => vec![QueryAndValues(
+                format!("UPDATE {prefix}posts SET post_author = ? WHERE post_author = ?;"),
+                vec![Value::UInt(reassign), id.clone()],
+            )],
+            None => delete_posts_queries(prefix, self.id),
+        };
+
+        queries.push(QueryAndValues(
+            format!("DELETE FROM {prefix}usermeta WHERE user_id = ?;"),
+            vec![id.clone()],
+        ));
+        queries.push(QueryAndValues(
+            format!("DELETE FROM {prefix}users WHERE ID = ?;"),
+            vec![id],
+        ));
+
+        Ok(queries)
+    }
+}
+
+/// Deletes the posts of a user along with their related rows, like `wp_delete_post` for each post.
+fn delete_posts_queries(prefix: &str, user_id: u64) -> Vec<QueryAndValues> {
+    let post_types = implode_to_question_mark(&DELETE_WITH_USER_POST_TYPES);
+    let owned_posts = format!(
+        "SELECT ID FROM {prefix}posts WHERE post_author = ? AND post_type IN ({post_types})"
+    );
+
+    let owned_values = || {
+        let mut values = vec![Value::UInt(user_id)];
+        values.extend(DELETE_WITH_USER_POST_TYPES.iter().map(|t| t.to_value()));
+        values
+    };
+
+    vec![
+        // Children of the same post type are moved to the parent of the deleted post
+        QueryAndValues(
+            format!(
+                "UPDATE {prefix}posts AS child JOIN {prefix}posts AS parent ON child.post_parent = parent.ID
+                SET child.post_parent = parent.post_parent
+                WHERE parent.post_author = ? AND parent.post_type IN ({post_types}) AND child.post_type = parent.post_type;"
+            ),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!(
+                "UPDATE {prefix}term_taxonomy AS tt JOIN (
+                    SELECT term_taxonomy_id, COUNT(*) AS removed FROM {prefix}term_relationships
+                    WHERE object_id IN ({owned_posts}) GROUP BY term_taxonomy_id
+                ) AS r ON r.term_taxonomy_id = tt.term_taxonomy_id
+                SET tt.count = IF(tt.count > r.removed, tt.count - r.removed, 0);"
+            ),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!("DELETE FROM {prefix}term_relationships WHERE object_id IN ({owned_posts});"),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!(
+                "DELETE FROM {prefix}commentmeta WHERE comment_id IN (
+                    SELECT comment_ID FROM {prefix}comments WHERE comment_post_ID IN ({owned_posts})
+                );"
+            ),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!("DELETE FROM {prefix}comments WHERE comment_post_ID IN ({owned_posts});"),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!("DELETE FROM {prefix}postmeta WHERE post_id IN ({owned_posts});"),
+            owned_values(),
+        ),
+        QueryAndValues(
+            format!(
+                "DELETE FROM {prefix}posts WHERE post_author = ? AND post_type IN ({post_types});"
+            ),
+            owned_values(),
+        ),
+    ]
+}
+
+/// Selects the ID, login and email of users which have one of the logins or emails of the users given.
+fn existing_users_query(users: &[WpUser]) -> QueryAndValues {
+    let logins: Vec<&str> = users.iter().map(|user| user.user_login.as_str()).collect();
+    let emails: Vec<&str> = users
+        .iter()
+        .map(|user| user.user_email.as_str())
+        .filter(|email| !email.is_empty())
+        .collect();
+
+    let mut q = format!(
+        "SELECT ID, user_login, user_email FROM {}users WHERE user_login IN ({})",
+        get_table_prefix(),
+        implode_to_question_mark(&logins)
+    );
+    if !emails.is_empty() {
+        q.push_str(&format!(
+            " OR user_email IN ({})",
+            implode_to_question_mark(&emails)
+        ));
+    }
+    q.push(';');
+
+    let values = logins.into_iter().chain(emails).map(Value::from).collect();
+
+    QueryAndValues(q, values)
+}
+
+/// Checks that the users have a login, and that no two users share a login or email,
+/// ignoring the existing row of each user itself. Logins and emails are compared case insensitively like MySQL.
+fn check_unique(users: &[WpUser], existing: &[(u64, String, String)]) -> Result<(), WpError> {
+    let mut others: Vec<(u64, &str, &str)> = existing
+        .iter()
+        .map(|(id, login, email)| (*id, login.as_str(), email.as_str()))
+        .collect();
+
+    for user in users {
+        if user.user_login.trim().is_empty() {
+            return Err(WpError::InvalidParams(String::from(
+                "Cannot create a user with an empty login name.",
+            )));
+        }
+
+        for (id, login, email) in others.iter() {
+            if user.id != 0 && user.id == *id {
+                continue;
+            }
+            if login.eq_ignore_ascii_case(&user.user_login) {
+                return Err(WpError::InvalidParams(format!(
+                    "Sorry, that username already exists: {}",
+                    user.user_login
+                )));
+            }
+            if !user.user_email.is_empty() && email.eq_ignore_ascii_case(&user.user_email) {
+                return Err(WpError::InvalidParams(format!(
+                    "Sorry, that email address is already used: {}",
+                    user.user_email
+                )));
+            }
+        }
+
+        // Later users of a batch must not reuse the login or email of this one
+        others.push((user.id, &user.user_login, &user.user_email));
+    }
+
+    Ok(())
+}
+
+impl From<WpUser> for mysql_common::params::Params {
+    fn from(user: WpUser) -> Self {
+        mysql_common::params::Params::Positional(vec![
+            user.id.to_value(),
+            user.user_login.to_value(),
+            user.user_pass.to_value(),
+            user.user_nicename.to_value(),
+            user.user_email.to_value(),
+            user.user_url.to_value(),
+            user.user_registered.to_value(),
+            user._user_activation_key.to_value(),
+            user._user_status.to_value(),
+            user.display_name.to_value(),
+        ])
+    }
+}
+
+#[cfg(feature = "query_sync")]
+impl Insertable for WpUser {
+    fn batch(values: impl IntoIterator<Item = Self>) -> Result<(), WpError> {
+        let mut users: Vec<Self> = values.into_iter().collect();
+        if users.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = get_conn()?;
+
+        let QueryAndValues(q, values) = existing_users_query(&users);
+        let existing: Vec<(u64, String, String)> = conn.exec(q, values)?;
+        check_unique(&users, &existing)?;
+
+        for user in users.iter_mut() {
+            user.hash_new_password()?;
+        }
+
+        let stmt = Self::get_stmt(&mut conn)?;
+
+        conn.exec_batch(
+            stmt,
+            users
+                .into_iter()
+                .map(|user| -> mysql_common::params::Params { user.into() }),
+        )?;
+
+        Ok(())
+    }
+
+    fn insert(mut self) -> Result<u64, WpError> {
+        let mut conn = get_conn()?;
+
+        let QueryAndValues(q, values) = existing_users_query(std::slice::from_ref(&self));
+        let existing: Vec<(u64, String, String)> = conn.exec(q, values)?;
+        check_unique(std::slice::from_ref(&self), &existing)?;
+
+        self.hash_new_password()?;
+
+        let stmt = Self::get_stmt(&mut conn)?;
+
+        conn.exec_drop(stmt, self)?;
+
+        let user_id = last_insert_id(conn.exec_first("SELECT LAST_INSERT_ID();", ())?)?;
+
+        Ok(user_id)
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::{future::Future, pin::Pin};
+
+    use mysql_async::prelude::*;
+
+    use crate::{
+        query_builder::QueryAndValues,
+        sql::{
+            last_insert_id, r#async::get_conn, table_prefix::get_table_prefix,
+            traits::r#async::Insertable,
+        },
+        wp_options::r#async::get_option,
+        wp_user::{
+            query_builder::set_password_query,
+            r#async::{get_user_meta, WpUserExt},
+            roles::{CapabilityList, UserCapabilities, WpRoles},
+            WpUser,
+        },
+        WpError,
+    };
+
+    use super::{check_unique, existing_users_query, insert_query, wp_hash_password};
+
+    impl Insertable for WpUser {
+        fn batch<T>(values: T) -> Pin<Box<dyn Future<Output = Result<(), WpError>>>>
+        where
+            T: IntoIterator<Item = Self> + Send + 'static,
+            T::IntoIter: Send,
+            Self: Sized,
+        {
+            let fut = async move {
+                let mut users: Vec<Self> = values.into_iter().collect();
+                if users.is_empty() {
+                    return Ok(());
+                }
+
+                let mut conn = get_conn().await?;
+
+                let QueryAndValues(q, values) = existing_users_query(&users);
+                let existing: Vec<(u64, String, String)> = conn.exec(q, values).await?;
+                check_unique(&users, &existing)?;
+
+                for user in users.iter_mut() {
+                    user.hash_new_password()?;
+                }
+
+                let stmt = conn.prep(insert_query()).await?;
+
+                conn.exec_batch(
+                    stmt,
+                    users
+                        .into_iter()
+                        .map(|user| -> mysql_common::params::Params { user.into() }),
+                )
+                .await?;
+
+                Ok(())
+            };
+
+            Box::pin(fut)
+        }
+
+        fn insert(mut self) -> Pin<Box<dyn Future<Output = Result<u64, WpError>>>> {
+            let fut = async move {
+                let mut conn = get_conn().await?;
+
+                let QueryAndValues(q, values) = existing_users_query(std::slice::from_ref(&self));
+                let existing: Vec<(u64, String, String)> = conn.exec(q, values).await?;
+                check_unique(std::slice::from_ref(&self), &existing)?;
+
+                self.hash_new_password()?;
+
+                let stmt = conn.prep(insert_query()).await?;
+
+                conn.exec_drop(stmt, self).await?;
+
+                let user_id =
+                    last_insert_id(conn.exec_first("SELECT LAST_INSERT_ID();", ()).await?)?;
+
+                Ok(user_id)
+            };
+
+            Box::pin(fut)
+        }
+    }
+
+    impl WpUserExt for WpUser {
+        async fn set_password(&mut self, password: &str) -> Result<(), WpError> {
+            let user_pass = wp_hash_password(password)?;
+            let mut conn = get_conn().await?;
+
+            let QueryAndValues(q, values) = set_password_query(self.id, &user_pass);
+            conn.exec_drop(q, values).await?;
+
+            self.user_pass = user_pass;
+            self._user_activation_key.clear();
+
+            Ok(())
+        }
+
+        async fn capabilities(&self) -> Result<UserCapabilities, WpError> {
+            let prefix = get_table_prefix();
+
+            let caps: Option<CapabilityList> =
+                get_user_meta(self.id, &format!("{prefix}capabilities")).await?;
+            let wp_roles: Option<WpRoles> = get_option(&WpRoles::option_name(prefix)).await?;
+
+            Ok(UserCapabilities::new(
+                caps.unwrap_or_default(),
+                &wp_roles.unwrap_or_default(),
+            ))
+        }
+
+        async fn update(&mut self) -> Result<(), WpError> {
+            let mut conn = get_conn().await?;
+
+            let QueryAndValues(q, values) = existing_users_query(std::slice::from_ref(self));
+            let existing: Vec<(u64, String, String)> = conn.exec(q, values).await?;
+            check_unique(std::slice::from_ref(self), &existing)?;
+
+            self.hash_new_password()?;
+            let QueryAndValues(q, values) = self.update_query();
+
+            Ok(conn.exec_drop(q, values).await?)
+        }
+
+        async fn delete(self, reassign: Option<u64>) -> Result<(), WpError> {
+            let statements = self.delete_queries(reassign)?;
+
+            let mut conn = get_conn().await?;
+            let mut tx = conn
+                .start_transaction(mysql_async::TxOpts::default())
+                .await?;
+            for QueryAndValues(q, values) in statements {
+                tx.exec_drop(q, values).await?;
+            }
+
+            Ok(tx.commit().await?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(id: u64, login: &str, email: &str) -> (u64, String, String) {
+        (id, String::from(login), String::from(email))
+    }
+
+    #[test]
+    fn can_convert_user_to_params() {
+        let user = WpUser::new("john", "john@example.com");
+
+        let params: mysql_common::params::Params = user.into();
+        match params {
+            mysql_common::params::Params::Positional(data) => {
+                assert_eq!(data.len(), 10);
+                assert_eq!(data[0], Value::UInt(0));
+                assert_eq!(data[1], Value::Bytes(b"john".to_vec()));
+                assert_eq!(data[4], Value::Bytes(b"john@example.com".to_vec()));
+            }
+            _ => panic!("Not positional"),
+        }
+    }
+
+    #[test]
+    fn hashes_new_password() {
+        let mut user = WpUser::new("john", "john@example.com");
+        user.set_user_pass("secret");
+        user._user_activation_key = String::from("key");
+
+        user.hash_new_password().unwrap();
+
+        assert!(user.user_pass.starts_with("$wp$2y$10$"));
+        assert!(user.check_password("secret"));
+        assert!(user._user_activation_key.is_empty());
+        assert!(!format!("{user:?}").contains("secret"));
+    }
+
+    #[test]
+    fn update_query_sets_fields_by_id() {
+        let mut user = WpUser::new("john", "john@example.com");
+        user.id = 5;
+
+        let QueryAndValues(q, values) = user.update_query();
+
+        assert!(q.starts_with("UPDATE `wp_users` SET `user_login` = ?, `user_pass` = ?"));
+        assert!(q.ends_with("`display_name` = ? WHERE `ID` = ?;"));
+        assert_eq!(values.len(), 10);
+        assert_eq!(values.last(), Some(&Value::UInt(5)));
+    }
+
+    #[test]
+    fn finds_users_with_same_login_or_email() {
+        let users = [
+            WpUser::new("john", "john@example.com"),
+            WpUser::new("jane", ""),
+        ];
+
+        let QueryAndValues(q, values) = existing_users_query(&users);
+
+        assert_eq!(
+            q,
+            "SELECT ID, user_login, user_email FROM wp_users WHERE user_login IN (?,?) OR user_email IN (?);"
+        );
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    fn rejects_duplicate_login_and_email() {
+        let user = WpUser::new("John", "john@example.com");
+
+        let err = check_unique(
+            std::slice::from_ref(&user),
+            &[existing(2, "john", "other@example.com")],
+        )
+        .unwrap_err();
+        assert!(matches!(err, WpError::InvalidParams(_)));
+        assert!(err.to_string().contains("username"));
+
+        let err = check_unique(
+            std::slice::from_ref(&user),
+            &[existing(2, "other", "JOHN@example.com")],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("email"));
+
+        let empty = WpUser::new(" ", "");
+        assert!(matches!(
+            check_unique(&[empty], &[]),
+            Err(WpError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn allows_user_to_keep_own_login() {
+        let mut user = WpUser::new("john", "john@example.com");
+        user.id = 2;
+
+        assert!(check_unique(
+            std::slice::from_ref(&user),
+            &[existing(2, "john", "john@example.com")]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_duplicates_within_batch() {
+        let users = [
+            WpUser::new("john", "john@example.com"),
+            WpUser::new("jane", "john@example.com"),
+        ];
+
+        assert!(check_unique(&users, &[]).is_err());
+    }
+
+    #[test]
+    fn delete_reassigns_posts() {
+        let mut user = WpUser::new("john", "john@example.com");
+        user.id = 4;
+
+        let queries = user.delete_queries(Some(1)).unwrap();
+        let statements = [
+            "UPDATE wp_posts SET post_author = ? WHERE post_author = ?;",
+            "DELETE FROM wp_usermeta WHERE user_id = ?;",
+            "DELETE FROM wp_users WHERE ID = ?;",
+        ];
+
+        assert_eq!(queries.len(), statements.len());
+        for (QueryAndValues(q, _), statement) in queries.iter().zip(statements) {
+            assert_eq!(q, statement);
+        }
+        assert_eq!(queries[0].1, vec![Value::UInt(1), Value::UInt(4)]);
+        assert!(user.delete_queries(Some(4)).is_err());
+    }
+
+    #[test]
+    fn delete_without_reassign_cascades_to_posts() {
+        let mut user = WpUser::new("john", "john@example.com");
+        user.id = 4;
+
+        let queries = user.delete_queries(None).unwrap();
+        let statements = [
+            "UPDATE wp_posts AS child",
+            "UPDATE wp_term_taxonomy AS tt",
+            "DELETE FROM wp_term_relationships",
+            "DELETE FROM wp_commentmeta",
+            "DELETE FROM wp_comments",
+            "DELETE FROM wp_postmeta",
+            "DELETE FROM wp_posts",
+            "DELETE FROM wp_usermeta",
+            "DELETE FROM wp_users",
+        ];
+
+        assert_eq!(queries.len(), statements.len());
+        for (QueryAndValues(q, values), statement) in queries.iter().zip(statements) {
+            assert!(q.starts_with(statement));
+            assert_eq!(q.matches('?').count(), values.len());
+            assert_eq!(values[0], Value::UInt(4));
+        }
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(feature = "query_sync")]
+use mysql::prelude::Queryable;
+
+#[cfg(feature = "query_sync")]
+use crate::{query_builder::QueryAndValues, sql::get_conn, WpError};
+
+use super::WpUser;
+#[cfg(feature = "query_sync")]
+use super::{arg_builder::WpUserQueryArgs, query_builder::UserQueryBuilder};
+
+#[derive(Debug)]
+pub struct WpUserQuery {
+    pub users: Vec<WpUser>,
+    /// Number of users matching the query ignoring pagination. None if `count_total` was not set.
+    pub total_users: Option<u64>,
+}
+
+impl WpUserQuery {
+    /// Queries the WordPress Database for users using the global connection pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use wp_query_rs::{WpUserArgBuilder, WpUserQuery};
+    ///
+    /// let mut args = WpUserArgBuilder::new();
+    /// args.role__in = Some(vec![String::from("author")]);
+    ///
+    /// let user_q = WpUserQuery::new(args);
+    /// ```
+    ///
+    /// # Errors
+    /// Will return an error if there is an error in the mysql query.
+    #[cfg(feature = "query_sync")]
+    pub fn new<T>(args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpUserQueryArgs>,
+    {
+        let mut conn = get_conn()?;
+
+        Self::query(&mut conn, args)
+    }
+
+    /// Queries the WordPress database for users with a mysql connection.
+    ///
+    /// # Errors
+    /// When an error occurs in the SQL query.
+    #[cfg(feature = "query_sync")]
+    pub fn with_connection<T>(conn: &mut impl Queryable, args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpUserQueryArgs>,
+    {
+        Self::query(conn, args)
+    }
+
+    #[cfg(feature = "query_sync")]
+    fn query<T>(conn: &mut impl Queryable, args: T) -> Result<Self, WpError>
+    where
+        T: Into<WpUserQueryArgs>,
+    {
+        let (QueryAndValues(q, values), count) = UserQueryBuilder::new(args.into()).query()?;
+
+        let stmt = conn.prep(q)?;
+        let users: Vec<WpUser> = conn.exec(stmt, values)?;
+
+        let total_users = match count {
+            Some(QueryAndValues(q, values)) => conn.exec_first(q, values)?,
+            None => None,
+        };
+
+        Ok(Self { users, total_users })
+    }
+
+    /// Number of users found on this page.
+    pub fn user_count(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Consumes the query returning the users found.
+    pub fn to_vec(self) -> Vec<WpUser> {
+        self.users
+    }
+}
+
+#[cfg(feature = "query_async")]
+pub mod r#async {
+    use std::ops::Deref;
+
+    use mysql_async::prelude::*;
+
+    use crate::{query_builder::QueryAndValues, sql::r#async::get_conn, WpError};
+
+    use super::super::{arg_builder::WpUserQueryArgs, query_builder::UserQueryBuilder, WpUser};
+
+    /// Queries users like [`crate::WpUserQuery`], dereferencing to its results.
+    #[derive(Debug)]
+    pub struct WpUserQuery(super::WpUserQuery);
+
+    impl WpUserQuery {
+        /// Queries the WordPress Database for users using the global connection pool.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// use wp_query_rs::{WpUserArgBuilder, WpUserQuery};
+        ///
+        /// let mut args = WpUserArgBuilder::new();
+        /// args.role__in = Some(vec![String::from("author")]);
+        ///
+        /// let user_q = WpUserQuery::new(args);
+        /// ```
+        ///
+        /// # Errors
+        /// Will return an error if there is an error in the mysql query.
+        pub async fn new<T>(args: T) -> Result<Self, WpError>
+        where
+            T: Into<WpUserQueryArgs>,
+        {
+            let mut conn = get_conn().await?;
+
+            Self::query(&mut conn, args).await
+        }
+
+        /// Queries the WordPress database for users with a mysql connection.
+        ///
+        /// # Errors
+        /// When an error occurs in the SQL query.
+        pub async fn with_connection<T>(
+            conn: &mut mysql_async::Conn,
+            args: T,
+        ) -> Result<Self, WpError>
+        where
+            T: Into<WpUserQueryArgs>,
+        {
+            Self::query(conn, args).await
+        }
+
+        async fn query<T>(conn: &mut mysql_async::Conn, args: T) -> Result<Self, WpError>
+        where
+            T: Into<WpUserQueryArgs>,
+        {
+            let (QueryAndValues(q, values), count) = UserQueryBuilder::new(args.into()).query()?;
+
+            let stmt = conn.prep(q).await?;
+            let users: Vec<WpUser> = conn.exec(stmt, values).await?;
+
+            let total_users = match count {
+                Some(QueryAndValues(q, values)) => conn.exec_first(q, values).await?,
+                None => None,
+            };
+
+            Ok(Self(super::WpUserQuery { users, total_users }))
+        }
+
+        /// Consumes the wrapper returning the results of the query.
+        pub fn into_inner(self) -> super::WpUserQuery {
+            self.0
+        }
+    }
+
+    impl Deref for WpUserQuery {
+        type Target = super::WpUserQuery;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl From<WpUserQuery> for super::WpUserQuery {
+        fn from(query: WpUserQuery) -> Self {
+            query.0
+        }
+    }
+}
@@ -3,9 +3,33 @@ use wp_query_rs::*;
 #[cfg(feature = "query_async")]
 #[tokio::test]
 async fn can_query() {
+    use wp_query_rs::r#async::WpQuery;
+
     let params = ParamBuilder::new();
 
-    let posts = WP_Query::new(params).await.expect("SqlFailed");
+    let posts = WpQuery::new(params).await.expect("SqlFailed");
 
     assert_eq!(posts.post_count(), 10);
 }
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_stream_by_keyset() {
+    use futures_util::TryStreamExt;
+    use wp_query_rs::r#async::WpQuery;
+
+    let found_posts = WpQuery::new(ParamBuilder::new())
+        .await
+        .expect("SqlFailed")
+        .found_posts();
+
+    let posts: Vec<WP_Post> = WpQuery::stream_keyset(ParamBuilder::new(), 3)
+        .await
+        .expect("ConnFailed")
+        .try_collect()
+        .await
+        .expect("BatchFailed");
+
+    assert_eq!(posts.len(), found_posts);
+    assert!(posts.windows(2).all(|pair| pair[0].ID < pair[1].ID));
+}
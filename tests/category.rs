@@ -32,7 +32,7 @@ fn tag_id() {
 fn category_and_tag() {
     let params = ParamBuilder::new().tag("tag-a").cat(1);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -42,7 +42,7 @@ fn category_name_and_tag() {
         .tag("tag-a")
         .category_name("uncategorized");
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -0,0 +1,168 @@
+use wp_query_rs::*;
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_comments() {
+    let args = WpCommentArgBuilder::new();
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed");
+    assert!(comments.comment_count() > 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_comments_by_post() {
+    let mut args = WpCommentArgBuilder::new();
+    args.post_id = Some(1);
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed").to_vec();
+    assert!(comments.iter().all(|c| c.comment_post_ID == 1));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_comment_ids() {
+    let mut args = WpCommentArgBuilder::new();
+    args.fields = wp_comment::arg_builder::fields::Fields::Ids;
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed");
+    match comments.comments {
+        wp_comment::WpCommentResults::Ids(ids) => assert!(!ids.is_empty()),
+        _ => panic!("Not IDs"),
+    }
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_count_comments() {
+    let mut args = WpCommentArgBuilder::new();
+    args.count = true;
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed");
+    match comments.comments {
+        wp_comment::WpCommentResults::Count(n) => assert!(n > 0),
+        _ => panic!("Not count"),
+    }
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_query_comments_async() {
+    use wp_query_rs::r#async::WpCommentQuery;
+
+    let args = WpCommentArgBuilder::new();
+
+    let comments = WpCommentQuery::new(args).await.expect("SqlFailed");
+    assert!(comments.comment_count() > 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_threaded_comments() {
+    let mut args = WpCommentArgBuilder::new();
+    args.hierarchical = Some(wp_comment::arg_builder::hierarchical::Hierarchy::Threaded(
+        3,
+    ));
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed").to_vec();
+    assert!(comments.iter().all(|c| c.comment_parent == 0));
+    assert!(comments.iter().all(|c| c.has_children_loaded()));
+    assert!(comments
+        .iter()
+        .flat_map(|c| c.children())
+        .all(|c| c.comment_parent != 0));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_flat_comments() {
+    let mut args = WpCommentArgBuilder::new();
+    args.hierarchical = Some(wp_comment::arg_builder::hierarchical::Hierarchy::Flat);
+
+    let comments = WpCommentQuery::new(args).expect("SqlFailed").to_vec();
+    assert!(comments.iter().all(|c| !c.has_children_loaded()));
+}
+
+#[cfg(feature = "query_sync")]
+fn get_comment(
+    comment_id: u64,
+    status: wp_comment::comment_approved::CommentApproved,
+) -> WpComment {
+    let mut args = WpCommentArgBuilder::new();
+    args.comment__in = Some(vec![comment_id]);
+    args.status = Some(status);
+
+    WpCommentQuery::new(args)
+        .expect("SqlFailed")
+        .to_vec()
+        .remove(0)
+}
+
+#[cfg(feature = "query_sync")]
+fn comment_count(post_id: u64) -> u64 {
+    let query = WP_Query::new(ParamBuilder::new().p(post_id)).expect("SqlFailed");
+
+    query.posts[0].comment_count
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_insert_moderate_and_delete_comment() {
+    use wp_comment::comment_approved::CommentApproved;
+
+    let mut post = WP_Post::new(1);
+    post.post_title = String::from("Post to comment on");
+    post.post_status = PostStatus::Publish;
+    let post_id = post.insert().expect("PostInsertFailed");
+
+    let mut comment = WpComment::new(0);
+    comment.comment_post_ID = post_id;
+    comment.comment_content = String::from("An IPv6 comment");
+    comment.comment_author_IP = Some("2001:db8::1".parse().unwrap());
+    let comment_id = comment.insert().expect("InsertFailed");
+    assert_eq!(comment_count(post_id), 1);
+
+    let mut comment = get_comment(comment_id, CommentApproved::Approved);
+    assert_eq!(comment.comment_author_IP, "2001:db8::1".parse().ok());
+
+    comment.hold().expect("HoldFailed");
+    assert_eq!(comment_count(post_id), 0);
+
+    comment.spam().expect("SpamFailed");
+    let spam = get_comment(comment_id, CommentApproved::Spam);
+    assert_eq!(spam.comment_approved, CommentApproved::Spam);
+    let previous: Option<String> =
+        get_comment_meta(comment_id, "_wp_trash_meta_status").expect("MetaFailed");
+    assert_eq!(previous.as_deref(), Some("0"));
+
+    comment.approve().expect("ApproveFailed");
+    assert_eq!(comment_count(post_id), 1);
+
+    update_comment_meta(comment_id, "rating", 5, None).expect("MetaUpdateFailed");
+    let rating: Option<u64> = get_comment_meta(comment_id, "rating").expect("MetaFailed");
+    assert_eq!(rating, Some(5));
+
+    comment.delete().expect("DeleteFailed");
+    assert_eq!(comment_count(post_id), 0);
+    assert!(!delete_comment_meta(comment_id, "rating", None).expect("MetaDeleteFailed"));
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_insert_and_trash_comment_async() {
+    use wp_query_rs::r#async::{Insertable, WpCommentExt};
+
+    let mut comment = WpComment::new(0);
+    comment.comment_post_ID = 1;
+    comment.comment_content = String::from("An async comment");
+
+    let comment_id = Insertable::insert(comment.clone())
+        .await
+        .expect("InsertFailed");
+    comment.comment_ID = comment_id;
+
+    WpCommentExt::trash(&mut comment)
+        .await
+        .expect("TrashFailed");
+    WpCommentExt::delete(comment).await.expect("DeleteFailed");
+}
@@ -5,7 +5,7 @@ use wp_query_rs::*;
 fn year_month_day() {
     let params = ParamBuilder::new().year(2023).monthnum(1).day(1);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -13,7 +13,7 @@ fn year_month_day() {
 fn year_month_day_hour() {
     let params = ParamBuilder::new().year(2023).monthnum(1).day(1).hour(4);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -26,7 +26,7 @@ fn year_month_day_hour_minute() {
         .hour(4)
         .minute(23);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -40,7 +40,7 @@ fn year_month_day_hour_minute_second() {
         .minute(23)
         .second(12);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -61,5 +61,5 @@ fn date_query() {
             .inclusive(true),
     ).orderby(WpOrderBy::Date);
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
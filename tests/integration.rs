@@ -73,3 +73,74 @@ fn paginate() {
     let posts = WP_Query::new(params).expect("SqlFailed");
     assert!(posts.post_count() == 2);
 }
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn found_posts() {
+    let params = ParamBuilder::new().posts_per_page(2);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.found_posts() >= posts.post_count());
+    assert_eq!(posts.max_num_pages(), posts.found_posts().div_ceil(2));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn no_found_rows() {
+    let params = ParamBuilder::new().no_found_rows(true);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert_eq!(posts.found_posts(), 0);
+    assert_eq!(posts.max_num_pages(), 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn explains_posts_query() {
+    use std::env;
+
+    let mut conn = mysql::Conn::new(
+        mysql::OptsBuilder::default()
+            .ip_or_hostname(env::var("WORDPRESS_DB_HOST").ok())
+            .user(env::var("WORDPRESS_DB_USER").ok())
+            .pass(env::var("WORDPRESS_DB_PASSWORD").ok())
+            .db_name(env::var("WORDPRESS_DB_NAME").ok()),
+    )
+    .expect("ConnFailed");
+    let params = ParamBuilder::new().post_type(PostType::Post);
+
+    let plan = WP_Query::explain(&mut conn, params).expect("SqlFailed");
+    assert!(!plan.is_empty());
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn streams_every_post() {
+    let found_posts = WP_Query::new(ParamBuilder::new())
+        .expect("SqlFailed")
+        .found_posts();
+
+    let posts: Vec<WP_Post> = WP_Query::stream(ParamBuilder::new())
+        .expect("SqlFailed")
+        .collect::<Result<_, _>>()
+        .expect("RowFailed");
+
+    assert_eq!(posts.len(), found_posts);
+    ensure_no_duplicate(&posts);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn streams_posts_by_keyset() {
+    let found_posts = WP_Query::new(ParamBuilder::new())
+        .expect("SqlFailed")
+        .found_posts();
+
+    let posts: Vec<WP_Post> = WP_Query::stream_keyset(ParamBuilder::new(), 3)
+        .expect("SqlFailed")
+        .collect::<Result<_, _>>()
+        .expect("BatchFailed");
+
+    assert_eq!(posts.len(), found_posts);
+    assert!(posts.windows(2).all(|pair| pair[0].ID < pair[1].ID));
+}
@@ -14,13 +14,15 @@ fn meta_key() {
 
 #[cfg(feature = "query_async")]
 #[tokio::test]
-async fn meta_key() {
+async fn meta_key_async() {
+    use wp_query_rs::r#async::WpQuery;
+
     let params = ParamBuilder::new()
         .post_type_all()
         .post_status(PostStatus::Any)
         .meta_key("my_inserted_meta");
 
-    let posts = WP_Query::new(params).await.expect("SqlFailed");
+    let posts = WpQuery::new(params).await.expect("SqlFailed");
     assert!(posts.post_count() > 0);
 }
 
@@ -53,51 +55,35 @@ fn meta_queries() {
             MetaRelation::Or,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_inserted_meta"),
-                value: String::from("0"),
-                compare: SqlSearchOperators::Equals,
-            },
+            MetaQuery::new("my_inserted_meta", "0", SqlSearchOperators::Equals),
             MetaRelation::Or,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_inserted_meta"),
-                value: String::from("2"),
-                compare: SqlSearchOperators::Like,
-            },
+            MetaQuery::new("my_inserted_meta", "2", SqlSearchOperators::Like),
             MetaRelation::Or,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_custom_rs_bulk_meta"),
-                value: String::from("1"),
-                compare: SqlSearchOperators::GreaterThan,
-            },
+            MetaQuery::new(
+                "my_custom_rs_bulk_meta",
+                "1",
+                SqlSearchOperators::GreaterThan,
+            ),
             MetaRelation::Or,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_custom_rs_bulk_meta"),
-                value: String::from("2"),
-                compare: SqlSearchOperators::LessThanOrEqualTo,
-            },
+            MetaQuery::new(
+                "my_custom_rs_bulk_meta",
+                "2",
+                SqlSearchOperators::LessThanOrEqualTo,
+            ),
             MetaRelation::And,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_custom_rs_bulk_meta"),
-                value: String::from("3"),
-                compare: SqlSearchOperators::NotLike,
-            },
+            MetaQuery::new("my_custom_rs_bulk_meta", "3", SqlSearchOperators::NotLike),
             MetaRelation::And,
         )
         .meta_query(
-            MetaQuery {
-                key: String::from("my_inserted_meta"),
-                value: String::from("2"),
-                compare: SqlSearchOperators::NotEquals,
-            },
+            MetaQuery::new("my_inserted_meta", "2", SqlSearchOperators::NotEquals),
             MetaRelation::And,
         );
 
@@ -105,6 +91,58 @@ fn meta_queries() {
     assert!(posts.post_count() > 0);
 }
 
+#[cfg(feature = "query_sync")]
+#[test]
+fn meta_query_tree() {
+    // Matches any of the inserted meta, or bulk meta in a range
+    let tree = MetaQueryTree::group(MetaRelation::Or)
+        .push(MetaQuery::new(
+            "my_inserted_meta",
+            "42",
+            SqlSearchOperators::Equals,
+        ))
+        .push(MetaQuery::new(
+            "my_inserted_meta",
+            "0",
+            SqlSearchOperators::Equals,
+        ))
+        .push(MetaQuery::new(
+            "my_inserted_meta",
+            "2",
+            SqlSearchOperators::Like,
+        ))
+        .push(
+            MetaQueryTree::group(MetaRelation::And)
+                .push(MetaQuery::new(
+                    "my_custom_rs_bulk_meta",
+                    "1",
+                    SqlSearchOperators::GreaterThan,
+                ))
+                .push(MetaQuery::new(
+                    "my_custom_rs_bulk_meta",
+                    "2",
+                    SqlSearchOperators::LessThanOrEqualTo,
+                ))
+                .push(MetaQuery::new(
+                    "my_custom_rs_bulk_meta",
+                    "3",
+                    SqlSearchOperators::NotLike,
+                ))
+                .push(MetaQuery::new(
+                    "my_inserted_meta",
+                    "2",
+                    SqlSearchOperators::NotEquals,
+                )),
+        );
+    let params = ParamBuilder::new()
+        .post_type_all()
+        .post_status(PostStatus::Any)
+        .meta_query_tree(tree);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
+
 #[cfg(feature = "query_sync")]
 #[test]
 fn can_query_exists() {
@@ -134,3 +172,23 @@ fn can_query_not_exists() {
     let posts = WP_Query::new(params).expect("SqlFailed");
     assert!(posts.post_count() > 0);
 }
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_numeric_meta() {
+    let params = ParamBuilder::new()
+        .post_type_all()
+        .post_status(PostStatus::Any)
+        .meta_query(
+            MetaQuery::with_values(
+                "my_inserted_meta",
+                vec![1, 100],
+                SqlSearchOperators::Between,
+            )
+            .cast(CastType::Numeric),
+            MetaRelation::And,
+        );
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
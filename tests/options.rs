@@ -0,0 +1,88 @@
+#[cfg(feature = "query_sync")]
+use std::collections::HashMap;
+
+#[cfg(feature = "query_sync")]
+use wp_query_rs::*;
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_add_update_and_delete_option() {
+    let name = "wp_query_rs_test_option";
+    delete_option(name).expect("DeleteFailed");
+
+    assert!(add_option(name, 42, false).expect("AddFailed"));
+    assert!(!add_option(name, 43, false).expect("AddFailed"));
+    assert_eq!(get_option::<u64>(name).expect("GetFailed"), Some(42));
+
+    let colors = vec!["red", "blue"];
+    assert!(update_option(name, &colors).expect("UpdateFailed"));
+    assert!(!update_option(name, &colors).expect("UpdateFailed"));
+    assert_eq!(
+        get_option::<Vec<String>>(name).expect("GetFailed"),
+        Some(vec![String::from("red"), String::from("blue")])
+    );
+
+    assert!(delete_option(name).expect("DeleteFailed"));
+    assert_eq!(get_option::<String>(name).expect("GetFailed"), None);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_set_and_get_transient() {
+    let mut value = HashMap::new();
+    value.insert(String::from("count"), 3u64);
+
+    set_transient("wp_query_rs_test_transient", &value, 60).expect("SetFailed");
+
+    let cached: Option<HashMap<String, u64>> =
+        get_transient("wp_query_rs_test_transient").expect("GetFailed");
+    assert_eq!(cached, Some(value));
+
+    assert!(delete_transient("wp_query_rs_test_transient").expect("DeleteFailed"));
+    assert_eq!(
+        get_transient::<String>("wp_query_rs_test_transient").expect("GetFailed"),
+        None
+    );
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_load_autoloaded_options() {
+    let options = WpOptions::load().expect("LoadFailed");
+
+    assert!(options.blogname().is_some());
+    assert!(options.home().is_some());
+    assert!(options.posts_per_page().is_some());
+    assert_eq!(
+        get_option::<String>("blogname")
+            .expect("GetFailed")
+            .as_deref(),
+        options.blogname()
+    );
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_add_and_delete_option() {
+    use wp_query_rs::r#async::{add_option, delete_option, get_option};
+
+    let name = "wp_query_rs_test_async_option";
+    delete_option(name).await.expect("DeleteFailed");
+
+    assert!(add_option(name, "value", true).await.expect("AddFailed"));
+    assert_eq!(
+        get_option::<String>(name).await.expect("GetFailed"),
+        Some(String::from("value"))
+    );
+    assert!(delete_option(name).await.expect("DeleteFailed"));
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_load_autoloaded_options_async() {
+    let options = wp_query_rs::r#async::wp_load_alloptions()
+        .await
+        .expect("LoadFailed");
+
+    assert!(options.blogname().is_some());
+}
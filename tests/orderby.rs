@@ -54,3 +54,65 @@ fn orderby_title() {
     let posts = WP_Query::new(params).expect("SqlFailed");
     assert_eq!(posts.post_count(), 10);
 }
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn orderby_multiple_keys() {
+    let params = ParamBuilder::new()
+        .add_orderby(WpOrderBy::MenuOrder, SqlOrder::Asc)
+        .add_orderby(WpOrderBy::Date, SqlOrder::Desc);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert_eq!(posts.post_count(), 10);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn orderby_post_in_keeps_given_order() {
+    let ids: Vec<u64> = WP_Query::new(ParamBuilder::new().posts_per_page(3))
+        .expect("SqlFailed")
+        .posts
+        .iter()
+        .map(|p| p.ID)
+        .rev()
+        .collect();
+
+    let params = ids
+        .iter()
+        .fold(ParamBuilder::new(), |params, id| params.post__in(*id))
+        .orderby(WpOrderBy::PostIn);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    let ordered: Vec<u64> = posts.posts.iter().map(|p| p.ID).collect();
+    assert_eq!(ordered, ids);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn orderby_named_meta_clause() {
+    let params = ParamBuilder::new()
+        .post_type_all()
+        .post_status(PostStatus::Any)
+        .meta_query(
+            MetaQuery::new("my_inserted_meta", "", SqlSearchOperators::Exists)
+                .cast(CastType::Numeric)
+                .name("inserted_clause"),
+            MetaRelation::And,
+        )
+        .add_orderby(
+            WpOrderBy::MetaClause(String::from("inserted_clause")),
+            SqlOrder::Asc,
+        );
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn orderby_rand() {
+    let params = ParamBuilder::new().orderby(WpOrderBy::Rand);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert_eq!(posts.post_count(), 10);
+}
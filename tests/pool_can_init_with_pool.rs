@@ -3,8 +3,9 @@ use wp_query_rs::*;
 // Must be in separate file so that the default init is not called
 #[cfg(feature = "query_async")]
 #[tokio::test]
-async fn init_with_my_pool() {
+async fn init_with_my_pool_async() {
     use std::env;
+    use wp_query_rs::r#async::{PoolInit, WpQuery};
 
     let host = env::var("WORDPRESS_DB_HOST").unwrap();
     let user = env::var("WORDPRESS_DB_USER").unwrap();
@@ -22,7 +23,7 @@ async fn init_with_my_pool() {
 
     let params = ParamBuilder::new();
 
-    let posts = WP_Query::new(params).await.expect("SqlFailed");
+    let posts = WpQuery::new(params).await.expect("SqlFailed");
 
     assert_eq!(posts.post_count(), 10);
 }
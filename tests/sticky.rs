@@ -0,0 +1,31 @@
+use wp_query_rs::*;
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn home_includes_sticky_posts() {
+    let params = ParamBuilder::new();
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    // Sticky posts missing from the first page are added on top of it
+    assert!(posts.post_count() >= 10);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_ignore_sticky_posts() {
+    let params = ParamBuilder::new().ignore_sticky_posts(true);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert_eq!(posts.post_count(), 10);
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_ignore_sticky_posts_async() {
+    use wp_query_rs::r#async::WpQuery;
+
+    let params = ParamBuilder::new().ignore_sticky_posts(true);
+
+    let posts = WpQuery::new(params).await.expect("SqlFailed");
+    assert_eq!(posts.post_count(), 10);
+}
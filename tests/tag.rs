@@ -14,7 +14,7 @@ fn tag_id() {
 fn tag() {
     let params = ParamBuilder::new().tag("tag-a");
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -23,7 +23,7 @@ fn tag_in() {
     let params = ParamBuilder::new().tag__in(1);
 
     let posts = WP_Query::new(params).expect("SqlFailed");
-    assert!(posts.posts.len() > 0);
+    assert!(!posts.posts.is_empty());
 }
 
 #[cfg(feature = "query_sync")]
@@ -32,7 +32,7 @@ fn tag_not_in() {
     let params = ParamBuilder::new().tag__not_in(1);
 
     let posts = WP_Query::new(params).expect("SqlFailed");
-    assert!(posts.posts.len() > 0);
+    assert!(!posts.posts.is_empty());
 }
 
 #[cfg(feature = "query_sync")]
@@ -40,7 +40,7 @@ fn tag_not_in() {
 fn tag_slug_and() {
     let params = ParamBuilder::new().tag_slug__and("tag-a");
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
 
 #[cfg(feature = "query_sync")]
@@ -48,5 +48,5 @@ fn tag_slug_and() {
 fn tag_slug_in() {
     let params = ParamBuilder::new().tag_slug__in("tag-a");
 
-    let posts = WP_Query::new(params).expect("SqlFailed");
+    let _posts = WP_Query::new(params).expect("SqlFailed");
 }
@@ -45,3 +45,49 @@ fn can_search_by_tax_field() {
     let posts = WP_Query::new(params).expect("SqlFailed");
     assert!(posts.post_count() == 0);
 }
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_search_without_children() {
+    let params = ParamBuilder::new().tax_query(
+        TaxQuery::new("category", vec!["uncategorized"])
+            .field(TaxField::Slug)
+            .include_children(false),
+        None,
+    );
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_search_with_children() {
+    let params = ParamBuilder::new().tax_query(
+        TaxQuery::new("category", vec!["uncategorized"])
+            .field(TaxField::Slug)
+            .include_children(true),
+        None,
+    );
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_search_nested() {
+    let tree = TaxQueryTree::group(TaxRelation::Or)
+        .push(
+            TaxQueryTree::group(TaxRelation::And)
+                .push(TaxQuery::new("category", vec!["uncategorized"]).field(TaxField::Slug))
+                .push(
+                    TaxQuery::new("post_format", vec![0]).operator(SqlConditionOperator::NotExists),
+                ),
+        )
+        .push(TaxQuery::new("post_tag", vec![0]).operator(SqlConditionOperator::Exists));
+    let params = ParamBuilder::new().tax_query_tree(tree);
+
+    let posts = WP_Query::new(params).expect("SqlFailed");
+    assert!(posts.post_count() > 0);
+}
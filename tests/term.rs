@@ -0,0 +1,42 @@
+use wp_query_rs::*;
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_get_terms() {
+    let args = WpTermArgBuilder::new()
+        .taxonomy("category")
+        .hide_empty(false);
+
+    let terms = get_terms(args).expect("SqlFailed");
+    assert!(!terms.is_empty());
+    assert!(terms.iter().all(|t| t.taxonomy == "category"));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_limit_terms() {
+    let args = WpTermArgBuilder::new().hide_empty(false).number(1);
+
+    let terms = get_terms(args).expect("SqlFailed");
+    assert_eq!(terms.len(), 1);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_get_post_terms() {
+    let terms = wp_get_post_terms(1, "category").expect("SqlFailed");
+    assert!(terms.iter().all(|t| t.taxonomy == "category"));
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_get_terms_async() {
+    use wp_query_rs::r#async::get_terms;
+
+    let args = WpTermArgBuilder::new()
+        .taxonomy("category")
+        .hide_empty(false);
+
+    let terms = get_terms(args).await.expect("SqlFailed");
+    assert!(terms.iter().all(|t| t.taxonomy == "category"));
+}
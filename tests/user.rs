@@ -1,5 +1,15 @@
+use std::time::{SystemTime, UNIX_EPOCH};
 use wp_query_rs::*;
 
+fn unique_login(name: &str) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    format!("{name}_{now}")
+}
+
 #[cfg(feature = "query_sync")]
 #[test]
 fn can_get_user_by_id() {
@@ -8,13 +18,184 @@ fn can_get_user_by_id() {
     assert_eq!(user.id, 1);
 }
 
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_get_user_by_login_email_and_slug() {
+    let user = WpUser::get_user_by_id(1).unwrap().unwrap();
+
+    let by_login = WpUser::get_user_by(UserField::Login(user.user_login()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_login.id, 1);
+
+    let by_email = WpUser::get_user_by(UserField::Email(user.user_email()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_email.id, 1);
+
+    let by_slug = WpUser::get_user_by(UserField::Slug(&user.user_nicename))
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_slug.id, 1);
+
+    assert!(WpUser::get_user_by(UserField::Login("no_such_user_login"))
+        .unwrap()
+        .is_none());
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn first_user_is_administrator() {
+    let user = WpUser::get_user_by_id(1).unwrap().unwrap();
+
+    let caps = user.capabilities().expect("CapabilitiesFailed");
+    assert!(caps.has_role("administrator"));
+    assert!(caps.has_cap("manage_options"));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_query_users_by_role() {
+    let mut args = WpUserArgBuilder::new();
+    args.role__in = Some(vec![String::from("administrator")]);
+
+    let users = WpUserQuery::new(args).expect("SqlFailed");
+
+    assert!(users.user_count() > 0);
+    assert!(users.total_users.unwrap() >= users.user_count() as u64);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_paginate_users() {
+    let mut args = WpUserArgBuilder::new();
+    args.number = Some(1);
+    args.include = Some(vec![1]);
+
+    let users = WpUserQuery::new(args).expect("SqlFailed");
+
+    assert_eq!(users.user_count(), 1);
+    assert_eq!(users.users[0].id, 1);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_read_and_write_user_meta() {
+    update_user_meta(1, "wp_query_rs_test_meta", vec![1, 2], None).expect("UpdateFailed");
+
+    let value: Option<Vec<u64>> = get_user_meta(1, "wp_query_rs_test_meta").expect("GetFailed");
+    assert_eq!(value, Some(vec![1, 2]));
+
+    assert!(delete_user_meta(1, "wp_query_rs_test_meta", None).expect("DeleteFailed"));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_set_and_check_password() {
+    let mut user = WpUser::get_user_by_id(1).unwrap().unwrap();
+
+    user.set_password("wp_query_rs_password")
+        .expect("SetPasswordFailed");
+    assert!(user.check_password("wp_query_rs_password"));
+
+    let user = WpUser::get_user_by_id(1).unwrap().unwrap();
+    assert!(user.check_password("wp_query_rs_password"));
+    assert!(!user.check_password("wrong_password"));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_insert_update_and_delete_user() {
+    let login = unique_login("wp_query_rs_user");
+    let mut user = WpUser::new(&login, &format!("{login}@example.com"));
+    user.set_user_pass("wp_query_rs_password");
+
+    user.id = user.clone().insert().expect("InsertFailed");
+
+    let mut inserted = WpUser::get_user_by_id(user.id).unwrap().unwrap();
+    assert_eq!(inserted.user_login(), login);
+    assert!(inserted.check_password("wp_query_rs_password"));
+
+    let duplicate = WpUser::new(&login, "wp_query_rs_other@example.com");
+    assert!(duplicate.insert().is_err());
+
+    inserted.display_name = String::from("Updated Name");
+    inserted.update().expect("UpdateFailed");
+    let updated = WpUser::get_user_by_id(user.id).unwrap().unwrap();
+    assert_eq!(updated.display_name, "Updated Name");
+
+    let mut post = WP_Post::new(user.id);
+    post.post_title = login.clone();
+    let post_id = post.insert().expect("PostInsertFailed");
+
+    updated.delete(Some(1)).expect("DeleteFailed");
+    assert!(WpUser::get_user_by_id(user.id).unwrap().is_none());
+
+    let query = WP_Query::new(ParamBuilder::new().p(post_id)).unwrap();
+    assert_eq!(query.posts[0].post_author, 1);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn cannot_update_to_email_of_other_user() {
+    let admin = WpUser::get_user_by_id(1).unwrap().unwrap();
+    let login = unique_login("wp_query_rs_user");
+    let mut user = WpUser::new(&login, &format!("{login}@example.com"));
+    user.id = user.clone().insert().expect("InsertFailed");
+
+    user.set_user_email(admin.user_email());
+    assert!(user.update().is_err());
+
+    user.delete(None).expect("DeleteFailed");
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_insert_and_delete_user_async() {
+    use wp_query_rs::r#async::{get_user_by_id, Insertable, WpUserExt};
+
+    let login = unique_login("wp_query_rs_async_user");
+    let mut user = WpUser::new(&login, &format!("{login}@example.com"));
+    user.set_user_pass("wp_query_rs_password");
+
+    let user_id = Insertable::insert(user).await.expect("InsertFailed");
+
+    let user = get_user_by_id(user_id).await.unwrap().unwrap();
+    assert!(user.check_password("wp_query_rs_password"));
+
+    WpUserExt::delete(user, Some(1))
+        .await
+        .expect("DeleteFailed");
+    assert!(get_user_by_id(user_id).await.unwrap().is_none());
+}
+
 #[cfg(feature = "query_async")]
 #[test]
-fn can_get_user_by_id() {
+fn can_get_user_by_id_async() {
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
-        let user = WpUser::get_user_by_id(1).await.unwrap().unwrap();
+        let user = wp_query_rs::r#async::get_user_by_id(1)
+            .await
+            .unwrap()
+            .unwrap();
 
         assert_eq!(user.id, 1);
     });
 }
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_query_users_async() {
+    use wp_query_rs::r#async::{WpUserExt, WpUserQuery};
+
+    let mut args = WpUserArgBuilder::new();
+    args.include = Some(vec![1]);
+
+    let users = WpUserQuery::new(args).await.expect("SqlFailed");
+    assert_eq!(users.user_count(), 1);
+
+    let caps = WpUserExt::capabilities(&users.users[0])
+        .await
+        .expect("CapabilitiesFailed");
+    assert!(caps.has_role("administrator"));
+}
@@ -22,7 +22,9 @@ fn can_insert_post() {
 
 #[cfg(feature = "query_async")]
 #[tokio::test]
-async fn can_insert_post() {
+async fn can_insert_post_async() {
+    use wp_query_rs::r#async::{Insertable, WpQuery};
+
     let mut post = WP_Post::new(1);
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -31,10 +33,10 @@ async fn can_insert_post() {
     let title = format!("My Test Post {}", now);
     post.post_title = title.clone();
 
-    post.insert().await.expect("InsertFailed");
+    Insertable::insert(post).await.expect("InsertFailed");
 
     let q = ParamBuilder::new().s(&title);
-    let query = WP_Query::new(q).await.unwrap();
+    let query = WpQuery::new(q).await.unwrap();
 
     assert!(query.post_count() > 0);
 }
@@ -80,7 +82,9 @@ fn can_insert_multiple_posts() {
 
 #[cfg(feature = "query_async")]
 #[tokio::test]
-async fn can_insert_multiple_posts() {
+async fn can_insert_multiple_posts_async() {
+    use wp_query_rs::r#async::{Insertable, WpQuery};
+
     let dirty_posts: Vec<WP_Post> = (0..10)
         .map(|_| {
             let mut post = WP_Post::new(1);
@@ -94,10 +98,12 @@ async fn can_insert_multiple_posts() {
         })
         .collect();
 
-    WP_Post::batch(dirty_posts).await.expect("BulkInsertFailed");
+    <WP_Post as Insertable>::batch(dirty_posts)
+        .await
+        .expect("BulkInsertFailed");
 
     let q = ParamBuilder::new().s("My Bulk Posts");
-    let query = WP_Query::new(q).await.unwrap();
+    let query = WpQuery::new(q).await.unwrap();
 
     assert!(query.post_count() == 10);
 }
@@ -37,7 +37,7 @@ fn can_get_post_meta() {
 
     add_post_meta(post_id, "my_custom_rs_meta", 42).expect("MetaInsertFailed");
 
-    let meta = get_post_meta(post_id, "my_custom_rs_meta", true);
+    let meta = get_post_meta(post_id, "my_custom_rs_meta", true).expect("MetaQueryFailed");
 
     match meta {
         WpMetaResults::Single(meta) => {
@@ -58,7 +58,7 @@ fn can_bulk_insert_meta() {
     )
     .expect("insertError");
 
-    let meta = get_post_meta(post_id, "my_custom_rs_bulk_meta", false);
+    let meta = get_post_meta(post_id, "my_custom_rs_bulk_meta", false).expect("MetaQueryFailed");
 
     match meta {
         WpMetaResults::Array(meta) => {
@@ -76,7 +76,7 @@ fn can_insert_post_meta() {
     let pm = WpMeta::new(post_id, "my_inserted_meta", 42);
     pm.insert().expect("InsertFailed");
 
-    let meta = get_post_meta(post_id, "my_inserted_meta", true);
+    let meta = get_post_meta(post_id, "my_inserted_meta", true).expect("MetaQueryFailed");
 
     match meta {
         WpMetaResults::Single(meta) => {
@@ -85,3 +85,110 @@ fn can_insert_post_meta() {
         _ => panic!("MetaQueryFailed"),
     }
 }
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_update_post_meta() {
+    let post_id = add_post();
+
+    // Adds the meta when missing
+    assert!(update_post_meta(post_id, "my_updated_meta", 1, None).expect("UpdateFailed"));
+    // Nothing changes when the value is the same
+    assert!(!update_post_meta(post_id, "my_updated_meta", 1, None).expect("UpdateFailed"));
+    // Only updates the matching previous value
+    assert!(!update_post_meta(post_id, "my_updated_meta", 3, Some("2")).expect("UpdateFailed"));
+    assert!(update_post_meta(post_id, "my_updated_meta", 3, Some("1")).expect("UpdateFailed"));
+
+    match get_post_meta(post_id, "my_updated_meta", true).expect("MetaQueryFailed") {
+        WpMetaResults::Single(meta) => assert_eq!(meta.meta_value, "3"),
+        _ => panic!("MetaQueryFailed"),
+    }
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_delete_post_meta() {
+    let post_id = add_post();
+
+    WpMeta::add_post_meta_bulk(
+        post_id,
+        &[
+            ("my_deleted_meta", 1),
+            ("my_deleted_meta", 2),
+            ("my_deleted_meta", 3),
+        ],
+    )
+    .expect("insertError");
+
+    assert!(delete_post_meta(post_id, "my_deleted_meta", Some("1")).expect("DeleteFailed"));
+    match get_post_meta(post_id, "my_deleted_meta", false).expect("MetaQueryFailed") {
+        WpMetaResults::Array(meta) => assert_eq!(meta.len(), 2),
+        _ => panic!("MetaQueryFailed"),
+    }
+
+    assert!(delete_post_meta(post_id, "my_deleted_meta", None).expect("DeleteFailed"));
+    assert!(!delete_post_meta(post_id, "my_deleted_meta", None).expect("DeleteFailed"));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_get_post_custom() {
+    let post_id = add_post();
+
+    WpMeta::add_post_meta_bulk(
+        post_id,
+        &[
+            ("my_custom_color", "red"),
+            ("my_custom_color", "blue"),
+            ("my_custom_size", "large"),
+        ],
+    )
+    .expect("insertError");
+
+    let custom = get_post_custom(post_id).expect("MetaQueryFailed");
+
+    assert_eq!(custom["my_custom_color"], vec!["red", "blue"]);
+    assert_eq!(custom["my_custom_size"], vec!["large"]);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_read_meta_from_query_cache() {
+    let post_id = add_post();
+
+    WpMeta::add_post_meta_bulk(post_id, &[("my_cached_meta", 1), ("my_cached_meta", 2)])
+        .expect("insertError");
+
+    let params = ParamBuilder::new().p(post_id).post_status(PostStatus::Any);
+    let query = WP_Query::new(params).expect("SqlFailed");
+
+    assert_eq!(query.meta(post_id, "my_cached_meta"), Some("1"));
+    assert_eq!(query.meta_values(post_id, "my_cached_meta"), ["1", "2"]);
+    assert_eq!(query.meta(post_id, "my_missing_meta"), None);
+
+    let params = ParamBuilder::new()
+        .p(post_id)
+        .post_status(PostStatus::Any)
+        .update_post_meta_cache(false);
+    let query = WP_Query::new(params).expect("SqlFailed");
+
+    assert_eq!(query.meta(post_id, "my_cached_meta"), None);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_write_and_read_serialized_meta() {
+    let post_id = add_post();
+
+    add_post_meta(post_id, "my_serialized_meta", vec!["red", "blue"]).expect("MetaInsertFailed");
+
+    match get_post_meta(post_id, "my_serialized_meta", true).expect("MetaQueryFailed") {
+        WpMetaResults::Single(meta) => {
+            assert_eq!(meta.meta_value, "a:2:{i:0;s:3:\"red\";i:1;s:4:\"blue\";}");
+
+            let colors: Vec<String> = meta.value_as().expect("DecodeFailed");
+            assert_eq!(colors, vec!["red", "blue"]);
+        }
+        _ => panic!("MetaQueryFailed"),
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use wp_query_rs::*;
+
+fn new_post(title: &str) -> WP_Post {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let mut post = WP_Post::new(1);
+    post.post_title = format!("{} {}", title, now);
+    post.post_status = PostStatus::Publish;
+
+    post
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_update_post() {
+    let mut post = new_post("My Post To Update");
+    post.ID = post.clone().insert().expect("InsertFailed");
+
+    post.post_content = String::from("Updated content");
+    post.update(&[PostField::Content]).expect("UpdateFailed");
+
+    let q = ParamBuilder::new().p(post.ID);
+    let query = WP_Query::new(q).unwrap();
+    assert_eq!(query.posts[0].post_content, "Updated content");
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_trash_and_untrash_post() {
+    let mut post = new_post("My Post To Trash");
+    post.ID = post.clone().insert().expect("InsertFailed");
+
+    post.trash().expect("TrashFailed");
+    assert_eq!(post.post_status, PostStatus::Trash);
+
+    match get_post_meta(post.ID, "_wp_trash_meta_status", true).expect("MetaQueryFailed") {
+        wp_post::WpMetaResults::Single(meta) => assert_eq!(meta.meta_value, "publish"),
+        _ => panic!("Trash status not saved"),
+    }
+
+    post.untrash().expect("UntrashFailed");
+    assert_eq!(post.post_status, PostStatus::Publish);
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn trash_saves_status_of_row_not_struct() {
+    let mut post = new_post("My Stale Post To Trash");
+    post.ID = post.clone().insert().expect("InsertFailed");
+
+    let mut stale = post.clone();
+    post.post_status = PostStatus::Private;
+    post.update(&[PostField::Status]).expect("UpdateFailed");
+
+    stale.trash().expect("TrashFailed");
+    stale.untrash().expect("UntrashFailed");
+    assert_eq!(stale.post_status, PostStatus::Private);
+    assert!(matches!(
+        get_post_meta(post.ID, "_wp_trash_meta_status", true).expect("MetaQueryFailed"),
+        wp_post::WpMetaResults::Empty
+    ));
+}
+
+#[cfg(feature = "query_sync")]
+#[test]
+fn can_delete_post() {
+    let mut post = new_post("My Post To Delete");
+    post.ID = post.clone().insert().expect("InsertFailed");
+    let post_id = post.ID;
+    add_post_meta(post_id, "my_deleted_meta", "value").expect("MetaInsertFailed");
+
+    post.delete().expect("DeleteFailed");
+
+    let q = ParamBuilder::new().p(post_id).post_status(PostStatus::Any);
+    let query = WP_Query::new(q).unwrap();
+    assert_eq!(query.post_count(), 0);
+    assert!(matches!(
+        get_post_meta(post_id, "my_deleted_meta", true).expect("MetaQueryFailed"),
+        wp_post::WpMetaResults::Empty
+    ));
+}
+
+#[cfg(feature = "query_async")]
+#[tokio::test]
+async fn can_update_trash_and_delete_post() {
+    use wp_query_rs::r#async::{Insertable, WpPostExt, WpQuery};
+
+    let mut post = new_post("My Async Post To Update");
+    post.ID = Insertable::insert(post.clone())
+        .await
+        .expect("InsertFailed");
+
+    post.post_content = String::from("Updated content");
+    WpPostExt::update(&mut post, &[PostField::Content])
+        .await.expect("UpdateFailed");
+
+    WpPostExt::trash(&mut post).await.expect("TrashFailed");
+    WpPostExt::untrash(&mut post).await.expect("UntrashFailed");
+    assert_eq!(post.post_status, PostStatus::Publish);
+
+    let post_id = post.ID;
+    WpPostExt::delete(post).await.expect("DeleteFailed");
+
+    let q = ParamBuilder::new().p(post_id).post_status(PostStatus::Any);
+    let query = WpQuery::new(q).await.unwrap();
+    assert_eq!(query.post_count(), 0);
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "query_sync")]
 use wp_query_rs::wp_rewrite::parse_request;
 use wp_query_rs::*;
 
@@ -28,19 +29,23 @@ fn can_parse_url() {
 
 #[cfg(feature = "query_async")]
 #[test]
-fn can_retrieve_options() {
+fn can_retrieve_options_async() {
+    use wp_query_rs::r#async::WpRewriteExt;
+
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
         let rewrite = wp_rewrite::WpRewrite::new();
 
-        let rewrite_rules = rewrite.wp_rewrite_rules().await.unwrap();
+        let rewrite_rules = WpRewriteExt::wp_rewrite_rules(&rewrite).await.unwrap();
         assert!(rewrite_rules.is_some());
     });
 }
 
 #[cfg(feature = "query_async")]
 #[test]
-fn can_parse_url() {
+fn can_parse_url_async() {
+    use wp_query_rs::r#async::parse_request;
+
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
         let rewrite = wp_rewrite::WpRewrite::new();
@@ -57,3 +62,38 @@ fn can_parse_url() {
         assert_eq!(params.name, Some("a-page-about-tomates"));
     });
 }
+
+#[cfg(all(feature = "query_sync", feature = "rewrite"))]
+#[test]
+fn can_get_permalinks() {
+    let options = WpOptions::load().unwrap();
+    let home = options.home().unwrap().trim_end_matches('/').to_string();
+
+    let query = WP_Query::new(ParamBuilder::new().p(1)).unwrap();
+    let link = wp_rewrite::get_permalink(&query.posts[0]).unwrap();
+    assert!(link.starts_with(&home));
+
+    let user = WpUser::get_user_by_id(1).unwrap().unwrap();
+    let author_link = wp_rewrite::get_author_posts_url(&user).unwrap();
+    assert!(author_link.starts_with(&home));
+
+    let categories = get_terms(
+        WpTermArgBuilder::new()
+            .taxonomy("category")
+            .hide_empty(false),
+    )
+    .unwrap();
+    let term_link = wp_rewrite::get_term_link(&categories[0]).unwrap();
+    assert!(term_link.starts_with(&home));
+}
+
+#[cfg(all(feature = "query_async", feature = "rewrite"))]
+#[tokio::test]
+async fn can_get_permalinks_async() {
+    use wp_query_rs::r#async::{get_permalink, WpQuery};
+
+    let query = WpQuery::new(ParamBuilder::new().p(1)).await.unwrap();
+
+    let link = get_permalink(&query.posts[0]).await.unwrap();
+    assert!(link.starts_with("http"));
+}